@@ -0,0 +1,41 @@
+/* C declarations of the `ffi` feature of tempref. */
+#ifndef TEMPREF_H
+#define TEMPREF_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A reference-counted buffer of zeroed bytes, shared with Rust. */
+typedef struct tempref_temp tempref_temp;
+/* A lock on a buffer, which zeroes the bytes when it is unlocked. */
+typedef struct tempref_guard tempref_guard;
+
+/* Creates a buffer of `len` zeroed bytes, and returns the only reference to it. */
+const tempref_temp *tempref_new(size_t len);
+/* Adds a reference to the buffer, returning `temp` again. */
+const tempref_temp *tempref_retain(const tempref_temp *temp);
+/* Gives back a reference to the buffer, which is freed with the last one. */
+void tempref_release(const tempref_temp *temp);
+/* Returns how many times the buffer has been zeroed. */
+size_t tempref_generation(const tempref_temp *temp);
+
+/* Locks the buffer, blocking until it is free. */
+tempref_guard *tempref_lock(const tempref_temp *temp);
+/* Locks the buffer if it is free, or returns NULL. */
+tempref_guard *tempref_try_lock(const tempref_temp *temp);
+/* Returns the bytes of the buffer, valid until the guard is unlocked. */
+uint8_t *tempref_guard_data(tempref_guard *guard);
+/* Returns the number of bytes of the buffer. */
+size_t tempref_guard_len(const tempref_guard *guard);
+/* Zeroes the bytes and unlocks the buffer, on the thread which locked it. */
+void tempref_unlock_reset(tempref_guard *guard);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* TEMPREF_H */
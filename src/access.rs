@@ -0,0 +1,126 @@
+//! A trait for code which borrows a `Temp` without depending on its module.
+
+use core::ops::DerefMut;
+
+use crate::Error;
+#[cfg(feature = "mutex")]
+use crate::mutex;
+#[cfg(feature = "rwlock")]
+use crate::rwlock;
+#[cfg(feature = "unsync")]
+use crate::unsync;
+
+/// Exclusive access to a `Temp` through a guard which resets the value when dropped.
+///
+/// It is implemented for the `Temp` of `unsync`, `mutex` and `rwlock`, and for `testing::MockTemp` with the `testing`
+/// feature, so that code which depends on a workspace can be written once and tested deterministically.
+///
+/// # Examples
+/// ```
+/// use tempref::{Error, TempAccess, mutex, unsync};
+///
+/// fn checksum<W: TempAccess<Value = Vec<u8>>>(workspace: &W, data: &[u8]) -> Result<u32, Error> {
+///     let mut buf = workspace.access()?;
+///     buf.extend_from_slice(data);
+///     Ok(buf.iter().map(|&b| b as u32).sum())
+/// }
+///
+/// let a = unsync::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+/// let b = mutex::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+/// assert_eq!(checksum(&a, &[1, 2]), Ok(3));
+/// assert_eq!(checksum(&b, &[1, 2]), Ok(3));
+/// assert!(b.lock().unwrap().is_empty());
+/// ```
+pub trait TempAccess {
+    /// The type of the value.
+    type Value;
+    /// The guard which resets the value when dropped.
+    type Guard<'a>: DerefMut<Target = Self::Value>
+    where
+        Self: 'a;
+
+    /// Returns a guard, blocking the current thread until the lock is acquired.
+    ///
+    /// `unsync::Temp` cannot block, so it returns [`Error::Borrow`] if the value is already borrowed.
+    fn access(&self) -> Result<Self::Guard<'_>, Error>;
+    /// Returns a guard, or an error if it cannot be acquired without blocking.
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error>;
+    /// Invokes the reset function on the value, blocking like [`Self::access`].
+    fn reset(&self) -> Result<(), Error>;
+}
+
+#[cfg(feature = "unsync")]
+impl<T, F: FnMut(&mut T)> TempAccess for unsync::Temp<T, F> {
+    type Value = T;
+    type Guard<'a>
+        = unsync::TempRef<'a, T, F>
+    where
+        Self: 'a;
+
+    fn access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.try_borrow_mut()?)
+    }
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.try_borrow_mut()?)
+    }
+    fn reset(&self) -> Result<(), Error> {
+        Ok(self.try_reset()?)
+    }
+}
+
+#[cfg(feature = "mutex")]
+impl<T: Send, F: FnMut(&mut T) + Send> TempAccess for mutex::Temp<T, F> {
+    type Value = T;
+    type Guard<'a>
+        = mutex::TempRef<'a, T, F>
+    where
+        Self: 'a;
+
+    fn access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.lock()?)
+    }
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.try_lock()?)
+    }
+    fn reset(&self) -> Result<(), Error> {
+        Ok(mutex::Temp::reset(self)?)
+    }
+}
+
+#[cfg(feature = "mutex")]
+impl<T: Send, F: FnMut(&mut T) + Send> TempAccess for mutex::SharedTemp<T, F> {
+    type Value = T;
+    type Guard<'a>
+        = mutex::TempRef<'a, T, F>
+    where
+        Self: 'a;
+
+    fn access(&self) -> Result<Self::Guard<'_>, Error> {
+        (**self).access()
+    }
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error> {
+        (**self).try_access()
+    }
+    fn reset(&self) -> Result<(), Error> {
+        TempAccess::reset(&**self)
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<T: Send, F: FnMut(&mut T) + Send> TempAccess for rwlock::Temp<T, F> {
+    type Value = T;
+    type Guard<'a>
+        = rwlock::TempRef<'a, T, F>
+    where
+        Self: 'a;
+
+    fn access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.write()?)
+    }
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error> {
+        Ok(self.try_write()?)
+    }
+    fn reset(&self) -> Result<(), Error> {
+        Ok(rwlock::Temp::reset(self)?)
+    }
+}
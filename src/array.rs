@@ -0,0 +1,139 @@
+//! Fixed-size array version of TempRef for `Copy` elements. This module doesn't require std.
+//!
+//! [`TempArray`] is filled with a single reset value, which it writes back to every element when a guard is dropped.
+//! It needs no allocation, and its constructor is `const`, so it can be a `static` scratch table.
+//! Guards are handed out one at a time by spinning.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    hint,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::Error;
+
+/// A mutable reference from [`TempArray<T, N>`].
+/// When it is dropped, every element is set back to the reset value.
+pub struct TempRef<'a, T: Copy, const N: usize> {
+    temp: &'a TempArray<T, N>,
+}
+impl<'a, T: Copy, const N: usize> TempRef<'a, T, N> {
+    /// Sets every element back to the reset value now.
+    pub fn reset(&mut self) {
+        **self = [self.temp.reset; N];
+    }
+}
+impl<'a, T: Copy, const N: usize> core::ops::Deref for TempRef<'a, T, N> {
+    type Target = [T; N];
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The flag is set, so this guard is the only one accessing the array.
+        unsafe { &*self.temp.value.get() }
+    }
+}
+impl<'a, T: Copy, const N: usize> core::ops::DerefMut for TempRef<'a, T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As in `deref`.
+        unsafe { &mut *self.temp.value.get() }
+    }
+}
+impl<'a, T: Copy + Debug, const N: usize> Debug for TempRef<'a, T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("value", &**self).finish()
+    }
+}
+impl<'a, T: Copy, const N: usize> Drop for TempRef<'a, T, N> {
+    fn drop(&mut self) {
+        // Writing a `Copy` value cannot panic, so the flag is always cleared.
+        self.reset();
+        self.temp.generation.fetch_add(1, Ordering::Relaxed);
+        self.temp.locked.store(false, Ordering::Release);
+    }
+}
+
+/// An array of `N` `Copy` elements which are all set back to one reset value when a guard is dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::array::TempArray;
+///
+/// static SCRATCH: TempArray<f32, 256> = TempArray::new(0.0);
+///
+/// fn convolve(input: &[f32]) -> f32 {
+///     let mut scratch = SCRATCH.lock();
+///     for (i, x) in input.iter().enumerate() {
+///         scratch[i % 256] += x;
+///     }
+///     scratch.iter().sum()
+/// } // Every element is 0.0 again here.
+///
+/// assert_eq!(convolve(&[1.0, 2.0]), 3.0);
+/// assert_eq!(convolve(&[1.0]), 1.0);
+/// ```
+pub struct TempArray<T: Copy, const N: usize> {
+    locked: AtomicBool,
+    generation: AtomicUsize,
+    value: UnsafeCell<[T; N]>,
+    reset: T,
+}
+// SAFETY: The array is only accessed by the one guard which set the flag.
+unsafe impl<T: Copy + Send, const N: usize> Sync for TempArray<T, N> {}
+impl<T: Copy, const N: usize> TempArray<T, N> {
+    /// A constructor of TempArray<T, N>, whose elements all start as `reset`.
+    pub const fn new(reset: T) -> Self {
+        TempArray {
+            locked: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            value: UnsafeCell::new([reset; N]),
+            reset,
+        }
+    }
+    /// Returns a [`TempRef`], spinning while another guard is alive.
+    ///
+    /// This never returns while the current thread holds a [`TempRef`] of the same `TempArray`.
+    pub fn lock(&self) -> TempRef<'_, T, N> {
+        loop {
+            match self.try_lock() {
+                Ok(guard) => return guard,
+                Err(_) => hint::spin_loop(),
+            }
+        }
+    }
+    /// Returns a [`TempRef`], or [`Error::WouldBlock`] if another guard is alive.
+    pub fn try_lock(&self) -> Result<TempRef<'_, T, N>, Error> {
+        match self
+            .locked
+            .compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed)
+        {
+            Ok(_) => Ok(TempRef { temp: self }),
+            Err(_) => Err(Error::WouldBlock),
+        }
+    }
+    /// Returns the reset value.
+    pub const fn reset_value(&self) -> T {
+        self.reset
+    }
+    /// Returns how many guards have been dropped, each of which reset the array.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+    /// Returns a mutable reference to the array.
+    ///
+    /// Since this call borrows the `TempArray` mutably, no guard can be alive and the array is not reset.
+    pub fn get_mut(&mut self) -> &mut [T; N] {
+        self.value.get_mut()
+    }
+    /// Consumes the TempArray, returning the array.
+    pub fn into_inner(self) -> [T; N] {
+        self.value.into_inner()
+    }
+}
+impl<T: Copy + Debug, const N: usize> Debug for TempArray<T, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        // The elements are only readable through a guard, so only the reset value is shown.
+        f.debug_struct("TempArray")
+            .field("len", &N)
+            .field("reset", &self.reset)
+            .finish()
+    }
+}
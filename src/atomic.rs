@@ -0,0 +1,149 @@
+//! Lock-free version of TempRef for integers and `bool`. This module doesn't require std.
+//!
+//! Each `Temp*` type dereferences to its atomic, so `load`, `store` and the `fetch_*` operations are used as usual,
+//! and [`AtomicTemp::reset`] stores the reset value with the given ordering.
+//! Nothing in this module blocks, so it can be used from signal handlers and other hot paths.
+//!
+//! # Examples
+//! ```
+//! use core::sync::atomic::{AtomicUsize, Ordering};
+//! use tempref::atomic::{AtomicTemp, TempU32};
+//!
+//! static RETRIES: TempU32 = TempU32::new_with(0);
+//! {
+//!     let retries = RETRIES.scope(Ordering::Release);
+//!     retries.fetch_add(2, Ordering::Relaxed);
+//!     assert_eq!(RETRIES.load(Ordering::Relaxed), 2);
+//! } // 0 is stored here.
+//! assert_eq!(RETRIES.load(Ordering::Acquire), 0);
+//! ```
+
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// The operations shared by every `Temp*` type of this module.
+pub trait AtomicTemp: sealed::Sealed {
+    /// Stores the reset value with the given ordering.
+    fn reset(&self, order: Ordering);
+    /// Returns how many times the reset value has been stored.
+    fn generation(&self) -> usize;
+    /// Returns a [`TempRef`] which resets the value with the given ordering when dropped.
+    fn scope(&self, order: Ordering) -> TempRef<'_, Self> {
+        TempRef { temp: self, order }
+    }
+}
+
+/// A scope of an [`AtomicTemp`] which resets it when dropped.
+pub struct TempRef<'a, A: AtomicTemp + ?Sized> {
+    temp: &'a A,
+    order: Ordering,
+}
+impl<'a, A: AtomicTemp + ?Sized> core::ops::Deref for TempRef<'a, A> {
+    type Target = A;
+    fn deref(&self) -> &Self::Target {
+        self.temp
+    }
+}
+impl<'a, A: AtomicTemp + ?Sized> Drop for TempRef<'a, A> {
+    fn drop(&mut self) {
+        self.temp.reset(self.order);
+    }
+}
+
+macro_rules! atomic_temp {
+    ($(#[cfg($cfg:meta)] $name:ident($atomic:ident, $t:ty);)*) => {$(
+        #[doc = concat!("A [`", stringify!($atomic), "`](core::sync::atomic::", stringify!($atomic), ") which snaps back to a stored reset value.")]
+        ///
+        /// It dereferences to the atomic, and resets through [`AtomicTemp`].
+        #[cfg($cfg)]
+        pub struct $name {
+            value: core::sync::atomic::$atomic,
+            reset: $t,
+            generation: AtomicUsize,
+        }
+        #[cfg($cfg)]
+        impl $name {
+            #[doc = concat!("A constructor of ", stringify!($name), ".")]
+            pub const fn new(value: $t, reset: $t) -> Self {
+                $name {
+                    value: core::sync::atomic::$atomic::new(value),
+                    reset,
+                    generation: AtomicUsize::new(0),
+                }
+            }
+            #[doc = concat!("A constructor of ", stringify!($name), " whose value starts at the reset value.")]
+            pub const fn new_with(reset: $t) -> Self {
+                $name::new(reset, reset)
+            }
+            /// Returns the reset value.
+            pub fn reset_value(&self) -> $t {
+                self.reset
+            }
+            /// Replaces the reset value. The current value is not changed.
+            pub fn set_reset_value(&mut self, reset: $t) {
+                self.reset = reset;
+            }
+            /// Returns a mutable reference to the value.
+            pub fn get_mut(&mut self) -> &mut $t {
+                self.value.get_mut()
+            }
+            /// Consumes the Temp, returning the value.
+            pub fn into_inner(self) -> $t {
+                self.value.into_inner()
+            }
+        }
+        #[cfg($cfg)]
+        impl sealed::Sealed for $name {}
+        #[cfg($cfg)]
+        impl AtomicTemp for $name {
+            fn reset(&self, order: Ordering) {
+                self.value.store(self.reset, order);
+                self.generation.fetch_add(1, Ordering::Release);
+            }
+            fn generation(&self) -> usize {
+                self.generation.load(Ordering::Acquire)
+            }
+        }
+        #[cfg($cfg)]
+        impl core::ops::Deref for $name {
+            type Target = core::sync::atomic::$atomic;
+            fn deref(&self) -> &Self::Target {
+                &self.value
+            }
+        }
+        #[cfg($cfg)]
+        impl Debug for $name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("value", &self.value.load(Ordering::Relaxed))
+                    .field("reset", &self.reset)
+                    .finish()
+            }
+        }
+        /// Creates a Temp whose value and reset value are the default value.
+        #[cfg($cfg)]
+        impl Default for $name {
+            fn default() -> Self {
+                $name::new_with(<$t>::default())
+            }
+        }
+    )*};
+}
+
+atomic_temp! {
+    #[cfg(target_has_atomic = "8")] TempBool(AtomicBool, bool);
+    #[cfg(target_has_atomic = "8")] TempU8(AtomicU8, u8);
+    #[cfg(target_has_atomic = "8")] TempI8(AtomicI8, i8);
+    #[cfg(target_has_atomic = "16")] TempU16(AtomicU16, u16);
+    #[cfg(target_has_atomic = "16")] TempI16(AtomicI16, i16);
+    #[cfg(target_has_atomic = "32")] TempU32(AtomicU32, u32);
+    #[cfg(target_has_atomic = "32")] TempI32(AtomicI32, i32);
+    #[cfg(target_has_atomic = "64")] TempU64(AtomicU64, u64);
+    #[cfg(target_has_atomic = "64")] TempI64(AtomicI64, i64);
+    #[cfg(target_has_atomic = "ptr")] TempUsize(AtomicUsize, usize);
+    #[cfg(target_has_atomic = "ptr")] TempIsize(AtomicIsize, isize);
+}
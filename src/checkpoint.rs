@@ -0,0 +1,186 @@
+//! Single thread version of TempRef which resets to the latest of a stack of checkpoints. This module requires alloc.
+//!
+//! The value is cloned into a checkpoint by [`Temp::checkpoint`], and every [`TempRef`] restores the latest checkpoint
+//! when dropped. Checkpoints are numbered by their depth, the initial value being checkpoint 0, so nested code can
+//! roll back to the one it took with [`Temp::rollback_to`].
+
+use alloc::vec::Vec;
+use core::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    fmt::{Debug, Display},
+};
+
+/// A mutable reference from [`Temp<T>`].
+/// When it is dropped, the value is restored to the latest checkpoint.
+pub struct TempRef<'a, T: Clone> {
+    re: RefMut<'a, T>,
+    temp: &'a Temp<T>,
+}
+impl<'a, T: Clone> TempRef<'a, T> {
+    /// Restores the value to the latest checkpoint.
+    pub fn reset(&mut self) {
+        self.temp.restore(&mut self.re);
+    }
+}
+impl<'a, T: Clone> core::ops::Deref for TempRef<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T: Clone> core::ops::DerefMut for TempRef<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T: Clone + Debug> Debug for TempRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("value", &*self.re).finish()
+    }
+}
+impl<'a, T: Clone + Display> Display for TempRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&*self.re, f)
+    }
+}
+impl<'a, T: Clone> Drop for TempRef<'a, T> {
+    fn drop(&mut self) {
+        self.temp.restore(&mut self.re);
+    }
+}
+
+/// A value whose mutable references restore it to the latest checkpoint when dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::checkpoint::Temp;
+///
+/// let doc = Temp::new(vec!["title"]);
+/// doc.borrow_mut().push("draft"); // Rolled back to checkpoint 0 when dropped.
+/// assert_eq!(*doc.borrow(), ["title"]);
+///
+/// doc.with_value(|v| v.push("intro"));
+/// let intro = doc.checkpoint();
+/// doc.with_value(|v| v.push("body"));
+/// doc.checkpoint();
+/// doc.borrow_mut().push("typo"); // Rolled back to checkpoint 2.
+/// assert_eq!(*doc.borrow(), ["title", "intro", "body"]);
+///
+/// doc.rollback_to(intro);
+/// assert_eq!(*doc.borrow(), ["title", "intro"]);
+/// doc.rollback();
+/// assert_eq!(*doc.borrow(), ["title"]);
+/// ```
+pub struct Temp<T: Clone> {
+    value: RefCell<T>,
+    // Never empty. The first one is the initial value.
+    checkpoints: RefCell<Vec<T>>,
+    generation: Cell<usize>,
+}
+impl<T: Clone> Temp<T> {
+    /// A constructor of `Temp<T>`, whose value is checkpoint 0.
+    pub fn new(value: T) -> Self {
+        Temp {
+            checkpoints: RefCell::new(alloc::vec![value.clone()]),
+            value: RefCell::new(value),
+            generation: Cell::new(0),
+        }
+    }
+    /// Immutably borrows the value.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+    /// Mutably borrows the value. The returned [`TempRef`] restores the latest checkpoint when dropped.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> TempRef<'_, T> {
+        TempRef {
+            re: self.value.borrow_mut(),
+            temp: self,
+        }
+    }
+    /// Changes the value with `f` without restoring it, so that the change can be saved by [`Self::checkpoint`].
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn with_value<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.value.borrow_mut())
+    }
+    /// Saves a clone of the value as the latest checkpoint, and returns its number.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    #[track_caller]
+    pub fn checkpoint(&self) -> usize {
+        let mut checkpoints = self.checkpoints.borrow_mut();
+        checkpoints.push(self.value.borrow().clone());
+        checkpoints.len() - 1
+    }
+    /// Returns the number of the latest checkpoint.
+    pub fn latest(&self) -> usize {
+        self.checkpoints.borrow().len() - 1
+    }
+    /// Discards the latest checkpoint and restores the value to the one before it.
+    /// Checkpoint 0 is never discarded, so the value is restored to it at the bottom of the stack.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn rollback(&self) {
+        let latest = self.latest();
+        self.rollback_to(latest.saturating_sub(1));
+    }
+    /// Discards every checkpoint after `checkpoint` and restores the value to it.
+    ///
+    /// # Panics
+    /// Panics if `checkpoint` is greater than [`Self::latest`], or if the value is currently borrowed.
+    #[track_caller]
+    pub fn rollback_to(&self, checkpoint: usize) {
+        let mut value = self.value.borrow_mut();
+        let mut checkpoints = self.checkpoints.borrow_mut();
+        assert!(
+            checkpoint < checkpoints.len(),
+            "checkpoint {checkpoint} does not exist"
+        );
+        checkpoints.truncate(checkpoint + 1);
+        drop(checkpoints);
+        self.restore(&mut value);
+    }
+    /// Returns how many times the value has been restored to a checkpoint.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be alive and the value is not restored.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn restore(&self, value: &mut T) {
+        value.clone_from(self.checkpoints.borrow().last().unwrap());
+        self.generation.set(self.generation.get() + 1);
+    }
+}
+impl<T: Clone + Debug> Debug for Temp<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("Temp");
+        match self.value.try_borrow() {
+            Ok(value) => d.field("value", &*value),
+            Err(_) => d.field("value", &format_args!("<borrowed>")),
+        };
+        d.field("checkpoints", &self.checkpoints.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,39 @@
+//! The collections which `Temp::clearing()` accepts.
+
+/// A collection which can be emptied in place, keeping its capacity.
+///
+/// It is implemented for the `Vec`, `String` and `VecDeque` of `alloc` with the `alloc` feature,
+/// and for the `Vec`, `String`, `Deque`, `IndexMap`, `IndexSet` and `LinearMap` of `heapless` with the `heapless` feature.
+pub trait Clear {
+    /// Removes every element, keeping the capacity.
+    fn clear(&mut self);
+}
+
+macro_rules! impl_clear {
+    ($([$($generics:tt)*] $ty:ty;)*) => {
+        $(
+            impl<$($generics)*> Clear for $ty {
+                fn clear(&mut self) {
+                    <$ty>::clear(self);
+                }
+            }
+        )*
+    };
+}
+
+#[cfg(feature = "alloc")]
+impl_clear! {
+    [T] alloc::vec::Vec<T>;
+    [] alloc::string::String;
+    [T] alloc::collections::VecDeque<T>;
+}
+
+#[cfg(feature = "heapless")]
+impl_clear! {
+    [T, const N: usize, LenT: heapless::LenType] heapless::Vec<T, N, LenT>;
+    [const N: usize, LenT: heapless::LenType] heapless::String<N, LenT>;
+    [T, const N: usize] heapless::Deque<T, N>;
+    [K, V, S, const N: usize] heapless::IndexMap<K, V, S, N>;
+    [T, S, const N: usize] heapless::IndexSet<T, S, N>;
+    [K: Eq, V, const N: usize] heapless::LinearMap<K, V, N>;
+}
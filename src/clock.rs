@@ -0,0 +1,126 @@
+//! The timing of the resets of `unsync`, `mutex` and `rwlock` temps, which requires std.
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+// The maximum age of the value and the minimum interval between drop-time resets. Only stored with std.
+pub(crate) struct ResetClock {
+    #[cfg(feature = "std")]
+    max_age: Option<Duration>,
+    #[cfg(feature = "std")]
+    min_interval: Option<Duration>,
+    // Only the holder of the value accesses it, so the mutex never blocks.
+    #[cfg(feature = "std")]
+    state: Mutex<State>,
+}
+#[cfg(feature = "std")]
+struct State {
+    reset_at: Option<Instant>,
+    // A dropped guard has left the value without resetting it.
+    pending: bool,
+}
+impl ResetClock {
+    pub(crate) const fn new() -> Self {
+        ResetClock {
+            #[cfg(feature = "std")]
+            max_age: None,
+            #[cfg(feature = "std")]
+            min_interval: None,
+            #[cfg(feature = "std")]
+            state: Mutex::new(State {
+                reset_at: None,
+                pending: false,
+            }),
+        }
+    }
+    #[cfg(feature = "std")]
+    pub(crate) fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = Some(max_age);
+        self.start();
+    }
+    #[cfg(feature = "std")]
+    pub(crate) fn max_age(&self) -> Option<Duration> {
+        self.max_age
+    }
+    #[cfg(feature = "std")]
+    pub(crate) fn set_min_interval(&mut self, interval: Duration) {
+        self.min_interval = Some(interval);
+        self.start();
+    }
+    #[cfg(feature = "std")]
+    pub(crate) fn min_interval(&self) -> Option<Duration> {
+        self.min_interval
+    }
+    // Returns whether a new guard has to reset the value first.
+    #[inline]
+    pub(crate) fn due(&self) -> bool {
+        #[cfg(feature = "std")]
+        if self.max_age.is_some() || self.min_interval.is_some() {
+            let state = self.state();
+            let Some(reset_at) = state.reset_at else {
+                return false;
+            };
+            let age = reset_at.elapsed();
+            return self.max_age.is_some_and(|max_age| age > max_age)
+                || (state.pending && self.min_interval.is_some_and(|interval| age >= interval));
+        }
+        false
+    }
+    // Returns whether a dropped guard should leave the value, marking the reset as pending.
+    #[inline]
+    pub(crate) fn postpone(&self) -> bool {
+        #[cfg(feature = "std")]
+        if let Some(interval) = self.min_interval {
+            let mut state = self.state();
+            let recent = state
+                .reset_at
+                .is_some_and(|reset_at| reset_at.elapsed() < interval);
+            if recent && !std::thread::panicking() {
+                state.pending = true;
+                return true;
+            }
+        }
+        false
+    }
+    #[inline]
+    pub(crate) fn reset(&self) {
+        #[cfg(feature = "std")]
+        if self.max_age.is_some() || self.min_interval.is_some() {
+            *self.state() = State {
+                reset_at: Some(Instant::now()),
+                pending: false,
+            };
+        }
+    }
+
+    #[cfg(feature = "std")]
+    fn start(&mut self) {
+        let state = self.state.get_mut().unwrap_or_else(PoisonError::into_inner);
+        state.reset_at.get_or_insert_with(Instant::now);
+    }
+    #[cfg(feature = "std")]
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl Clone for ResetClock {
+    fn clone(&self) -> Self {
+        #[allow(unused_mut)]
+        let mut clock = ResetClock::new();
+        #[cfg(feature = "std")]
+        {
+            clock.max_age = self.max_age;
+            clock.min_interval = self.min_interval;
+            clock.start();
+        }
+        clock
+    }
+}
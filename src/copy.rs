@@ -0,0 +1,143 @@
+//! `Cell` version of TempRef for `Copy` types. This module doesn't require std.
+//!
+//! Unlike [`unsync`](crate::unsync), there is no borrow flag to check, so nothing in this module panics.
+
+use core::{
+    cell::Cell,
+    fmt::{Debug, Display},
+};
+
+/// A scope of [`Temp<T>`] which stores the reset value into it when dropped.
+///
+/// It dereferences to the `Cell` holding the value, so the value can be read and written through it.
+pub struct TempRef<'a, T: Copy> {
+    temp: &'a Temp<T>,
+}
+impl<'a, T: Copy> core::ops::Deref for TempRef<'a, T> {
+    type Target = Cell<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.temp.value
+    }
+}
+impl<'a, T: Copy + Display> Display for TempRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.get(), f)
+    }
+}
+impl<'a, T: Copy> Drop for TempRef<'a, T> {
+    fn drop(&mut self) {
+        self.temp.reset();
+    }
+}
+
+/// A `Copy` value which snaps back to a stored reset value.
+///
+/// # Examples
+/// ```
+/// use tempref::copy::Temp;
+///
+/// let state = Temp::new(0u8, 0);
+/// {
+///     let scope = state.scope();
+///     scope.set(3);
+///     state.update(|n| n + 1);
+///     assert_eq!(state.get(), 4);
+/// } // The reset value 0 is stored here.
+/// assert_eq!(state.get(), 0);
+/// ```
+pub struct Temp<T: Copy> {
+    value: Cell<T>,
+    reset: T,
+    generation: Cell<usize>,
+}
+impl<T: Copy> Temp<T> {
+    /// A constructor of `Temp<T>`.
+    pub const fn new(value: T, reset: T) -> Self {
+        Temp {
+            value: Cell::new(value),
+            reset,
+            generation: Cell::new(0),
+        }
+    }
+    /// A constructor of `Temp<T>` whose value starts at the reset value.
+    pub const fn new_with(reset: T) -> Self {
+        Temp::new(reset, reset)
+    }
+    /// Returns a [`TempRef`] which stores the reset value when dropped.
+    pub fn scope(&self) -> TempRef<'_, T> {
+        TempRef { temp: self }
+    }
+    /// Returns a copy of the value.
+    pub fn get(&self) -> T {
+        self.value.get()
+    }
+    /// Sets the value. It is kept until the next reset.
+    pub fn set(&self, value: T) {
+        self.value.set(value);
+    }
+    /// Sets the value, returning the old one.
+    pub fn replace(&self, value: T) -> T {
+        self.value.replace(value)
+    }
+    /// Updates the value with `f`, returning the new one.
+    pub fn update(&self, f: impl FnOnce(T) -> T) -> T {
+        let value = f(self.value.get());
+        self.value.set(value);
+        value
+    }
+    /// Stores the reset value.
+    pub fn reset(&self) {
+        self.value.set(self.reset);
+        self.generation.set(self.generation.get() + 1);
+    }
+    /// Returns how many times the reset value has been stored.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+    /// Returns the reset value.
+    pub fn reset_value(&self) -> T {
+        self.reset
+    }
+    /// Replaces the reset value. The current value is not changed.
+    pub fn set_reset_value(&mut self, reset: T) {
+        self.reset = reset;
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no scope can be open.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+impl<T: Copy + Debug> Debug for Temp<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Temp")
+            .field("value", &self.value.get())
+            .field("reset", &self.reset)
+            .finish()
+    }
+}
+impl<T: Copy + Display> Display for Temp<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.value.get(), f)
+    }
+}
+impl<T: Copy> Clone for Temp<T> {
+    fn clone(&self) -> Self {
+        Temp {
+            value: self.value.clone(),
+            reset: self.reset,
+            generation: self.generation.clone(),
+        }
+    }
+}
+/// Creates a `Temp` whose value and reset value are `T::default()`.
+impl<T: Copy + Default> Default for Temp<T> {
+    fn default() -> Self {
+        Temp::new_with(T::default())
+    }
+}
@@ -0,0 +1,190 @@
+//! Critical section version of TempRef, a safe replacement for a `static mut` scratch value shared with interrupt
+//! handlers. This module doesn't require std.
+//!
+//! The value can only be borrowed inside a critical section of the `critical-section` crate, which disables interrupts
+//! on a single-core Cortex-M with the `critical-section-single-core` feature of `cortex-m`. A [`TempRef`] cannot
+//! outlive the [`CriticalSection`] it was borrowed with, so interrupts are always restored in order, after the reset.
+
+use core::{
+    cell::{Cell, UnsafeCell},
+    fmt::Debug,
+};
+
+use critical_section::CriticalSection;
+
+use crate::{Error, UnwindPolicy, unwind};
+
+/// A mutable reference from [`Temp<T, F>`], which lives no longer than the critical section.
+/// When it is dropped, it calls the reset function.
+pub struct TempRef<'cs, T, F: FnMut(&mut T)> {
+    temp: &'cs Temp<T, F>,
+    _cs: CriticalSection<'cs>,
+}
+impl<'cs, T, F: FnMut(&mut T)> TempRef<'cs, T, F> {
+    /// Invokes the reset function on the value.
+    ///
+    /// If the reset function panics, the `Temp` is marked as [reset-poisoned](Temp::is_reset_poisoned).
+    pub fn reset(&mut self) {
+        // SAFETY: Only the guard calls the reset function, and there is one guard at a time.
+        let reset = unsafe { &mut *self.temp.reset.get() };
+        let poisoned = &self.temp.reset_poisoned;
+        unwind::reset_or_poison(&mut **self, reset, || poisoned.set(true));
+    }
+}
+impl<'cs, T, F: FnMut(&mut T)> core::ops::Deref for TempRef<'cs, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The borrow flag is set and the critical section is held, so this guard is the only accessor.
+        unsafe { &*self.temp.value.get() }
+    }
+}
+impl<'cs, T, F: FnMut(&mut T)> core::ops::DerefMut for TempRef<'cs, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As in `deref`.
+        unsafe { &mut *self.temp.value.get() }
+    }
+}
+impl<'cs, T: Debug, F: FnMut(&mut T)> Debug for TempRef<'cs, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("value", &**self).finish()
+    }
+}
+impl<'cs, T, F: FnMut(&mut T)> Drop for TempRef<'cs, T, F> {
+    fn drop(&mut self) {
+        let _unborrow = Unborrow(&self.temp.borrowed);
+        // SAFETY: As in `reset`.
+        let reset = unsafe { &mut *self.temp.reset.get() };
+        // SAFETY: As in `deref`.
+        let value = unsafe { &mut *self.temp.value.get() };
+        unwind::reset_on_drop(UnwindPolicy::Reset, value, reset, || {
+            self.temp.reset_poisoned.set(true)
+        });
+        self.temp.generation.set(self.temp.generation.get() + 1);
+    }
+}
+
+// Clears the borrow flag when dropped, even if the reset function or `clone` panics.
+struct Unborrow<'a>(&'a Cell<bool>);
+impl Drop for Unborrow<'_> {
+    fn drop(&mut self) {
+        self.0.set(false);
+    }
+}
+
+/// A value shared with interrupt handlers, borrowed inside a critical section and reset when the guard is dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::critical::Temp;
+///
+/// static SCRATCH: Temp<[u8; 64]> = Temp::new([0; 64], |buf| buf.fill(0));
+///
+/// fn on_uart_rx(byte: u8) -> usize {
+///     SCRATCH.with_mut(|buf| {
+///         buf[0] = byte;
+///         buf.iter().filter(|&&b| b != 0).count()
+///     }) // Zeroed, then interrupts are restored.
+/// }
+///
+/// assert_eq!(on_uart_rx(7), 1);
+/// critical_section::with(|cs| assert_eq!(SCRATCH.borrow_mut(cs)[0], 0));
+/// ```
+pub struct Temp<T, F: FnMut(&mut T) = fn(&mut T)> {
+    value: UnsafeCell<T>,
+    reset: UnsafeCell<F>,
+    borrowed: Cell<bool>,
+    reset_poisoned: Cell<bool>,
+    generation: Cell<usize>,
+}
+// SAFETY: Everything is only accessed inside a critical section, which no other context can enter meanwhile.
+unsafe impl<T: Send, F: FnMut(&mut T) + Send> Sync for Temp<T, F> {}
+impl<T, F: FnMut(&mut T)> Temp<T, F> {
+    /// A constructor of Temp<T, F>.
+    pub const fn new(value: T, reset: F) -> Self {
+        Temp {
+            value: UnsafeCell::new(value),
+            reset: UnsafeCell::new(reset),
+            borrowed: Cell::new(false),
+            reset_poisoned: Cell::new(false),
+            generation: Cell::new(0),
+        }
+    }
+    /// Borrows the value for the rest of the critical section `cs`.
+    ///
+    /// # Panics
+    /// Panics if the value is already borrowed in this critical section.
+    #[track_caller]
+    pub fn borrow_mut<'cs>(&'cs self, cs: CriticalSection<'cs>) -> TempRef<'cs, T, F> {
+        match self.try_borrow_mut(cs) {
+            Ok(guard) => guard,
+            Err(err) => panic!("{err}"),
+        }
+    }
+    /// Borrows the value for the rest of the critical section `cs`, or returns [`Error::Borrow`] if it is already
+    /// borrowed.
+    pub fn try_borrow_mut<'cs>(
+        &'cs self,
+        cs: CriticalSection<'cs>,
+    ) -> Result<TempRef<'cs, T, F>, Error> {
+        if self.borrowed.replace(true) {
+            return Err(Error::Borrow);
+        }
+        Ok(TempRef {
+            temp: self,
+            _cs: cs,
+        })
+    }
+    /// Enters a critical section, calls `f` with the value, and resets it before leaving.
+    ///
+    /// # Panics
+    /// Panics if the value is already borrowed, e.g. when called from `f`.
+    #[track_caller]
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        critical_section::with(|cs| f(&mut self.borrow_mut(cs)))
+    }
+    /// Enters a critical section and returns a clone of the value, or [`Error::Borrow`] if it is borrowed.
+    /// The reset function is not called.
+    pub fn read_cloned(&self) -> Result<T, Error>
+    where
+        T: Clone,
+    {
+        critical_section::with(|_| {
+            // Marked as borrowed while cloning, so that `clone` cannot borrow it mutably.
+            if self.borrowed.replace(true) {
+                return Err(Error::Borrow);
+            }
+            let _unborrow = Unborrow(&self.borrowed);
+            // SAFETY: Inside a critical section, and no guard is alive.
+            Ok(unsafe { &*self.value.get() }.clone())
+        })
+    }
+    /// Returns how many guards have been dropped, each of which called the reset function.
+    pub fn generation(&self) -> usize {
+        critical_section::with(|_| self.generation.get())
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        critical_section::with(|_| self.reset_poisoned.get())
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        critical_section::with(|_| self.reset_poisoned.set(false));
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no critical section is needed and the reset function is not called.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+impl<T, F: FnMut(&mut T)> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Temp").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,188 @@
+//! Lock order checking of the `debug-deadlock` feature. This module requires std.
+//!
+//! Every thread keeps the locks its guards hold, and a global graph keeps, for every lock, the locks which have been
+//! acquired while it was held. Before a blocking acquisition, a path back from the new lock to a held one means that
+//! two threads could each wait for the other, so it panics with the cycle even if no deadlock happened this time.
+//! The checks only run with `debug_assertions`.
+//!
+//! Since the graph never has a cycle, an order which has been seen before needs no search, and only a new order takes
+//! the graph for writing. A lock leaves the graph with its edges when its `Temp` is dropped.
+
+extern crate std;
+
+use core::fmt::Write;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::string::String;
+use std::sync::{PoisonError, RwLock};
+use std::vec::Vec;
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+// For every lock, its type name, the locks which have been acquired while it was held and the locks which were held
+// while it was acquired.
+static ORDER: RwLock<BTreeMap<usize, Node>> = RwLock::new(BTreeMap::new());
+
+struct Node {
+    name: &'static str,
+    after: BTreeSet<usize>,
+    before: BTreeSet<usize>,
+}
+
+std::thread_local! {
+    // The locks held by the guards of this thread, with their type names.
+    static HELD: RefCell<Vec<(usize, &'static str)>> = const { RefCell::new(Vec::new()) };
+}
+
+// The identity of a lock, assigned on first use so that it survives moves of the `Temp`.
+pub(crate) struct LockId(AtomicUsize);
+impl LockId {
+    pub(crate) const fn new() -> Self {
+        LockId(AtomicUsize::new(0))
+    }
+    fn get(&self) -> usize {
+        match self.0.load(Ordering::Relaxed) {
+            0 => {
+                let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+                match self
+                    .0
+                    .compare_exchange(0, id, Ordering::Relaxed, Ordering::Relaxed)
+                {
+                    Ok(_) => id,
+                    Err(other) => other,
+                }
+            }
+            id => id,
+        }
+    }
+}
+impl Drop for LockId {
+    fn drop(&mut self) {
+        let id = *self.0.get_mut();
+        if id == 0 {
+            return;
+        }
+        let mut order = ORDER.write().unwrap_or_else(PoisonError::into_inner);
+        let Some(node) = order.remove(&id) else {
+            return;
+        };
+        for h in node.before {
+            if let Some(held) = order.get_mut(&h) {
+                held.after.remove(&id);
+            }
+        }
+        for a in node.after {
+            if let Some(after) = order.get_mut(&a) {
+                after.before.remove(&id);
+            }
+        }
+    }
+}
+
+// Called before blocking on the lock `id`, which is named `name` in the report.
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+pub(crate) fn check(id: &LockId, name: &'static str) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let id = id.get();
+    HELD.with(|held| {
+        let held = held.borrow();
+        // A guard of the same lock may still be counted as held, e.g. by a `ReadAfterWrite` which reacquires it.
+        let held = || held.iter().copied().filter(|&(h, _)| h != id);
+        let seen = |order: &BTreeMap<usize, Node>, h| order.get(&h).is_some_and(|node| node.after.contains(&id));
+        if held().next().is_none() {
+            return;
+        }
+        let order = ORDER.read().unwrap_or_else(PoisonError::into_inner);
+        if held().all(|(h, _)| seen(&order, h)) {
+            return;
+        }
+        drop(order);
+        let mut order = ORDER.write().unwrap_or_else(PoisonError::into_inner);
+        for (h, _) in held().filter(|&(h, _)| !seen(&order, h)) {
+            if let Some(path) = find_path(&order, id, h) {
+                let report = cycle_report(&order, h, &path);
+                drop(order);
+                panic!(
+                    "deadlock: lock order inversion, `{name}` (#{id}) is locked while holding #{h}, \
+                     which has been locked after it before: {report}"
+                );
+            }
+        }
+        for (h, held_name) in held() {
+            node(&mut order, h, held_name).after.insert(id);
+            node(&mut order, id, name).before.insert(h);
+        }
+    });
+}
+
+// Called when a guard of the lock `id` has been created.
+pub(crate) fn acquired(id: &LockId, name: &'static str) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let id = id.get();
+    HELD.with(|held| held.borrow_mut().push((id, name)));
+}
+
+// Called when a guard of the lock `id` is released.
+pub(crate) fn released(id: &LockId) {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+    let id = id.get();
+    HELD.with(|held| {
+        let mut held = held.borrow_mut();
+        if let Some(i) = held.iter().rposition(|&(h, _)| h == id) {
+            held.remove(i);
+        }
+    });
+}
+
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+fn node<'a>(order: &'a mut BTreeMap<usize, Node>, id: usize, name: &'static str) -> &'a mut Node {
+    order.entry(id).or_insert_with(|| Node {
+        name,
+        after: BTreeSet::new(),
+        before: BTreeSet::new(),
+    })
+}
+
+// Returns the locks on a path of the graph from `from` to `to`, both included.
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+fn find_path(order: &BTreeMap<usize, Node>, from: usize, to: usize) -> Option<Vec<usize>> {
+    let mut parents = BTreeMap::new();
+    let mut queue = std::collections::VecDeque::from([from]);
+    while let Some(id) = queue.pop_front() {
+        if id == to {
+            let mut path = Vec::from([to]);
+            while let Some(&parent) = parents.get(path.last().unwrap()) {
+                path.push(parent);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in order.get(&id).into_iter().flat_map(|node| &node.after) {
+            if next != from && !parents.contains_key(&next) {
+                parents.insert(next, id);
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+fn cycle_report(order: &BTreeMap<usize, Node>, held: usize, path: &[usize]) -> String {
+    let mut report = String::new();
+    for &id in core::iter::once(&held).chain(path) {
+        if !report.is_empty() {
+            report.push_str(" -> ");
+        }
+        let name = order.get(&id).map_or("?", |node| node.name);
+        let _ = write!(report, "`{name}` (#{id})");
+    }
+    report
+}
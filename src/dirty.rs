@@ -0,0 +1,336 @@
+//! Single thread version of TempRef for slices, which resets only the ranges written through the guard.
+//! This module requires alloc.
+//!
+//! The guard of [`Temp`] derefs to a shared slice. Writes go through [`TempRef::slice_mut`] or [`TempRef::get_mut`],
+//! which record the range they hand out, or through [`TempRef::untracked_mut`] followed by [`TempRef::mark_dirty`].
+//! When the guard is dropped, the reset function runs once for each of the merged dirty ranges instead of the whole slice.
+//!
+//! For huge buffers, [`Temp::with_page_size`] tracks dirtiness in pages instead, with one bit per page,
+//! so that many scattered writes cost neither memory nor sorting.
+
+use alloc::{boxed::Box, vec::Vec};
+use core::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    fmt::Debug,
+    ops::{Bound, Range, RangeBounds},
+};
+
+/// A mutable reference wrapper from [`Temp<T, F>`].
+///
+/// When dropped, it calls the reset function on every range that has been marked dirty.
+pub struct TempRef<'a, T, F: FnMut(&mut [T])> {
+    re: RefMut<'a, Box<[T]>>,
+    temp: &'a Temp<T, F>,
+}
+impl<'a, T, F: FnMut(&mut [T])> TempRef<'a, T, F> {
+    /// Returns a mutable subslice and marks it dirty.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn slice_mut(&mut self, range: impl RangeBounds<usize>) -> &mut [T] {
+        let range = to_range(range, self.re.len());
+        self.temp.mark(range.clone());
+        &mut self.re[range]
+    }
+    /// Returns a mutable reference to an element and marks it dirty, or `None` if `index` is out of bounds.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        let element = self.re.get_mut(index)?;
+        self.temp.mark(index..index + 1);
+        Some(element)
+    }
+    /// Returns the whole slice without marking anything dirty.
+    ///
+    /// Writes through it are not reset unless their range is passed to [`Self::mark_dirty`].
+    pub fn untracked_mut(&mut self) -> &mut [T] {
+        &mut self.re
+    }
+    /// Marks `range` dirty, so that it is reset when the guard is dropped.
+    ///
+    /// # Panics
+    /// Panics if `range` is out of bounds.
+    #[track_caller]
+    pub fn mark_dirty(&mut self, range: impl RangeBounds<usize>) {
+        let range = to_range(range, self.re.len());
+        self.temp.mark(range);
+    }
+    /// Invokes the reset function on the dirty ranges now, leaving nothing dirty.
+    pub fn reset(&mut self) {
+        self.temp.reset_dirty(&mut self.re);
+    }
+}
+impl<'a, T, F: FnMut(&mut [T])> core::ops::Deref for TempRef<'a, T, F> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T, F: FnMut(&mut [T])> AsRef<[T]> for TempRef<'a, T, F> {
+    fn as_ref(&self) -> &[T] {
+        self
+    }
+}
+impl<'a, T: Debug, F: FnMut(&mut [T])> Debug for TempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef")
+            .field("value", &&**self.re)
+            .field("dirty", &*self.temp.dirty.borrow())
+            .finish()
+    }
+}
+impl<'a, T, F: FnMut(&mut [T])> Drop for TempRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.temp.reset_dirty(&mut self.re);
+    }
+}
+
+/// A slice whose guards reset only the ranges written through them.
+///
+/// # Examples
+/// ```
+/// use tempref::dirty::Temp;
+///
+/// let workspace = Temp::new(vec![0u8; 1 << 20], |range| range.fill(0));
+/// {
+///     let mut guard = workspace.borrow_mut();
+///     guard.slice_mut(..4).copy_from_slice(b"head");
+///     *guard.get_mut(1000).unwrap() = 1;
+///     guard.untracked_mut()[2000] = 2;
+///     guard.mark_dirty(2000..=2000);
+/// } // Only 0..4, 1000..1001 and 2000..2001 are zeroed.
+/// assert!(workspace.borrow().iter().all(|&b| b == 0));
+/// ```
+pub struct Temp<T, F: FnMut(&mut [T]) = fn(&mut [T])> {
+    value: RefCell<Box<[T]>>,
+    reset: RefCell<F>,
+    dirty: RefCell<Dirty>,
+    generation: Cell<usize>,
+}
+impl<T, F: FnMut(&mut [T])> Temp<T, F> {
+    /// A constructor of Temp<T, F>. The reset function is called with each dirty range, never with the whole slice.
+    pub fn new(value: impl Into<Box<[T]>>, reset: F) -> Self {
+        Temp {
+            value: RefCell::new(value.into()),
+            reset: RefCell::new(reset),
+            dirty: RefCell::new(Dirty::Ranges(Vec::new())),
+            generation: Cell::new(0),
+        }
+    }
+    /// Tracks dirtiness in pages of `page_size` elements, resetting every page that has been written to as a whole.
+    ///
+    /// The ranges passed to the reset function are then aligned to pages, except at the end of the slice.
+    /// Anything already marked dirty is reset first.
+    ///
+    /// # Panics
+    /// Panics if `page_size` is 0.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::dirty::Temp;
+    ///
+    /// let resets = std::cell::Cell::new(0);
+    /// let workspace = Temp::new(vec![0u8; 64 << 20], |page| {
+    ///     resets.set(resets.get() + page.len());
+    ///     page.fill(0);
+    /// })
+    /// .with_page_size(4096);
+    /// {
+    ///     let mut guard = workspace.borrow_mut();
+    ///     for i in 0..2048 {
+    ///         *guard.get_mut(i * 7).unwrap() = 1;
+    ///     }
+    /// }
+    /// assert_eq!(resets.get(), 4 * 4096);
+    /// ```
+    pub fn with_page_size(mut self, page_size: usize) -> Self {
+        assert!(page_size > 0, "page size must be positive");
+        let value = self.value.get_mut();
+        self.dirty.get_mut().reset(value, self.reset.get_mut());
+        let pages = value.len().div_ceil(page_size);
+        *self.dirty.get_mut() = Dirty::Pages {
+            size: page_size,
+            bits: alloc::vec![0; pages.div_ceil(64)],
+            any: false,
+        };
+        self
+    }
+    /// Returns the page size set by [`Self::with_page_size`], or `None` if exact ranges are tracked.
+    pub fn page_size(&self) -> Option<usize> {
+        match &*self.dirty.borrow() {
+            Dirty::Ranges(_) => None,
+            Dirty::Pages { size, .. } => Some(*size),
+        }
+    }
+    /// Immutably borrows the slice.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, [T]> {
+        Ref::map(self.value.borrow(), |value| &**value)
+    }
+    /// Mutably borrows the slice. The returned [`TempRef`] resets the dirty ranges when dropped.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> TempRef<'_, T, F> {
+        TempRef {
+            re: self.value.borrow_mut(),
+            temp: self,
+        }
+    }
+    /// Invokes the reset function on the whole slice, e.g. to bring a value built elsewhere into the reset state.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn reset_all(&self) {
+        let mut value = self.value.borrow_mut();
+        (self.reset.borrow_mut())(&mut value);
+        self.generation.set(self.generation.get() + 1);
+    }
+    /// Returns the length of the slice.
+    pub fn len(&self) -> usize {
+        self.value.borrow().len()
+    }
+    /// Returns `true` if the slice is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns how many times the dirty ranges have been reset.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+    /// Returns a mutable reference to the slice.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be alive and nothing is marked dirty.
+    pub fn get_mut(&mut self) -> &mut [T] {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the slice.
+    pub fn into_inner(self) -> Box<[T]> {
+        self.value.into_inner()
+    }
+
+    fn mark(&self, range: Range<usize>) {
+        if !range.is_empty() {
+            self.dirty.borrow_mut().mark(range);
+        }
+    }
+    fn reset_dirty(&self, value: &mut [T]) {
+        if self
+            .dirty
+            .borrow_mut()
+            .reset(value, &mut *self.reset.borrow_mut())
+        {
+            self.generation.set(self.generation.get() + 1);
+        }
+    }
+}
+impl<T: Debug, F: FnMut(&mut [T])> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Temp").field("value", &self.value).finish()
+    }
+}
+
+#[track_caller]
+fn to_range(range: impl RangeBounds<usize>, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.checked_add(1).expect("range start overflows"),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end.checked_add(1).expect("range end overflows"),
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end && end <= len,
+        "range {start}..{end} is out of bounds of a slice of length {len}"
+    );
+    start..end
+}
+
+// The parts of the slice which have to be reset.
+#[derive(Debug)]
+enum Dirty {
+    // Sorted and merged only when they are reset.
+    Ranges(Vec<Range<usize>>),
+    // One bit per page of `size` elements. `any` lets a clean guard skip the scan.
+    Pages {
+        size: usize,
+        bits: Vec<u64>,
+        any: bool,
+    },
+}
+impl Dirty {
+    fn mark(&mut self, range: Range<usize>) {
+        match self {
+            Dirty::Ranges(ranges) => match ranges.last_mut() {
+                // Sequential writes usually extend the last range.
+                Some(last) if range.start <= last.end && last.start <= range.end => {
+                    last.start = last.start.min(range.start);
+                    last.end = last.end.max(range.end);
+                }
+                _ => ranges.push(range),
+            },
+            Dirty::Pages { size, bits, any } => {
+                for page in range.start / *size..=(range.end - 1) / *size {
+                    bits[page / 64] |= 1 << (page % 64);
+                }
+                *any = true;
+            }
+        }
+    }
+    // Resets the dirty parts of `value` with `reset`, returning whether there were any.
+    fn reset<T>(&mut self, value: &mut [T], reset: &mut impl FnMut(&mut [T])) -> bool {
+        match self {
+            Dirty::Ranges(ranges) => {
+                if ranges.is_empty() {
+                    return false;
+                }
+                ranges.sort_unstable_by_key(|range| range.start);
+                let mut ranges = ranges.drain(..);
+                let mut current = ranges.next().unwrap();
+                for range in ranges {
+                    if range.start <= current.end {
+                        current.end = current.end.max(range.end);
+                    } else {
+                        reset(&mut value[current]);
+                        current = range;
+                    }
+                }
+                reset(&mut value[current]);
+            }
+            Dirty::Pages { size, bits, any } => {
+                if !core::mem::take(any) {
+                    return false;
+                }
+                let len = value.len();
+                let mut run: Option<Range<usize>> = None;
+                for (word_index, word) in bits.iter_mut().enumerate() {
+                    let mut rest = core::mem::take(word);
+                    while rest != 0 {
+                        let page = word_index * 64 + rest.trailing_zeros() as usize;
+                        rest &= rest - 1;
+                        let page = page * *size..((page + 1) * *size).min(len);
+                        match &mut run {
+                            Some(current) if current.end == page.start => current.end = page.end,
+                            _ => {
+                                if let Some(current) = run.replace(page) {
+                                    reset(&mut value[current]);
+                                }
+                            }
+                        }
+                    }
+                }
+                if let Some(current) = run {
+                    reset(&mut value[current]);
+                }
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,80 @@
+//! An error type shared by every module.
+
+use core::cell::{BorrowError, BorrowMutError};
+use core::fmt;
+
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+extern crate std;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+use std::sync::{PoisonError, TryLockError};
+
+/// An error returned when a `Temp` could not be accessed, regardless of the module it comes from.
+///
+/// The errors of the underlying `RefCell`, `Mutex` and `RwLock` convert into it with `?`,
+/// so that code which is generic over the modules can handle failures uniformly.
+/// The guard carried by a `PoisonError` or a `TryLockError` is dropped by the conversion, which releases the lock.
+///
+/// # Examples
+/// ```
+/// use tempref::{Error, mutex, unsync};
+///
+/// fn fill(a: &unsync::Temp<Vec<u8>>, b: &mutex::Temp<Vec<u8>>) -> Result<usize, Error> {
+///     let mut a = a.try_borrow_mut()?;
+///     let mut b = b.try_lock()?;
+///     a.push(1);
+///     b.push(1);
+///     Ok(a.len() + b.len())
+/// }
+///
+/// let a: unsync::TempFn<Vec<u8>> = unsync::Temp::new(Vec::new(), |v| v.clear());
+/// let b: mutex::TempFn<Vec<u8>> = mutex::Temp::new(Vec::new(), |v| v.clear());
+/// assert_eq!(fill(&a, &b), Ok(2));
+///
+/// let _guard = b.lock().unwrap();
+/// assert_eq!(fill(&a, &b), Err(Error::WouldBlock));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// The value is already borrowed in a way that conflicts with the requested access.
+    Borrow,
+    /// The lock could not be acquired without blocking.
+    WouldBlock,
+    /// Another thread panicked while holding the lock.
+    Poisoned,
+}
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Borrow => f.write_str("the value is already borrowed"),
+            Error::WouldBlock => f.write_str("the lock could not be acquired without blocking"),
+            Error::Poisoned => f.write_str("the lock is poisoned"),
+        }
+    }
+}
+impl core::error::Error for Error {}
+impl From<BorrowError> for Error {
+    fn from(_: BorrowError) -> Self {
+        Error::Borrow
+    }
+}
+impl From<BorrowMutError> for Error {
+    fn from(_: BorrowMutError) -> Self {
+        Error::Borrow
+    }
+}
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+impl<G> From<PoisonError<G>> for Error {
+    fn from(_: PoisonError<G>) -> Self {
+        Error::Poisoned
+    }
+}
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+impl<G> From<TryLockError<G>> for Error {
+    fn from(err: TryLockError<G>) -> Self {
+        match err {
+            TryLockError::Poisoned(_) => Error::Poisoned,
+            TryLockError::WouldBlock => Error::WouldBlock,
+        }
+    }
+}
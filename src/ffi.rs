@@ -0,0 +1,154 @@
+//! C interface to `mutex` temps of zeroed byte buffers. This module requires std.
+//!
+//! A [`ByteTemp`] is handed to C as a reference-counted pointer, so the Rust side and the C side can share the same
+//! scratch buffer: Rust passes it with [`into_raw`] and takes it back with [`from_raw`], and C creates one with
+//! `tempref_new()`, shares it with `tempref_retain()` and lets go of it with `tempref_release()`.
+//! `tempref_lock()` returns a guard whose bytes are reached with `tempref_guard_data()` and `tempref_guard_len()`, and
+//! `tempref_unlock_reset()` zeroes the bytes and unlocks the buffer.
+//!
+//! The functions are exported unmangled, so a `cdylib` or `staticlib` which depends on this crate with the `ffi`
+//! feature exports them too. Their declarations are in `include/tempref.h`.
+//!
+//! A guard must be unlocked by the thread which locked it, like the guards of `std`. A poisoned buffer is locked as
+//! usual, since its bytes are zeroed whenever a guard is dropped anyway.
+
+extern crate std;
+
+use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
+use core::{mem::ManuallyDrop, ptr};
+use std::sync::{PoisonError, TryLockError};
+
+use crate::mutex::{OwnedTempRef, Temp};
+
+/// The `Temp` shared with C, a byte buffer whose reset function zeroes it.
+pub type ByteTemp = Temp<Vec<u8>, fn(&mut Vec<u8>)>;
+
+/// A guard of a [`ByteTemp`] handed to C, which zeroes the bytes when it is unlocked with `tempref_unlock_reset()`.
+pub struct TempRefGuard {
+    re: OwnedTempRef<Vec<u8>, fn(&mut Vec<u8>)>,
+}
+
+/// Hands `temp` over to C, as the pointer taken by the `tempref_*` functions.
+///
+/// The returned pointer owns one reference, which is given back by `tempref_release()` or [`from_raw`].
+pub fn into_raw(temp: Arc<ByteTemp>) -> *const ByteTemp {
+    Arc::into_raw(temp)
+}
+
+/// Takes back a reference handed to C by [`into_raw`], `tempref_new()` or `tempref_retain()`.
+///
+/// # Safety
+/// `temp` must own a reference which has not been given back yet, and it must not be used by this reference again.
+pub unsafe fn from_raw(temp: *const ByteTemp) -> Arc<ByteTemp> {
+    // SAFETY: Upheld by the caller.
+    unsafe { Arc::from_raw(temp) }
+}
+
+/// Creates a buffer of `len` zeroed bytes, and returns the only reference to it.
+#[unsafe(no_mangle)]
+pub extern "C" fn tempref_new(len: usize) -> *const ByteTemp {
+    into_raw(Arc::new(Temp::zeroing(vec![0; len])))
+}
+
+/// Adds a reference to the buffer, returning `temp` again. Null is ignored.
+///
+/// # Safety
+/// `temp` must be null, or a reference which has not been released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_retain(temp: *const ByteTemp) -> *const ByteTemp {
+    if !temp.is_null() {
+        // SAFETY: `temp` is a live reference.
+        unsafe { Arc::increment_strong_count(temp) };
+    }
+    temp
+}
+
+/// Gives back a reference to the buffer, which is freed with the last one. Null is ignored.
+///
+/// # Safety
+/// `temp` must be null, or a reference which has not been released, and it must not be used again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_release(temp: *const ByteTemp) {
+    if !temp.is_null() {
+        // SAFETY: `temp` is a live reference, which is not used again.
+        drop(unsafe { from_raw(temp) });
+    }
+}
+
+/// Returns how many times the buffer has been zeroed.
+///
+/// # Safety
+/// `temp` must be a reference which has not been released.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_generation(temp: *const ByteTemp) -> usize {
+    // SAFETY: `temp` is a live reference.
+    unsafe { &*temp }.generation()
+}
+
+/// Locks the buffer, blocking the current thread until it is free, and returns the guard.
+///
+/// # Safety
+/// `temp` must be a reference which has not been released. The buffer may be released while the guard is alive,
+/// which keeps it alive until it is unlocked.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_lock(temp: *const ByteTemp) -> *mut TempRefGuard {
+    // SAFETY: `temp` is a live reference, which is only borrowed.
+    let temp = unsafe { borrowed(temp) };
+    let re = temp.lock_arc().unwrap_or_else(PoisonError::into_inner);
+    Box::into_raw(Box::new(TempRefGuard { re }))
+}
+
+/// Locks the buffer if it is free and returns the guard, or returns null if it is locked.
+///
+/// # Safety
+/// The same as `tempref_lock()`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_try_lock(temp: *const ByteTemp) -> *mut TempRefGuard {
+    // SAFETY: `temp` is a live reference, which is only borrowed.
+    let temp = unsafe { borrowed(temp) };
+    let re = match temp.try_lock_arc() {
+        Ok(re) => re,
+        Err(TryLockError::Poisoned(err)) => err.into_inner(),
+        Err(TryLockError::WouldBlock) => return ptr::null_mut(),
+    };
+    Box::into_raw(Box::new(TempRefGuard { re }))
+}
+
+/// Returns a pointer to the bytes of the buffer, which is valid until the guard is unlocked.
+///
+/// # Safety
+/// `guard` must be a guard which has not been unlocked.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_guard_data(guard: *mut TempRefGuard) -> *mut u8 {
+    // SAFETY: `guard` is a live guard.
+    unsafe { &mut *guard }.re.as_mut_ptr()
+}
+
+/// Returns the number of bytes of the buffer.
+///
+/// # Safety
+/// `guard` must be a guard which has not been unlocked.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_guard_len(guard: *const TempRefGuard) -> usize {
+    // SAFETY: `guard` is a live guard.
+    unsafe { &*guard }.re.len()
+}
+
+/// Zeroes the bytes of the buffer and unlocks it. Null is ignored.
+///
+/// # Safety
+/// `guard` must be null, or a guard which has not been unlocked, on the thread which locked it.
+/// Neither the guard nor the pointer from `tempref_guard_data()` may be used again.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn tempref_unlock_reset(guard: *mut TempRefGuard) {
+    if !guard.is_null() {
+        // SAFETY: `guard` is a live guard, which is not used again.
+        drop(unsafe { Box::from_raw(guard) });
+    }
+}
+
+// Returns the `Arc` behind `temp` without taking over its reference.
+unsafe fn borrowed(temp: *const ByteTemp) -> ManuallyDrop<Arc<ByteTemp>> {
+    // SAFETY: Upheld by the caller, and the reference is not given back since it is never dropped.
+    ManuallyDrop::new(unsafe { Arc::from_raw(temp) })
+}
@@ -0,0 +1,157 @@
+//! Branded version of TempRef, whose borrows are checked at compile time. This module doesn't require std.
+//!
+//! Like `GhostCell`, every [`Temp`] is branded with the lifetime of a [`GhostToken`], and borrowing its value needs
+//! the token: `&GhostToken` for shared access, `&mut GhostToken` for a [`TempRef`]. The borrow checker then rules out
+//! conflicting borrows, so there is no borrow flag and nothing here panics. Since a `TempRef` borrows the token,
+//! it is always dropped, and the value reset, before the scope of the token ends.
+
+use core::{cell::UnsafeCell, fmt::Debug, marker::PhantomData};
+
+// An invariant lifetime, so that brands can neither grow nor shrink into each other.
+type Brand<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
+
+/// The permission to borrow every [`Temp`] of the brand `'brand`.
+pub struct GhostToken<'brand> {
+    _brand: Brand<'brand>,
+}
+impl<'brand> GhostToken<'brand> {
+    /// Calls `f` with a token of a new, unique brand.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::ghost::{GhostToken, Temp};
+    ///
+    /// let sum = GhostToken::scope(|mut token| {
+    ///     let workspace = Temp::new(Vec::new(), |v: &mut Vec<u32>| v.clear());
+    ///     let mut guard = workspace.borrow_mut(&mut token);
+    ///     guard.extend([1, 2, 3]);
+    ///     guard.iter().sum::<u32>()
+    /// });
+    /// assert_eq!(sum, 6);
+    /// ```
+    pub fn scope<R>(f: impl for<'new> FnOnce(GhostToken<'new>) -> R) -> R {
+        f(GhostToken {
+            _brand: PhantomData,
+        })
+    }
+}
+impl<'brand> Debug for GhostToken<'brand> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("GhostToken").finish()
+    }
+}
+
+/// A mutable reference from [`Temp<'brand, T, F>`], which holds the token mutably.
+/// When it is dropped, it calls the reset function.
+pub struct TempRef<'a, 'brand, T, F: FnMut(&mut T)> {
+    temp: &'a Temp<'brand, T, F>,
+    _token: &'a mut GhostToken<'brand>,
+}
+impl<'a, 'brand, T, F: FnMut(&mut T)> TempRef<'a, 'brand, T, F> {
+    /// Invokes the reset function on the value.
+    pub fn reset(&mut self) {
+        // SAFETY: The token is held mutably, so nothing else accesses the value or the reset function.
+        let (value, reset) = unsafe { (&mut *self.temp.value.get(), &mut *self.temp.reset.get()) };
+        reset(value);
+    }
+}
+impl<'a, 'brand, T, F: FnMut(&mut T)> core::ops::Deref for TempRef<'a, 'brand, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: As in `reset`.
+        unsafe { &*self.temp.value.get() }
+    }
+}
+impl<'a, 'brand, T, F: FnMut(&mut T)> core::ops::DerefMut for TempRef<'a, 'brand, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As in `reset`.
+        unsafe { &mut *self.temp.value.get() }
+    }
+}
+impl<'a, 'brand, T: Debug, F: FnMut(&mut T)> Debug for TempRef<'a, 'brand, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("value", &**self).finish()
+    }
+}
+impl<'a, 'brand, T, F: FnMut(&mut T)> Drop for TempRef<'a, 'brand, T, F> {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+/// A value of the brand `'brand`, borrowed with its [`GhostToken`] and reset when the guard is dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::ghost::{GhostToken, Temp};
+///
+/// GhostToken::scope(|mut token| {
+///     let stack = Temp::new(vec![0], |v: &mut Vec<u8>| v.truncate(1));
+///     let other = Temp::new(String::new(), |s: &mut String| s.clear());
+///     stack.borrow_mut(&mut token).push(1);
+///     assert_eq!(*stack.borrow(&token), [0]);
+///     // Both can be read at once, but only one can be borrowed mutably at a time.
+///     assert_eq!(other.borrow(&token).len(), stack.borrow(&token).len() - 1);
+/// });
+/// ```
+///
+/// A second mutable borrow does not compile:
+/// ```compile_fail
+/// use tempref::ghost::{GhostToken, Temp};
+///
+/// GhostToken::scope(|mut token| {
+///     let a = Temp::new(0, |n: &mut i32| *n = 0);
+///     let b = Temp::new(0, |n: &mut i32| *n = 0);
+///     let guard = a.borrow_mut(&mut token);
+///     *b.borrow_mut(&mut token) += *guard;
+/// });
+/// ```
+pub struct Temp<'brand, T, F: FnMut(&mut T) = fn(&mut T)> {
+    value: UnsafeCell<T>,
+    reset: UnsafeCell<F>,
+    _brand: Brand<'brand>,
+}
+// SAFETY: Like `GhostCell`, shared access needs `&GhostToken` and exclusive access needs `&mut GhostToken`,
+// so the value is accessed like through `&T` and `&mut T`, and the reset function only with the token held mutably.
+unsafe impl<'brand, T: Send + Sync, F: FnMut(&mut T) + Send> Sync for Temp<'brand, T, F> {}
+impl<'brand, T, F: FnMut(&mut T)> Temp<'brand, T, F> {
+    /// A constructor of Temp<'brand, T, F>.
+    pub const fn new(value: T, reset: F) -> Self {
+        Temp {
+            value: UnsafeCell::new(value),
+            reset: UnsafeCell::new(reset),
+            _brand: PhantomData,
+        }
+    }
+    /// Immutably borrows the value for as long as the token is borrowed.
+    pub fn borrow<'a>(&'a self, _token: &'a GhostToken<'brand>) -> &'a T {
+        // SAFETY: The token is borrowed, so no `TempRef` of this brand is alive.
+        unsafe { &*self.value.get() }
+    }
+    /// Mutably borrows the value for as long as the token is borrowed.
+    /// The returned [`TempRef`] calls the reset function when dropped.
+    pub fn borrow_mut<'a>(
+        &'a self,
+        token: &'a mut GhostToken<'brand>,
+    ) -> TempRef<'a, 'brand, T, F> {
+        TempRef {
+            temp: self,
+            _token: token,
+        }
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no token is needed and the reset function is not called.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+impl<'brand, T, F: FnMut(&mut T)> Debug for Temp<'brand, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Temp").finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,167 @@
+//! Resetting many `Temp`s of any module at once. This module requires alloc.
+
+use alloc::{rc::Rc, sync::Arc, vec::Vec};
+use core::fmt;
+
+use crate::{Error, TempAccess};
+
+/// A `Temp` which can be reset through a trait object, unlike [`TempAccess`] whose guard type depends on it.
+///
+/// It is implemented for every [`TempAccess`], and for an `Rc` or an `Arc` of one.
+pub trait TempLike {
+    /// Resets the value, or returns why it could not be reset.
+    ///
+    /// A `mutex` or `rwlock` `Temp` waits for its lock, as [`TempAccess::reset`] does.
+    fn reset_temp(&self) -> Result<(), Error>;
+}
+impl<T: TempAccess + ?Sized> TempLike for T {
+    fn reset_temp(&self) -> Result<(), Error> {
+        self.reset()
+    }
+}
+impl<T: TempLike + ?Sized> TempLike for Rc<T> {
+    fn reset_temp(&self) -> Result<(), Error> {
+        (**self).reset_temp()
+    }
+}
+impl<T: TempLike + ?Sized> TempLike for Arc<T> {
+    fn reset_temp(&self) -> Result<(), Error> {
+        (**self).reset_temp()
+    }
+}
+
+/// The failures of [`reset_all`] or [`ResetGroup::try_reset_all`], each with the position of the `Temp` which failed.
+///
+/// The other `Temp`s were reset anyway.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResetErrors {
+    failures: Vec<(usize, Error)>,
+}
+impl ResetErrors {
+    /// Returns the position of every `Temp` which could not be reset, in order, with its error.
+    pub fn failures(&self) -> &[(usize, Error)] {
+        &self.failures
+    }
+    /// Returns how many `Temp`s could not be reset.
+    pub fn len(&self) -> usize {
+        self.failures.len()
+    }
+    /// Always returns false, since there is at least one failure.
+    pub fn is_empty(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+impl fmt::Display for ResetErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} temp(s) could not be reset", self.failures.len())?;
+        for (i, (index, err)) in self.failures.iter().enumerate() {
+            f.write_str(if i == 0 { ": " } else { ", " })?;
+            write!(f, "#{index} ({err})")?;
+        }
+        Ok(())
+    }
+}
+impl core::error::Error for ResetErrors {}
+
+/// Resets every `Temp` of `temps` in order, and returns the failures if any.
+///
+/// A failure doesn't stop the `Temp`s after it from being reset.
+///
+/// # Examples
+/// ```
+/// use tempref::{Error, unsync};
+///
+/// let connections: Vec<_> = (0..4)
+///     .map(|_| unsync::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear()))
+///     .collect();
+/// assert_eq!(tempref::reset_all(&connections), Ok(()));
+/// assert!(connections.iter().all(|c| c.generation() == 1));
+///
+/// let _guard = connections[2].borrow_mut();
+/// let errors = tempref::reset_all(&connections).unwrap_err();
+/// assert_eq!(errors.failures(), [(2, Error::Borrow)]);
+/// ```
+pub fn reset_all<'a, T, I>(temps: I) -> Result<(), ResetErrors>
+where
+    T: TempLike + ?Sized + 'a,
+    I: IntoIterator<Item = &'a T>,
+{
+    let failures: Vec<_> = temps
+        .into_iter()
+        .enumerate()
+        .filter_map(|(index, temp)| temp.reset_temp().err().map(|err| (index, err)))
+        .collect();
+    if failures.is_empty() {
+        Ok(())
+    } else {
+        Err(ResetErrors { failures })
+    }
+}
+
+/// A list of `Temp`s of any module which are reset together, e.g. at the end of a frame.
+///
+/// # Examples
+/// ```
+/// use tempref::{Error, ResetGroup, mutex, unsync};
+///
+/// let vertices = unsync::Temp::new(Vec::new(), |v: &mut Vec<f32>| v.clear());
+/// let text = mutex::Temp::new(String::new(), |s: &mut String| s.clear());
+/// let mut frame = ResetGroup::new();
+/// frame.register(&vertices).register(&text);
+///
+/// text.set(String::from("fps: 60")).unwrap(); // Not reset by a guard.
+/// frame.reset_all();
+/// assert!(text.lock_read().unwrap().is_empty());
+///
+/// let _guard = vertices.borrow_mut();
+/// let errors = frame.try_reset_all().unwrap_err();
+/// assert_eq!(errors.failures(), [(0, Error::Borrow)]);
+/// ```
+#[derive(Default)]
+pub struct ResetGroup<'a> {
+    members: Vec<&'a dyn TempLike>,
+}
+impl<'a> ResetGroup<'a> {
+    /// A constructor of an empty ResetGroup.
+    pub const fn new() -> Self {
+        ResetGroup {
+            members: Vec::new(),
+        }
+    }
+    /// Adds `temp` to the group, after the `Temp`s already in it.
+    pub fn register(&mut self, temp: &'a dyn TempLike) -> &mut Self {
+        self.members.push(temp);
+        self
+    }
+    /// Returns how many `Temp`s are in the group.
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+    /// Returns true if no `Temp` is in the group.
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+    /// Resets every `Temp` in the group, in the order they were registered.
+    ///
+    /// # Panics
+    /// Panics after trying all of them if any could not be reset, e.g. because it is borrowed.
+    #[track_caller]
+    pub fn reset_all(&self) {
+        if let Err(errors) = self.try_reset_all() {
+            panic!("{errors}");
+        }
+    }
+    /// Resets every `Temp` in the group, in the order they were registered, and returns the failures if any.
+    ///
+    /// A failure doesn't stop the `Temp`s after it from being reset.
+    pub fn try_reset_all(&self) -> Result<(), ResetErrors> {
+        reset_all(self.members.iter().copied())
+    }
+}
+impl fmt::Debug for ResetGroup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ResetGroup")
+            .field("len", &self.members.len())
+            .finish()
+    }
+}
@@ -0,0 +1,191 @@
+//! Single thread version of TempRef with undo and redo. This module requires alloc.
+//!
+//! A [`TempRef`] of [`HistoryTemp`] is committed like a transaction. Committed states are recorded, and a guard
+//! dropped without committing restores the current state, so a reset means going back to the last good state.
+
+use alloc::vec::Vec;
+use core::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    fmt::{Debug, Display},
+};
+
+/// A mutable reference from [`HistoryTemp<T>`].
+///
+/// [`Self::commit`] records the value as a new state. Otherwise, the current state is restored when it is dropped.
+pub struct TempRef<'a, T: Clone> {
+    re: RefMut<'a, T>,
+    temp: &'a HistoryTemp<T>,
+    committed: bool,
+}
+impl<'a, T: Clone> TempRef<'a, T> {
+    /// Records the value as a new state, discarding the states which could be redone.
+    pub fn commit(mut self) {
+        self.temp.record(&self.re);
+        self.committed = true;
+    }
+    /// Restores the current state.
+    pub fn reset(&mut self) {
+        self.temp.restore(&mut self.re);
+    }
+}
+impl<'a, T: Clone> core::ops::Deref for TempRef<'a, T> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T: Clone> core::ops::DerefMut for TempRef<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T: Clone + Debug> Debug for TempRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("value", &*self.re).finish()
+    }
+}
+impl<'a, T: Clone + Display> Display for TempRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&*self.re, f)
+    }
+}
+impl<'a, T: Clone> Drop for TempRef<'a, T> {
+    fn drop(&mut self) {
+        if !self.committed {
+            self.temp.restore(&mut self.re);
+        }
+    }
+}
+
+/// A value with a history of committed states, whose uncommitted changes are reset when the guard is dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::history::HistoryTemp;
+///
+/// let text = HistoryTemp::new(String::new());
+/// let mut edit = text.borrow_mut();
+/// edit.push_str("Hello");
+/// edit.commit();
+/// let mut edit = text.borrow_mut();
+/// edit.push_str(", world");
+/// edit.commit();
+/// text.borrow_mut().push_str("!!!"); // Not committed, so it is reset to "Hello, world".
+/// assert_eq!(*text.borrow(), "Hello, world");
+///
+/// assert!(text.undo());
+/// assert_eq!(*text.borrow(), "Hello");
+/// assert!(text.redo());
+/// assert_eq!(*text.borrow(), "Hello, world");
+/// ```
+pub struct HistoryTemp<T: Clone> {
+    value: RefCell<T>,
+    // Never empty. `states[current]` is the current state, and the ones after it can be redone.
+    states: RefCell<Vec<T>>,
+    current: Cell<usize>,
+    generation: Cell<usize>,
+}
+impl<T: Clone> HistoryTemp<T> {
+    /// A constructor of `HistoryTemp<T>`, whose value is the first state.
+    pub fn new(value: T) -> Self {
+        HistoryTemp {
+            states: RefCell::new(alloc::vec![value.clone()]),
+            value: RefCell::new(value),
+            current: Cell::new(0),
+            generation: Cell::new(0),
+        }
+    }
+    /// Immutably borrows the value.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+    /// Mutably borrows the value. The returned [`TempRef`] restores the current state unless it is committed.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> TempRef<'_, T> {
+        TempRef {
+            re: self.value.borrow_mut(),
+            temp: self,
+            committed: false,
+        }
+    }
+    /// Goes back to the previous state. Returns `false` if there is none.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn undo(&self) -> bool {
+        let current = self.current.get();
+        current > 0 && self.go_to(current - 1)
+    }
+    /// Goes forward to the state which was undone last. Returns `false` if there is none.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn redo(&self) -> bool {
+        self.go_to(self.current.get() + 1)
+    }
+    /// Returns whether [`Self::undo`] would go back.
+    pub fn can_undo(&self) -> bool {
+        self.current.get() > 0
+    }
+    /// Returns whether [`Self::redo`] would go forward.
+    pub fn can_redo(&self) -> bool {
+        self.current.get() + 1 < self.states.borrow().len()
+    }
+    /// Returns how many times the value has been restored to a state.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `HistoryTemp` mutably, no guard can be alive and nothing is recorded.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the HistoryTemp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    #[track_caller]
+    fn go_to(&self, state: usize) -> bool {
+        if state >= self.states.borrow().len() {
+            return false;
+        }
+        let mut value = self.value.borrow_mut();
+        self.current.set(state);
+        self.restore(&mut value);
+        true
+    }
+    fn record(&self, value: &T) {
+        let mut states = self.states.borrow_mut();
+        let current = self.current.get() + 1;
+        states.truncate(current);
+        states.push(value.clone());
+        self.current.set(current);
+    }
+    fn restore(&self, value: &mut T) {
+        value.clone_from(&self.states.borrow()[self.current.get()]);
+        self.generation.set(self.generation.get() + 1);
+    }
+}
+impl<T: Clone + Debug> Debug for HistoryTemp<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("HistoryTemp");
+        match self.value.try_borrow() {
+            Ok(value) => d.field("value", &*value),
+            Err(_) => d.field("value", &format_args!("<borrowed>")),
+        };
+        d.field("state", &self.current.get())
+            .field("states", &self.states.borrow().len())
+            .finish()
+    }
+}
@@ -0,0 +1,108 @@
+//! Lifecycle hooks of `unsync`, `mutex` and `rwlock` temps.
+
+/// Functions called on the value of a `Temp` at points of its lifecycle, set with `with_hooks()`.
+///
+/// Every function does nothing by default. Since they are not given `self`, any state they record lives in statics.
+/// The hooks are only called with the `hooks` feature, which adds three function pointers to every `Temp`.
+///
+/// # Examples
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use tempref::{TempHooks, mutex::Temp};
+///
+/// static RESETS: AtomicUsize = AtomicUsize::new(0);
+///
+/// struct Checked;
+/// impl TempHooks<Vec<u8>> for Checked {
+///     fn on_reset(value: &Vec<u8>) {
+///         debug_assert!(value.is_empty());
+///         RESETS.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+///
+/// # #[cfg(feature = "hooks")] {
+/// let workspace = Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear()).with_hooks::<Checked>();
+/// workspace.lock().unwrap().push(1);
+/// assert_eq!(RESETS.load(Ordering::Relaxed), 1);
+/// # }
+/// ```
+pub trait TempHooks<T> {
+    /// Called when a guard which resets the value is created, such as `TempRef` or `Transaction`.
+    fn on_acquire(value: &T) {
+        let _ = value;
+    }
+    /// Called when such a guard is dropped, before the reset function runs.
+    fn on_release(value: &T) {
+        let _ = value;
+    }
+    /// Called after the reset function has run, including explicit calls of `reset()`.
+    fn on_reset(value: &T) {
+        let _ = value;
+    }
+}
+
+// The hooks of a `Temp`, which are only stored with the `hooks` feature.
+pub(crate) struct Hooks<T> {
+    #[cfg(feature = "hooks")]
+    acquire: fn(&T),
+    #[cfg(feature = "hooks")]
+    release: fn(&T),
+    #[cfg(feature = "hooks")]
+    reset: fn(&T),
+    #[cfg(not(feature = "hooks"))]
+    _value: core::marker::PhantomData<fn(&T)>,
+}
+impl<T> Hooks<T> {
+    pub(crate) const fn new() -> Self {
+        Hooks {
+            #[cfg(feature = "hooks")]
+            acquire: |_| {},
+            #[cfg(feature = "hooks")]
+            release: |_| {},
+            #[cfg(feature = "hooks")]
+            reset: |_| {},
+            #[cfg(not(feature = "hooks"))]
+            _value: core::marker::PhantomData,
+        }
+    }
+    #[cfg(feature = "hooks")]
+    pub(crate) fn of<H: TempHooks<T>>() -> Self {
+        Hooks {
+            acquire: H::on_acquire,
+            release: H::on_release,
+            reset: H::on_reset,
+        }
+    }
+    #[inline]
+    pub(crate) fn acquire(&self, value: &T) {
+        #[cfg(feature = "hooks")]
+        (self.acquire)(value);
+        let _ = value;
+    }
+    #[inline]
+    pub(crate) fn release(&self, value: &T) {
+        #[cfg(feature = "hooks")]
+        (self.release)(value);
+        let _ = value;
+    }
+    #[inline]
+    pub(crate) fn reset(&self, value: &T) {
+        #[cfg(feature = "hooks")]
+        (self.reset)(value);
+        let _ = value;
+    }
+}
+impl<T> Clone for Hooks<T> {
+    fn clone(&self) -> Self {
+        Hooks {
+            #[cfg(feature = "hooks")]
+            acquire: self.acquire,
+            #[cfg(feature = "hooks")]
+            release: self.release,
+            #[cfg(feature = "hooks")]
+            reset: self.reset,
+            #[cfg(not(feature = "hooks"))]
+            _value: core::marker::PhantomData,
+        }
+    }
+}
@@ -0,0 +1,250 @@
+//! Instrumentation of the guards of `unsync`, `mutex` and `rwlock` temps.
+// The type parameters are only used to name the type of the value in the events.
+#![cfg_attr(not(feature = "tracing"), allow(clippy::extra_unused_type_parameters))]
+
+#[cfg(any(
+    feature = "mutex",
+    feature = "rwlock",
+    feature = "tracing",
+    feature = "stats"
+))]
+extern crate std;
+
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+use core::marker::PhantomData;
+#[cfg(feature = "stats")]
+use core::sync::atomic::{AtomicU64, Ordering};
+#[cfg(feature = "stats")]
+use core::time::Duration;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+use std::sync::{LockResult, TryLockError, TryLockResult};
+#[cfg(any(feature = "tracing", feature = "stats"))]
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+#[cfg(feature = "debug-deadlock")]
+use crate::deadlock::{self, LockId};
+
+/// Counters of a `Temp` since it was created, returned by `stats()` with the `stats` feature.
+#[cfg(feature = "stats")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// The number of guards which reset the value that have been created.
+    pub borrows: u64,
+    /// The number of times the reset function has been called.
+    pub resets: u64,
+    /// The number of times a blocking lock found the value locked and had to wait.
+    pub contended: u64,
+    /// The total time the guards have been held.
+    pub total_hold: Duration,
+    /// The longest time a guard has been held.
+    pub max_hold: Duration,
+}
+
+// What a `Temp` records about its guards, which is only stored with the `tracing` or `stats` feature.
+pub(crate) struct Instrument {
+    #[cfg(feature = "tracing")]
+    label: Option<&'static str>,
+    // When the current guard was created. Only its holder accesses it, so the mutex never blocks.
+    #[cfg(any(feature = "tracing", feature = "stats"))]
+    acquired_at: Mutex<Option<Instant>>,
+    #[cfg(feature = "stats")]
+    borrows: AtomicU64,
+    #[cfg(feature = "stats")]
+    resets: AtomicU64,
+    #[cfg(feature = "stats")]
+    contended: AtomicU64,
+    // In nanoseconds.
+    #[cfg(feature = "stats")]
+    total_hold: AtomicU64,
+    #[cfg(feature = "stats")]
+    max_hold: AtomicU64,
+    #[cfg(feature = "debug-deadlock")]
+    lock_id: LockId,
+}
+impl Instrument {
+    pub(crate) const fn new() -> Self {
+        Instrument {
+            #[cfg(feature = "tracing")]
+            label: None,
+            #[cfg(any(feature = "tracing", feature = "stats"))]
+            acquired_at: Mutex::new(None),
+            #[cfg(feature = "stats")]
+            borrows: AtomicU64::new(0),
+            #[cfg(feature = "stats")]
+            resets: AtomicU64::new(0),
+            #[cfg(feature = "stats")]
+            contended: AtomicU64::new(0),
+            #[cfg(feature = "stats")]
+            total_hold: AtomicU64::new(0),
+            #[cfg(feature = "stats")]
+            max_hold: AtomicU64::new(0),
+            #[cfg(feature = "debug-deadlock")]
+            lock_id: LockId::new(),
+        }
+    }
+    #[cfg(feature = "tracing")]
+    pub(crate) fn set_label(&mut self, label: &'static str) {
+        self.label = Some(label);
+    }
+    #[cfg(feature = "stats")]
+    pub(crate) fn stats(&self) -> Stats {
+        Stats {
+            borrows: self.borrows.load(Ordering::Relaxed),
+            resets: self.resets.load(Ordering::Relaxed),
+            contended: self.contended.load(Ordering::Relaxed),
+            total_hold: Duration::from_nanos(self.total_hold.load(Ordering::Relaxed)),
+            max_hold: Duration::from_nanos(self.max_hold.load(Ordering::Relaxed)),
+        }
+    }
+    // Takes a lock with `lock`, recording whether `try_lock` would have had to wait.
+    #[cfg(any(feature = "mutex", feature = "rwlock"))]
+    pub(crate) fn lock<T: ?Sized, G>(
+        &self,
+        try_lock: impl FnOnce() -> TryLockResult<G>,
+        lock: impl FnOnce() -> LockResult<G>,
+    ) -> LockResult<G> {
+        #[cfg(feature = "debug-deadlock")]
+        deadlock::check(&self.lock_id, core::any::type_name::<T>());
+        match try_lock() {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Err(err),
+            Err(TryLockError::WouldBlock) => {
+                #[cfg(feature = "stats")]
+                self.contended.fetch_add(1, Ordering::Relaxed);
+                #[cfg(feature = "tracing")]
+                let start = Instant::now();
+                let result = lock();
+                #[cfg(feature = "tracing")]
+                tracing::debug!(
+                    target: "tempref",
+                    r#type = core::any::type_name::<T>(),
+                    label = self.label,
+                    waited = ?start.elapsed(),
+                    "contended"
+                );
+                result
+            }
+        }
+    }
+    #[inline]
+    pub(crate) fn acquire<T: ?Sized>(&self) {
+        #[cfg(feature = "debug-deadlock")]
+        deadlock::acquired(&self.lock_id, core::any::type_name::<T>());
+        #[cfg(any(feature = "tracing", feature = "stats"))]
+        {
+            *self.at() = Some(Instant::now());
+        }
+        #[cfg(feature = "stats")]
+        self.borrows.fetch_add(1, Ordering::Relaxed);
+        #[cfg(feature = "tracing")]
+        tracing::trace!(
+            target: "tempref",
+            r#type = core::any::type_name::<T>(),
+            label = self.label,
+            "acquired"
+        );
+    }
+    #[inline]
+    pub(crate) fn release<T: ?Sized>(&self) {
+        #[cfg(feature = "debug-deadlock")]
+        deadlock::released(&self.lock_id);
+        #[cfg(any(feature = "tracing", feature = "stats"))]
+        if let Some(acquired_at) = self.at().take() {
+            let held = acquired_at.elapsed();
+            #[cfg(feature = "stats")]
+            {
+                let nanos = u64::try_from(held.as_nanos()).unwrap_or(u64::MAX);
+                self.total_hold.fetch_add(nanos, Ordering::Relaxed);
+                self.max_hold.fetch_max(nanos, Ordering::Relaxed);
+            }
+            #[cfg(feature = "tracing")]
+            tracing::trace!(
+                target: "tempref",
+                r#type = core::any::type_name::<T>(),
+                label = self.label,
+                held = ?held,
+                "released"
+            );
+        }
+    }
+    // Counts the lock as held by the current thread in the lock order checks until the returned value is dropped,
+    // for the methods which lock the value without handing out a guard. No guard is recorded.
+    #[cfg(any(feature = "mutex", feature = "rwlock"))]
+    #[cfg_attr(
+        not(feature = "debug-deadlock"),
+        allow(clippy::extra_unused_type_parameters)
+    )]
+    #[inline]
+    pub(crate) fn hold<T: ?Sized>(&self) -> Hold<'_> {
+        #[cfg(feature = "debug-deadlock")]
+        deadlock::acquired(&self.lock_id, core::any::type_name::<T>());
+        Hold {
+            #[cfg(feature = "debug-deadlock")]
+            lock_id: &self.lock_id,
+            _instrument: PhantomData,
+        }
+    }
+    // Enters a span for the reset function, which is exited when the returned value is dropped.
+    #[cfg(feature = "tracing")]
+    #[inline]
+    pub(crate) fn enter_reset<T: ?Sized>(&self) -> tracing::span::EnteredSpan {
+        #[cfg(feature = "stats")]
+        self.resets.fetch_add(1, Ordering::Relaxed);
+        tracing::trace_span!(
+            target: "tempref",
+            "reset",
+            r#type = core::any::type_name::<T>(),
+            label = self.label
+        )
+        .entered()
+    }
+    #[cfg(not(feature = "tracing"))]
+    #[inline]
+    pub(crate) fn enter_reset<T: ?Sized>(&self) -> NoSpan {
+        #[cfg(feature = "stats")]
+        self.resets.fetch_add(1, Ordering::Relaxed);
+        NoSpan
+    }
+
+    #[cfg(any(feature = "tracing", feature = "stats"))]
+    fn at(&self) -> std::sync::MutexGuard<'_, Option<Instant>> {
+        self.acquired_at
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+// Returned by `Instrument::hold`.
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+pub(crate) struct Hold<'a> {
+    #[cfg(feature = "debug-deadlock")]
+    lock_id: &'a LockId,
+    _instrument: PhantomData<&'a Instrument>,
+}
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+impl Drop for Hold<'_> {
+    #[inline]
+    fn drop(&mut self) {
+        #[cfg(feature = "debug-deadlock")]
+        deadlock::released(self.lock_id);
+    }
+}
+
+// Stands for the span of the reset function without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoSpan;
+
+// The clone of a `Temp` starts with its own counters.
+impl Clone for Instrument {
+    fn clone(&self) -> Self {
+        #[allow(unused_mut)]
+        let mut instrument = Instrument::new();
+        #[cfg(feature = "tracing")]
+        {
+            instrument.label = self.label;
+        }
+        instrument
+    }
+}
@@ -1,18 +1,244 @@
 #![no_std]
+#![cfg_attr(feature = "allocator_api", feature(allocator_api))]
 //! This crate provides a type whose value remains unchanged even when accessed through a mutable reference.
 //! Some functions are compatible with `no_std` environments.
 //!
 //! | Module        | Characteristics                          | Feature Flags               |
 //! |---------------|-------------------------------------------|-----------------------------|
 //! | `unsync`      | `!Sync`, `!Send` type<br>Supports `no_std`| `default`, `all`, `no_std`, `unsync` |
+//! | `copy`        | `!Sync` type using `Cell` for `Copy` values<br>Supports `no_std`| `default`, `all`, `no_std`, `copy` |
+//! | `checkpoint`  | `!Sync` type restored to the latest of a stack of checkpoints<br>Requires `alloc`| `default`, `all`, `checkpoint` |
+//! | `history`     | `!Sync` type with undo and redo of committed states<br>Requires `alloc`| `default`, `all`, `history` |
+//! | `ghost`       | Branded type whose borrows are checked at compile time with a `GhostToken`<br>Supports `no_std`| `default`, `all`, `no_std`, `ghost` |
+//! | `dirty`       | `!Sync` slice type resetting only the ranges written through the guard<br>Requires `alloc`| `default`, `all`, `dirty` |
+//! | `secret`      | `!Sync` type whose guard only exposes the value inside a closure, wiping it on reset and redacting `Debug` | `default`, `all`, `secret` |
+//! | `uninit`      | `!Sync` buffer of `MaybeUninit` whose guard tracks the initialized elements and drops them on reset<br>Requires `alloc`| `default`, `all`, `uninit` |
+//! | `slab`        | `!Sync` keyed arena whose guard resets one slot, and which resets a value before freeing its slot<br>Requires `alloc`| `default`, `all`, `slab` |
+//! | `atomic`      | Lock-free `Sync`, `Send` types for integers and `bool`<br>Supports `no_std`| `default`, `all`, `no_std`, `atomic` |
+//! | `seqlock`     | `Sync`, `Send` type for read-mostly `Copy` values<br>Supports `no_std`| `default`, `all`, `no_std`, `seqlock` |
+//! | `triple`      | Wait-free triple buffer for one producer and one consumer<br>Supports `no_std`| `default`, `all`, `no_std`, `triple` |
+//! | `array`       | Spin-locked fixed-size array of `Copy` values reset to one value, usable in a `static`<br>Supports `no_std`| `default`, `all`, `no_std`, `array` |
+//! | `critical`    | `Sync` type for values shared with interrupt handlers, borrowed inside a critical section<br>Supports `no_std`, uses `critical-section`| `critical-section` |
 //! | `mutex`       | `Sync`, `Send` type using `std::sync::Mutex` | `default`, `all`, `mutex` |
 //! | `rwlock`      | `Sync`, `Send` type using `std::sync::RwLock` | `default`, `all`, `rwlock` |
+//! | `reentrant`   | `Sync`, `Send` type whose lock can be taken again by the same thread | `default`, `all`, `reentrant` |
+//! | `pool`        | Pool of `mutex` temps handed out to whichever is free | `default`, `all`, `pool` |
+//! | `sharded`     | `N` `mutex` temps routed by thread | `default`, `all`, `sharded` |
+//! | `map`         | `mutex` temps created on the first access of their key | `default`, `all`, `map` |
+//! | `per_thread`  | One `mutex` temp per rayon worker thread | `rayon` |
+//! | `scoped`      | Sharing a `mutex` temp with `crossbeam::scope` workers, and sending pool checkouts through channels | `crossbeam` |
+//! | `rcu`         | Lock-free `Arc` snapshots for readers, using `arc-swap` | `rcu` |
+//! | `mmap`        | Byte buffer in an anonymous mapping, reset by handing its pages back to the kernel<br>Requires unix, uses `libc`| `mmap` |
+//! | `testing`     | Mock `Temp` which records borrows and resets and fails on demand, for testing code generic over `TempAccess` | `testing` |
+//! | `registry`    | Process-wide registry of shared `Temp`s under string names, to look them up, reset them or list them | `registry` |
+//!
+//! The `alloc` feature (enabled by `default` and `all`) adds the APIs which require `alloc`, such as `Rc`-owned guards in `unsync`.
+//! The `serde` feature implements `Serialize` and `Deserialize` for `Temp`, which is serialized as its value alone.
+//! The `rkyv` feature implements `Archive` and `Serialize` for the guards of `Temp`, whose archive is the archived value itself.
+//! The `proptest` feature implements `Arbitrary` for `Temp<T, fn(&mut T)>`, which resets to `T::default()`.
+//! The `watch` feature adds `watch()` to the `Temp` of `mutex` and `rwlock`, which publishes the value before and after every reset to a [`watch::Watcher`].
+//! The `upgradable` feature adds `upgradable_read()` to `rwlock::Temp` and `downgrade()` to `rwlock::TempRef`. Every writer then passes through a second lock, which an upgradable reader or a downgraded guard holds to keep the other writers out, so it is enabled by neither `default` nor `all`.
+//! The `hooks` feature adds `with_hooks()` to the `Temp` of `unsync`, `mutex` and `rwlock`, which calls the functions of a [`TempHooks`] when a guard is created, when it is dropped and after every reset.
+//! The `tracing` feature emits `tracing` events with the target `tempref` when a guard of `unsync`, `mutex` or `rwlock` is created, when it is dropped with how long it was held, and when `mutex` or `rwlock` has to wait for the lock, and enters a span while the reset function runs. `with_label()` adds a label to them besides the type name.
+//! The `stats` feature adds `stats()` to the `Temp` of `unsync`, `mutex` and `rwlock`, which returns the `Stats` of how many guards were created, how many resets ran, how often `mutex` or `rwlock` had to wait for the lock, and how long the guards were held.
+//! The `allocator_api` feature (nightly only) adds `new_in()` and `with_capacity_in()` to `Temp<Vec<T, A>>` of `unsync`, `mutex` and `rwlock`, which create an empty `Vec` in the allocator `A` and clear it on reset.
+//! The `heapless` feature implements `Clear` for the collections of `heapless`, so that `Temp::clearing()` of `unsync`, `mutex` and `rwlock` can make a `Temp` which empties them in place on reset.
+//! Building with `RUSTFLAGS="--cfg loom"` makes `mutex` and `rwlock` use the locks and atomics of `loom`, so that code sharing a `Temp` across threads can be model-checked. Under `cfg(loom)`, `Temp::new()` is not `const`, and the methods which need a clock, a background thread or `RwLockWriteGuard::downgrade`, as well as `StaticTemp`, are not available.
+//! The `derive` feature adds `#[derive(TempProject)]`, which implements `TempProject` for a struct so that `rwlock::Temp::project_fields()` splits it into a `TempField` for each field, locked and reset on its own.
+//! The `debug_borrow` feature records where the value of an `unsync::Temp` was last borrowed and includes it in the panic message of a conflicting borrow.
+//! The `debug-deadlock` feature makes every blocking lock of `mutex` and `rwlock` check the order in which each thread acquires the locks, and panic with the cycle when two `Temp`s have been locked in both orders, which could deadlock. The checks only run with `debug_assertions`.
+//! The `std` feature (enabled by `mutex` and `rwlock`) lets the guards of `unsync` follow the [`UnwindPolicy`] of their `Temp` and implement `std::io::Read` and `std::io::Write`, and adds `with_max_age()` to `unsync::Temp`.
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+mod error;
+pub use error::Error;
+#[cfg_attr(
+    not(any(
+        feature = "unsync",
+        feature = "seqlock",
+        feature = "triple",
+        feature = "mutex",
+        feature = "rwlock",
+        feature = "reentrant",
+        feature = "critical-section"
+    )),
+    allow(dead_code)
+)]
+mod unwind;
+pub use unwind::UnwindPolicy;
+
+#[cfg(any(feature = "unsync", feature = "mutex", feature = "rwlock"))]
+mod hooks;
+#[cfg(any(feature = "unsync", feature = "mutex", feature = "rwlock"))]
+pub use hooks::TempHooks;
+#[cfg(all(
+    feature = "debug-deadlock",
+    any(feature = "unsync", feature = "mutex", feature = "rwlock")
+))]
+mod deadlock;
+#[cfg(any(feature = "unsync", feature = "mutex", feature = "rwlock"))]
+mod instrument;
+#[cfg(all(
+    feature = "stats",
+    any(feature = "unsync", feature = "mutex", feature = "rwlock")
+))]
+pub use instrument::Stats;
+#[cfg(any(
+    feature = "unsync",
+    feature = "mutex",
+    feature = "rwlock",
+    feature = "testing"
+))]
+mod access;
+#[cfg(any(
+    feature = "unsync",
+    feature = "mutex",
+    feature = "rwlock",
+    feature = "testing"
+))]
+pub use access::TempAccess;
+#[cfg(all(
+    feature = "alloc",
+    any(
+        feature = "unsync",
+        feature = "mutex",
+        feature = "rwlock",
+        feature = "testing"
+    )
+))]
+mod group;
+#[cfg(all(
+    feature = "alloc",
+    any(
+        feature = "unsync",
+        feature = "mutex",
+        feature = "rwlock",
+        feature = "testing"
+    )
+))]
+pub use group::{ResetErrors, ResetGroup, TempLike, reset_all};
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+mod clear;
+#[cfg(any(feature = "unsync", feature = "mutex", feature = "rwlock"))]
+mod clock;
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+pub use clear::Clear;
 
 #[cfg(feature = "unsync")]
 pub mod unsync;
 
+#[cfg(feature = "copy")]
+pub mod copy;
+
+#[cfg(feature = "checkpoint")]
+pub mod checkpoint;
+
+#[cfg(feature = "history")]
+pub mod history;
+
+#[cfg(feature = "ghost")]
+pub mod ghost;
+
+#[cfg(feature = "dirty")]
+pub mod dirty;
+
+#[cfg(feature = "uninit")]
+pub mod uninit;
+
+#[cfg(feature = "slab")]
+pub mod slab;
+
+#[cfg(feature = "secret")]
+pub mod secret;
+
+#[cfg(feature = "atomic")]
+pub mod atomic;
+
+#[cfg(feature = "seqlock")]
+pub mod seqlock;
+
+#[cfg(feature = "triple")]
+pub mod triple;
+
+#[cfg(feature = "array")]
+pub mod array;
+
+#[cfg(feature = "critical-section")]
+pub mod critical;
+
 #[cfg(feature = "mutex")]
 pub mod mutex;
 
 #[cfg(feature = "rwlock")]
 pub mod rwlock;
+#[cfg(feature = "rwlock")]
+pub use rwlock::TempProject;
+#[cfg(feature = "derive")]
+pub use tempref_derive::TempProject;
+
+#[cfg(feature = "reentrant")]
+pub mod reentrant;
+
+#[cfg(feature = "pool")]
+pub mod pool;
+
+#[cfg(feature = "sharded")]
+pub mod sharded;
+
+#[cfg(feature = "map")]
+pub mod map;
+
+#[cfg(feature = "rayon")]
+pub mod per_thread;
+
+#[cfg(feature = "crossbeam")]
+pub mod scoped;
+
+#[cfg(feature = "rcu")]
+pub mod rcu;
+
+#[cfg(all(unix, feature = "mmap"))]
+pub mod mmap;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "registry")]
+pub mod registry;
+
+#[cfg(all(feature = "watch", any(feature = "mutex", feature = "rwlock")))]
+pub mod watch;
+
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+mod multi;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+pub use multi::{LockAll, Lockable, lock_all};
+#[cfg(all(any(feature = "mutex", feature = "rwlock"), not(loom)))]
+mod resetter;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+mod spin;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+mod sync;
+#[cfg(feature = "mutex")]
+mod ticket;
+#[cfg(any(feature = "mutex", feature = "rwlock"))]
+mod timeout;
+#[cfg(all(any(feature = "mutex", feature = "rwlock"), not(loom)))]
+pub use resetter::ResetterHandle;
+
+/// The reset function of the `Default` and `From<T>` impls of `Temp<T, fn(&mut T)>`.
+#[allow(dead_code)]
+fn reset_to_default<T: Default>(value: &mut T) {
+    *value = T::default();
+}
+
+/// The reset function of `Temp::zeroing`. `fill(0)` on bytes is lowered to a single `memset`.
+#[allow(dead_code)]
+fn zero_bytes<T: AsMut<[u8]>>(value: &mut T) {
+    value.as_mut().fill(0);
+}
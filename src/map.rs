@@ -0,0 +1,181 @@
+//! A map of resettable values created on the first access of their key, built on
+//! [`mutex::Temp`](crate::mutex::Temp). This module requires std.
+
+extern crate std;
+
+use core::borrow::Borrow;
+use core::fmt::Debug;
+use core::hash::Hash;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::vec::Vec;
+
+use crate::Error;
+use crate::mutex::{OwnedTempRef, Temp};
+
+type PoisonResult<T> = Result<T, PoisonError<T>>;
+type TryLockResult<T> = Result<T, TryLockError<T>>;
+
+/// A map from keys to values protected by their own `Mutex`, each of which is created by the factory on the first
+/// access of its key and reset whenever its guard is dropped.
+///
+/// The map itself is only locked to look up or insert an entry, so a guard of one key never blocks another key.
+/// Every entry has a clone of the reset function.
+///
+/// # Examples
+/// ```
+/// use tempref::map::TempMap;
+///
+/// let buffers = TempMap::new(|_: &u32| Vec::with_capacity(1024), |v: &mut Vec<u8>| v.clear());
+///
+/// std::thread::scope(|s| {
+///     for connection in 0..4u32 {
+///         let buffers = &buffers;
+///         s.spawn(move || {
+///             let mut buf = buffers.lock(connection).unwrap();
+///             buf.extend_from_slice(b"HTTP/1.1 200 OK");
+///         });
+///     }
+/// });
+/// assert_eq!(buffers.len(), 4);
+/// assert!(buffers.get(&2).unwrap().lock().unwrap().is_empty());
+///
+/// buffers.retain(|connection, _| connection % 2 == 0);
+/// assert_eq!(buffers.len(), 2);
+/// ```
+pub struct TempMap<K, T: Send, F: FnMut(&mut T) + Send = fn(&mut T), C = fn(&K) -> T> {
+    entries: Mutex<HashMap<K, Arc<Temp<T, F>>>>,
+    factory: C,
+    reset: F,
+}
+impl<K, T, F, C> TempMap<K, T, F, C>
+where
+    K: Eq + Hash,
+    T: Send + 'static,
+    F: FnMut(&mut T) + Send + Clone + 'static,
+    C: Fn(&K) -> T,
+{
+    /// A constructor of an empty TempMap, whose values are created by `factory` and reset by clones of `reset`.
+    ///
+    /// `factory` runs while the map is locked, so it must not access the map.
+    pub fn new(factory: C, reset: F) -> Self {
+        TempMap {
+            entries: Mutex::new(HashMap::new()),
+            factory,
+            reset,
+        }
+    }
+    /// Acquires the value of `key`, creating it first if there is none.
+    /// If the value is locked, blocks the current thread until it is released.
+    ///
+    /// The guard keeps the value alive by itself, even if the entry is removed while it is held.
+    pub fn lock(&self, key: K) -> PoisonResult<OwnedTempRef<T, F>> {
+        self.entry(key).lock_arc()
+    }
+    /// Attempts to acquire the value of `key`, creating it first if there is none.
+    /// If the value is locked, then Err is returned.
+    pub fn try_lock(&self, key: K) -> TryLockResult<OwnedTempRef<T, F>> {
+        self.entry(key).try_lock_arc()
+    }
+    /// Returns the `Temp` of `key`, creating it first if there is none.
+    pub fn entry(&self, key: K) -> Arc<Temp<T, F>> {
+        self.entries()
+            .entry(key)
+            .or_insert_with_key(|key| Arc::new(Temp::new((self.factory)(key), self.reset.clone())))
+            .clone()
+    }
+}
+impl<K: Eq + Hash, T: Send, F: FnMut(&mut T) + Send, C> TempMap<K, T, F, C> {
+    /// Returns the `Temp` of `key`, or `None` if it has not been created.
+    pub fn get<Q>(&self, key: &Q) -> Option<Arc<Temp<T, F>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries().get(key).cloned()
+    }
+    /// Removes the entry of `key` and returns its `Temp`, or `None` if it has not been created.
+    pub fn remove<Q>(&self, key: &Q) -> Option<Arc<Temp<T, F>>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries().remove(key)
+    }
+    /// Returns true if the value of `key` has been created.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.entries().contains_key(key)
+    }
+    /// Returns the number of entries.
+    pub fn len(&self) -> usize {
+        self.entries().len()
+    }
+    /// Returns true if there is no entry.
+    pub fn is_empty(&self) -> bool {
+        self.entries().is_empty()
+    }
+    /// Invokes the reset function on the value of `key`, or returns `None` if it has not been created.
+    ///
+    /// This method waits for the value to be released, without locking the map meanwhile.
+    pub fn reset_key<Q>(&self, key: &Q) -> Option<Result<(), Error>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let temp = self.get(key)?;
+        Some(temp.reset().map_err(Error::from))
+    }
+    /// Invokes the reset function on every value, and returns the keys of those which could not be reset with
+    /// their errors.
+    ///
+    /// This method waits for each value to be released, without locking the map meanwhile.
+    pub fn reset_all(&self) -> Result<(), Vec<(K, Error)>>
+    where
+        K: Clone,
+    {
+        let temps: Vec<_> = self
+            .entries()
+            .iter()
+            .map(|(key, temp)| (key.clone(), temp.clone()))
+            .collect();
+        let failures: Vec<_> = temps
+            .into_iter()
+            .filter_map(|(key, temp)| temp.reset().err().map(|err| (key, err.into())))
+            .collect();
+        if failures.is_empty() {
+            Ok(())
+        } else {
+            Err(failures)
+        }
+    }
+    /// Keeps only the entries for which `f` returns true, dropping the others.
+    ///
+    /// The map is locked while `f` runs, so `f` must not access the map, and it should not block on the `Temp`.
+    /// A value whose guard is held when its entry is dropped is freed once the guard is dropped.
+    pub fn retain(&self, mut f: impl FnMut(&K, &Temp<T, F>) -> bool) {
+        self.entries().retain(|key, temp| f(key, temp));
+    }
+
+    // The map is never left half-updated by a panic, so a poisoned map is used as usual.
+    fn entries(&self) -> MutexGuard<'_, HashMap<K, Arc<Temp<T, F>>>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<K: Debug, T: Debug + Send, F: FnMut(&mut T) + Send, C> Debug for TempMap<K, T, F, C> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.entries.try_lock() {
+            Ok(entries) => f
+                .debug_struct("TempMap")
+                .field("entries", &*entries)
+                .finish(),
+            Err(_) => f
+                .debug_struct("TempMap")
+                .field("entries", &format_args!("<locked>"))
+                .finish(),
+        }
+    }
+}
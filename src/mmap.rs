@@ -0,0 +1,226 @@
+//! Single thread TempRef for huge byte buffers backed by an anonymous memory mapping. This module requires unix.
+//!
+//! Instead of writing zeroes, a reset hands the pages of [`TempBuffer`] back to the kernel, which maps zeroed pages
+//! on the next access. The reset takes constant time whatever the size of the buffer, and only the touched pages
+//! cost a page fault afterwards.
+//! On Linux and Android this is `madvise(MADV_DONTNEED)`. Other unix systems do not promise zeroed pages after it,
+//! so the range is mapped again in place with `MAP_FIXED` instead.
+
+extern crate std;
+
+use core::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    fmt::Debug,
+    ptr::{self, NonNull},
+};
+use std::io;
+
+/// A mutable reference wrapper from [`TempBuffer`].
+///
+/// When dropped, it discards the pages of the buffer, so that every byte reads as zero again.
+pub struct TempRef<'a> {
+    re: RefMut<'a, Map>,
+    temp: &'a TempBuffer,
+}
+impl<'a> TempRef<'a> {
+    /// Discards the pages of the buffer now.
+    pub fn reset(&mut self) {
+        self.temp.reset(&mut self.re);
+    }
+}
+impl<'a> core::ops::Deref for TempRef<'a> {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a> core::ops::DerefMut for TempRef<'a> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a> AsRef<[u8]> for TempRef<'a> {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+impl<'a> AsMut<[u8]> for TempRef<'a> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+impl<'a> Debug for TempRef<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef").field("len", &self.len()).finish()
+    }
+}
+impl<'a> Drop for TempRef<'a> {
+    fn drop(&mut self) {
+        self.temp.reset(&mut self.re);
+    }
+}
+
+/// A zeroed byte buffer in its own anonymous memory mapping, whose guards hand the pages back to the kernel when dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::mmap::TempBuffer;
+///
+/// let scratch = TempBuffer::new(1 << 30)?; // Only the touched pages take memory.
+/// {
+///     let mut guard = scratch.borrow_mut();
+///     guard[..5].copy_from_slice(b"hello");
+///     guard[1 << 29] = 1;
+/// }
+/// assert_eq!(scratch.borrow()[..5], [0; 5]);
+/// assert_eq!(scratch.borrow()[1 << 29], 0);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub struct TempBuffer {
+    map: RefCell<Map>,
+    generation: Cell<usize>,
+}
+impl TempBuffer {
+    /// Maps a zeroed buffer of `len` bytes.
+    ///
+    /// # Errors
+    /// Returns the error of `mmap` if the mapping fails.
+    pub fn new(len: usize) -> io::Result<Self> {
+        Ok(TempBuffer {
+            map: RefCell::new(Map::new(len)?),
+            generation: Cell::new(0),
+        })
+    }
+    /// Immutably borrows the buffer.
+    ///
+    /// # Panics
+    /// Panics if the buffer is currently mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, [u8]> {
+        Ref::map(self.map.borrow(), |map| &**map)
+    }
+    /// Mutably borrows the buffer. The returned [`TempRef`] discards the pages when dropped.
+    ///
+    /// # Panics
+    /// Panics if the buffer is currently borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> TempRef<'_> {
+        TempRef {
+            re: self.map.borrow_mut(),
+            temp: self,
+        }
+    }
+    /// Mutably borrows the buffer, or returns `None` if it is currently borrowed.
+    pub fn try_borrow_mut(&self) -> Option<TempRef<'_>> {
+        Some(TempRef {
+            re: self.map.try_borrow_mut().ok()?,
+            temp: self,
+        })
+    }
+    /// Returns the length of the buffer in bytes.
+    pub fn len(&self) -> usize {
+        self.map.borrow().len
+    }
+    /// Returns `true` if the buffer is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns how many times the pages have been discarded.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+
+    fn reset(&self, map: &mut Map) {
+        map.discard();
+        self.generation.set(self.generation.get() + 1);
+    }
+}
+impl Debug for TempBuffer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempBuffer")
+            .field("len", &self.map.borrow().len)
+            .finish()
+    }
+}
+
+// An anonymous private mapping of `len` bytes. An empty one maps nothing.
+struct Map {
+    ptr: NonNull<u8>,
+    len: usize,
+}
+// SAFETY: The mapping is owned exclusively, like a `Box<[u8]>`.
+unsafe impl Send for Map {}
+impl Map {
+    fn new(len: usize) -> io::Result<Self> {
+        if len == 0 {
+            return Ok(Map {
+                ptr: NonNull::dangling(),
+                len,
+            });
+        }
+        // SAFETY: A new mapping is requested without a hint, so no existing memory is affected.
+        let ptr = unsafe { map_anonymous(ptr::null_mut(), len, 0) }?;
+        Ok(Map { ptr, len })
+    }
+    fn discard(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+        #[cfg(any(target_os = "linux", target_os = "android"))]
+        // SAFETY: The range is exactly the mapping, and private anonymous pages read as zero after MADV_DONTNEED.
+        let discarded =
+            unsafe { libc::madvise(self.ptr.as_ptr().cast(), self.len, libc::MADV_DONTNEED) == 0 };
+        #[cfg(not(any(target_os = "linux", target_os = "android")))]
+        // SAFETY: The range is exactly the mapping, which `&mut self` keeps from being borrowed.
+        let discarded =
+            unsafe { map_anonymous(self.ptr.as_ptr().cast(), self.len, libc::MAP_FIXED) }.is_ok();
+        if !discarded {
+            // The kernel refused, e.g. out of memory. Zeroing by hand keeps the guarantee.
+            self.fill(0);
+        }
+    }
+}
+impl core::ops::Deref for Map {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        // SAFETY: `ptr` points to `len` readable bytes for as long as the mapping lives.
+        unsafe { core::slice::from_raw_parts(self.ptr.as_ptr(), self.len) }
+    }
+}
+impl core::ops::DerefMut for Map {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        // SAFETY: `ptr` points to `len` writable bytes, and `&mut self` makes the access exclusive.
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.as_ptr(), self.len) }
+    }
+}
+impl Drop for Map {
+    fn drop(&mut self) {
+        if self.len != 0 {
+            // SAFETY: The range is exactly the mapping, which is never accessed again.
+            unsafe { libc::munmap(self.ptr.as_ptr().cast(), self.len) };
+        }
+    }
+}
+
+// SAFETY: The caller makes sure that with `MAP_FIXED`, nothing else uses the memory at `addr..addr + len`.
+unsafe fn map_anonymous(
+    addr: *mut libc::c_void,
+    len: usize,
+    flags: i32,
+) -> io::Result<NonNull<u8>> {
+    // SAFETY: An anonymous mapping reads no file, and the caller upholds the rest.
+    let ptr = unsafe {
+        libc::mmap(
+            addr,
+            len,
+            libc::PROT_READ | libc::PROT_WRITE,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS | flags,
+            -1,
+            0,
+        )
+    };
+    if ptr == libc::MAP_FAILED {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(NonNull::new(ptr.cast()).expect("mmap returned a null pointer"))
+}
@@ -0,0 +1,116 @@
+//! Locking several `Temp`s at once. This module requires std.
+
+extern crate std;
+
+use std::sync::PoisonError;
+
+#[cfg(feature = "mutex")]
+use crate::mutex;
+#[cfg(feature = "rwlock")]
+use crate::rwlock;
+
+/// A reference to a `Temp` which can be exclusively locked by [`lock_all`].
+pub trait Lockable {
+    /// The value returned when the lock is acquired.
+    type Guard;
+
+    /// Returns the address used to decide the locking order.
+    fn address(&self) -> usize;
+    /// Acquires the lock, blocking the current thread until it is able to do so.
+    fn acquire(self) -> Self::Guard;
+}
+
+#[cfg(feature = "mutex")]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Lockable for &'a mutex::Temp<T, F> {
+    type Guard = Result<mutex::TempRef<'a, T, F>, PoisonError<mutex::TempRef<'a, T, F>>>;
+
+    fn address(&self) -> usize {
+        *self as *const mutex::Temp<T, F> as usize
+    }
+    fn acquire(self) -> Self::Guard {
+        self.lock()
+    }
+}
+
+#[cfg(feature = "rwlock")]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Lockable for &'a rwlock::Temp<T, F> {
+    type Guard = Result<rwlock::TempRef<'a, T, F>, PoisonError<rwlock::TempRef<'a, T, F>>>;
+
+    fn address(&self) -> usize {
+        *self as *const rwlock::Temp<T, F> as usize
+    }
+    fn acquire(self) -> Self::Guard {
+        self.write()
+    }
+}
+
+/// A tuple of [`Lockable`]s which can be locked by [`lock_all`].
+pub trait LockAll {
+    /// The tuple of values returned when every lock is acquired.
+    type Guards;
+
+    /// Acquires every lock in address order.
+    fn lock_all(self) -> Self::Guards;
+}
+
+macro_rules! impl_lock_all {
+    ($($ty:ident $var:ident $guard:ident $idx:tt),+) => {
+        impl<$($ty: Lockable),+> LockAll for ($($ty,)+) {
+            type Guards = ($($ty::Guard,)+);
+
+            fn lock_all(self) -> Self::Guards {
+                let mut order = [$((self.$idx.address(), $idx)),+];
+                order.sort_unstable();
+                assert!(
+                    order.windows(2).all(|pair| pair[0].0 != pair[1].0),
+                    "the same Temp was passed to lock_all more than once"
+                );
+                let ($($var,)+) = self;
+                $(let mut $var = Some($var);)+
+                $(let mut $guard = None;)+
+                for (_, index) in order {
+                    match index {
+                        $($idx => $guard = $var.take().map(Lockable::acquire),)+
+                        _ => unreachable!(),
+                    }
+                }
+                ($($guard.unwrap(),)+)
+            }
+        }
+    };
+}
+
+impl_lock_all!(A a ga 0, B b gb 1);
+impl_lock_all!(A a ga 0, B b gb 1, C c gc 2);
+impl_lock_all!(A a ga 0, B b gb 1, C c gc 2, D d gd 3);
+impl_lock_all!(A a ga 0, B b gb 1, C c gc 2, D d gd 3, E e ge 4);
+impl_lock_all!(A a ga 0, B b gb 1, C c gc 2, D d gd 3, E e ge 4, G g gg 5);
+
+/// Locks several `Temp`s of the `mutex` and `rwlock` modules without deadlocking.
+///
+/// The locks are always acquired in the order of the addresses of the `Temp`s, regardless of the order in the tuple,
+/// so two threads locking the same `Temp`s never wait for each other in a cycle.
+/// The results are returned in the order of the tuple, and each guard still resets its value when dropped.
+///
+/// # Panics
+/// Panics if the same `Temp` is passed more than once.
+///
+/// # Examples
+/// ```
+/// use tempref::{lock_all, mutex, rwlock};
+///
+/// let a = mutex::Temp::new(vec![0; 4], |v| v.fill(0));
+/// let b = rwlock::Temp::new(0, |n| *n = 0);
+///
+/// {
+///     let (a, b) = lock_all((&a, &b));
+///     let (mut a, mut b) = (a.unwrap(), b.unwrap());
+///     a.fill(1);
+///     *b = a.iter().sum();
+///     assert_eq!(*b, 4);
+/// }
+/// assert_eq!(*b.read().unwrap(), 0);
+/// ```
+pub fn lock_all<L: LockAll>(temps: L) -> L::Guards {
+    temps.lock_all()
+}
@@ -2,68 +2,596 @@
 
 extern crate std;
 
-use core::cell::UnsafeCell;
-use core::fmt::Debug;
-use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+use core::mem::{self, ManuallyDrop};
+use core::ptr::{self, NonNull};
+use core::time::Duration;
+use std::boxed::Box;
+use std::rc::Rc;
+use std::sync::{Arc, PoisonError, TryLockError, Weak};
+#[cfg(not(loom))]
+use std::sync::{OnceLock, WaitTimeoutResult};
+
+#[cfg(feature = "hooks")]
+use crate::TempHooks;
+use crate::clock::ResetClock;
+use crate::hooks::Hooks;
+use crate::instrument::Instrument;
+#[cfg(feature = "stats")]
+use crate::instrument::Stats;
+#[cfg(not(loom))]
+use crate::resetter::{self, ResetterHandle};
+use crate::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::sync::{Condvar, Mutex, MutexGuard};
+#[cfg(loom)]
+use crate::sync::{Poison, WaitWhile};
+use crate::ticket::TicketQueue;
+use crate::timeout::Waiters;
+#[cfg(feature = "watch")]
+use crate::watch::{self, Watcher};
+use crate::{UnwindPolicy, spin, unwind};
 
 type PoisonResult<T> = Result<T, PoisonError<T>>;
 type TryLockResult<T> = Result<T, TryLockError<T>>;
 
+// The predicate set with `with_reset_if()`.
+type ResetIf<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+// The value and its reset function, locked together so that only the holder of the lock can call the reset function.
+struct Slot<T, F> {
+    value: T,
+    reset: F,
+}
+
+// The reset function followed by the `on_reset` hook, inside the span of the reset.
+// It borrows the fields of the `Temp` it uses, so that `get_mut_reset` can call it while the value is borrowed.
+fn hooked_reset<'r, T, F: FnMut(&mut T)>(
+    reset: &'r mut F,
+    generation: &'r AtomicUsize,
+    clock: &'r ResetClock,
+    hooks: &'r Hooks<T>,
+    instrument: &'r Instrument,
+) -> impl FnMut(&mut T) + 'r {
+    move |value: &mut T| {
+        let _span = instrument.enter_reset::<T>();
+        reset(value);
+        generation.fetch_add(1, Ordering::Release);
+        clock.reset();
+        hooks.reset(value);
+    }
+}
+
+// The `Temp` of a lock which is held, which wakes up the threads in `lock_timeout` when it is dropped.
+// It is declared after the guard of the lock, so that it is dropped once the lock has been released.
+struct Notify<'a, T: Send, F: FnMut(&mut T) + Send>(&'a Temp<T, F>);
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for Notify<'a, T, F> {
+    type Target = Temp<T, F>;
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for Notify<'a, T, F> {
+    fn drop(&mut self) {
+        self.0.waiters.notify();
+    }
+}
+
 /// A mutable reference from `Temp<T, F>`.
 /// When it is dropped, it calls the reset function.
+///
+/// It holds the `MutexGuard` of the value and the reset function, which share one lock, and a reference to the `Temp`.
+/// The reset function is called directly, not through a pointer, so a closure without captures is inlined.
+///
+/// It is not pointer-sized: the `MutexGuard` of `std` is already a pointer and a poison flag, and `std` has no way
+/// to unlock a mutex without one, so the guard is one `MutexGuard` and one reference.
 pub struct TempRef<'a, T: Send, F: FnMut(&mut T) + Send> {
-    re: MutexGuard<'a, T>,
-    reset: &'a mut F,
+    re: MutexGuard<'a, Slot<T, F>>,
+    temp: Notify<'a, T, F>,
 }
 impl<'a, T: Send, F: FnMut(&mut T) + Send> TempRef<'a, T, F> {
-    fn new(re: MutexGuard<'a, T>, reset: &'a mut F) -> Self {
-        TempRef { re, reset }
+    fn new(mut re: MutexGuard<'a, Slot<T, F>>, temp: &'a Temp<T, F>) -> Self {
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re.value);
+        temp.instrument.acquire::<T>();
+        TempRef {
+            re,
+            temp: Notify(temp),
+        }
     }
     fn lock(temp: &'a Temp<T, F>) -> PoisonResult<Self> {
-        let reset = unsafe { &mut *temp.reset.get() };
-        match temp.value.lock() {
-            Ok(guard) => Ok(TempRef::new(guard, reset)),
-            Err(err) => Err(PoisonError::new(TempRef::new(err.into_inner(), reset))),
+        match temp.lock_value() {
+            Ok(guard) => Ok(TempRef::new(guard, temp)),
+            Err(err) => Err(PoisonError::new(TempRef::new(err.into_inner(), temp))),
         }
     }
     fn try_lock(temp: &'a Temp<T, F>) -> TryLockResult<Self> {
-        let reset = unsafe { &mut *temp.reset.get() };
         match temp.value.try_lock() {
-            Ok(guard) => Ok(TempRef::new(guard, reset)),
+            Ok(guard) => Ok(TempRef::new(guard, temp)),
             Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new(
-                TempRef::new(err.into_inner(), reset),
+                TempRef::new(err.into_inner(), temp),
             ))),
             Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
         }
     }
 
     /// Invokes the reset function on the internal value.
+    ///
+    /// If the reset function panics, the `Temp` is marked as [reset-poisoned](Temp::is_reset_poisoned).
     pub fn reset(&mut self) {
-        (self.reset)(&mut self.re)
+        self.temp.reset_or_poison(&mut self.re);
+    }
+    /// Makes a [`MappedTempRef`] for a component of the locked data.
+    ///
+    /// The reset function still runs on the whole value when the returned reference is dropped.
+    pub fn map<U: ?Sized, M: FnOnce(&mut T) -> &mut U>(
+        mut orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, U, F> {
+        let value = NonNull::from(f(&mut orig));
+        MappedTempRef::new(value, Owner::Unique(orig))
+    }
+    /// Splits a `TempRef` into multiple [`MappedTempRef`]s for different components of the locked data.
+    ///
+    /// The reset function runs on the whole value once both returned references are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, M: FnOnce(&mut T) -> (&mut U, &mut V)>(
+        mut orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, U, F>, MappedTempRef<'a, T, V, F>) {
+        let (a, b) = f(&mut orig);
+        let (a, b) = (NonNull::from(a), NonNull::from(b));
+        let owner = Rc::new(orig);
+        (
+            MappedTempRef::new(a, Owner::Shared(owner.clone())),
+            MappedTempRef::new(b, Owner::Shared(owner)),
+        )
+    }
+    /// Moves the value out of the guard, leaving `T::default()` in its place, without cloning it.
+    ///
+    /// If `reset` is true, the reset function runs on the default value when the guard is dropped as usual.
+    /// Otherwise the default value is left as it is.
+    pub fn take(orig: TempRef<'a, T, F>, reset: bool) -> T
+    where
+        T: Default,
+    {
+        let mut orig = orig;
+        let value = mem::take(&mut *orig);
+        if !reset {
+            let (re, temp) = orig.into_parts();
+            temp.hooks.release(&re.value);
+            temp.instrument.release::<T>();
+            drop(re);
+        }
+        value
+    }
+
+    #[cfg(feature = "crossbeam")]
+    pub(crate) fn temp(&self) -> &'a Temp<T, F> {
+        self.temp.0
+    }
+    fn into_parts(self) -> (MutexGuard<'a, Slot<T, F>>, Notify<'a, T, F>) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `re` and `temp` are moved out exactly once.
+        unsafe { (ptr::read(&this.re), ptr::read(&this.temp)) }
     }
 }
 impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for TempRef<'a, T, F> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
-        &self.re
+        &self.re.value
     }
 }
 impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::DerefMut for TempRef<'a, T, F> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.re
+        &mut self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsRef<T> for TempRef<'a, T, F> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsMut<T> for TempRef<'a, T, F> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::borrow::Borrow<T> for TempRef<'a, T, F> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::borrow::BorrowMut<T> for TempRef<'a, T, F> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T: core::fmt::Write + Send, F: FnMut(&mut T) + Send> core::fmt::Write
+    for TempRef<'a, T, F>
+{
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.re.value.write_str(s)
+    }
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        self.re.value.write_char(c)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        self.re.value.write_fmt(args)
+    }
+}
+impl<'a, T: std::io::Read + Send, F: FnMut(&mut T) + Send> std::io::Read for TempRef<'a, T, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.re.value.read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        self.re.value.read_vectored(bufs)
+    }
+    fn read_to_end(&mut self, buf: &mut std::vec::Vec<u8>) -> std::io::Result<usize> {
+        self.re.value.read_to_end(buf)
+    }
+    fn read_to_string(&mut self, buf: &mut std::string::String) -> std::io::Result<usize> {
+        self.re.value.read_to_string(buf)
+    }
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.re.value.read_exact(buf)
+    }
+}
+impl<'a, T: std::io::Write + Send, F: FnMut(&mut T) + Send> std::io::Write for TempRef<'a, T, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.re.value.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.re.value.write_vectored(bufs)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.re.value.flush()
+    }
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.re.value.write_all(buf)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> std::io::Result<()> {
+        self.re.value.write_fmt(args)
     }
 }
 impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for TempRef<'a, T, F> {
     fn drop(&mut self) {
-        (self.reset)(&mut self.re);
+        self.temp.release(&mut self.re);
     }
 }
 impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for TempRef<'a, T, F> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("TempRef").field("value", &self.re).finish()
+        f.debug_struct("TempRef")
+            .field("value", &self.re.value)
+            .finish()
+    }
+}
+impl<'a, T: Display + Send, F: FnMut(&mut T) + Send> Display for TempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// Archives the value alone, so `Archived<TempRef<'_, T, F>>` is `Archived<T>`.
+///
+/// The guard holds the lock, so the value cannot change between serializing and resolving it. The archive deserializes back into a `T`.
+#[cfg(feature = "rkyv")]
+impl<'a, T: rkyv::Archive + Send, F: FnMut(&mut T) + Send> rkyv::Archive for TempRef<'a, T, F> {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        (**self).resolve(resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<'a, T, F, S> rkyv::Serialize<S> for TempRef<'a, T, F>
+where
+    T: rkyv::Serialize<S> + Send,
+    F: FnMut(&mut T) + Send,
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// A mutable reference to a component of a value locked from [`Temp<T, F>`].
+///
+/// Created by [`TempRef::map`] and [`TempRef::map_split`].
+/// The reset function runs on the whole value when the last reference mapped from the same `TempRef` is dropped.
+pub struct MappedTempRef<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> {
+    value: NonNull<U>,
+    owner: Owner<'a, T, F>,
+    marker: PhantomData<&'a mut U>,
+}
+enum Owner<'a, T: Send, F: FnMut(&mut T) + Send> {
+    Unique(TempRef<'a, T, F>),
+    Shared(Rc<TempRef<'a, T, F>>),
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> MappedTempRef<'a, T, U, F> {
+    fn new(value: NonNull<U>, owner: Owner<'a, T, F>) -> Self {
+        MappedTempRef {
+            value,
+            owner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Makes a new `MappedTempRef` for a component of the locked data.
+    pub fn map<V: ?Sized, M: FnOnce(&mut U) -> &mut V>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, V, F> {
+        let MappedTempRef {
+            mut value, owner, ..
+        } = orig;
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        let value = NonNull::from(f(unsafe { value.as_mut() }));
+        MappedTempRef::new(value, owner)
+    }
+    /// Splits a `MappedTempRef` into multiple `MappedTempRef`s for different components of the locked data.
+    pub fn map_split<V: ?Sized, W: ?Sized, M: FnOnce(&mut U) -> (&mut V, &mut W)>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, V, F>, MappedTempRef<'a, T, W, F>) {
+        let MappedTempRef {
+            mut value, owner, ..
+        } = orig;
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        let (a, b) = f(unsafe { value.as_mut() });
+        let (a, b) = (NonNull::from(a), NonNull::from(b));
+        let owner = match owner {
+            Owner::Unique(guard) => Rc::new(guard),
+            Owner::Shared(guard) => guard,
+        };
+        (
+            MappedTempRef::new(a, Owner::Shared(owner.clone())),
+            MappedTempRef::new(b, Owner::Shared(owner)),
+        )
+    }
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> core::ops::Deref
+    for MappedTempRef<'a, T, U, F>
+{
+    type Target = U;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        unsafe { self.value.as_ref() }
+    }
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> core::ops::DerefMut
+    for MappedTempRef<'a, T, U, F>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        unsafe { self.value.as_mut() }
+    }
+}
+impl<'a, T: Send, U: Debug + ?Sized, F: FnMut(&mut T) + Send> Debug for MappedTempRef<'a, T, U, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MappedTempRef")
+            .field("value", &&**self)
+            .finish()
+    }
+}
+impl<'a, T: Send, U: Display + ?Sized, F: FnMut(&mut T) + Send> Display
+    for MappedTempRef<'a, T, U, F>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A mutable reference from an `Arc<Temp<T, F>>`.
+///
+/// Created by [`Temp::lock_arc`]. Unlike [`TempRef`], it keeps the `Temp` alive by itself and has no lifetime,
+/// so it can be stored in `'static` contexts.
+/// When dropped, it calls the reset function before releasing the `Arc`.
+///
+/// It is not `Send`, and a `Send` version is not provided: it holds a guard of `std`, which has to be released by the
+/// thread that acquired the lock, since some platforms unlock a lock only from its owning thread. To move the work to
+/// another thread, send a clone of the `Arc` and call `lock_arc()` there.
+///
+/// Moving it into a spawned thread does not compile:
+/// ```compile_fail
+/// use std::sync::Arc;
+/// use tempref::mutex::Temp;
+///
+/// let workspace = Arc::new(Temp::new(0, |n| *n = 0));
+/// let guard = workspace.lock_arc().unwrap();
+/// std::thread::spawn(move || drop(guard));
+/// ```
+pub struct OwnedTempRef<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> {
+    // Declared before `temp` so that it is dropped first.
+    guard: TempRef<'static, T, F>,
+    temp: Arc<Temp<T, F>>,
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> OwnedTempRef<T, F> {
+    fn lock(temp: &Arc<Temp<T, F>>) -> PoisonResult<Self> {
+        let temp = Arc::clone(temp);
+        // SAFETY: the guard borrows the `Temp` owned by `temp`, which is dropped after the guard.
+        let target: &'static Temp<T, F> = unsafe { &*Arc::as_ptr(&temp) };
+        match TempRef::lock(target) {
+            Ok(guard) => Ok(OwnedTempRef { guard, temp }),
+            Err(err) => Err(PoisonError::new(OwnedTempRef {
+                guard: err.into_inner(),
+                temp,
+            })),
+        }
+    }
+    fn try_lock(temp: &Arc<Temp<T, F>>) -> TryLockResult<Self> {
+        let temp = Arc::clone(temp);
+        // SAFETY: the guard borrows the `Temp` owned by `temp`, which is dropped after the guard.
+        let target: &'static Temp<T, F> = unsafe { &*Arc::as_ptr(&temp) };
+        match TempRef::try_lock(target) {
+            Ok(guard) => Ok(OwnedTempRef { guard, temp }),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(OwnedTempRef {
+                    guard: err.into_inner(),
+                    temp,
+                })))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Invokes the reset function on the internal value.
+    pub fn reset(&mut self) {
+        self.guard.reset();
+    }
+    /// Returns the `Arc` which owns the locked `Temp`.
+    pub fn temp(&self) -> &Arc<Temp<T, F>> {
+        &self.temp
+    }
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> core::ops::Deref for OwnedTempRef<T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> core::ops::DerefMut
+    for OwnedTempRef<T, F>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+impl<T: Debug + Send + 'static, F: FnMut(&mut T) + Send + 'static> Debug for OwnedTempRef<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OwnedTempRef")
+            .field("value", &*self.guard)
+            .finish()
+    }
+}
+impl<T: Display + Send + 'static, F: FnMut(&mut T) + Send + 'static> Display
+    for OwnedTempRef<T, F>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A transactional mutable reference from [`Temp<T, F>`].
+///
+/// Changes made through it are kept only if [`Self::commit`] is called.
+/// Otherwise the reset function is called when it is dropped,
+/// including while unwinding from a panic unless the `Temp` uses [`UnwindPolicy::Skip`].
+pub struct Transaction<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: MutexGuard<'a, Slot<T, F>>,
+    temp: Notify<'a, T, F>,
+    committed: bool,
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Transaction<'a, T, F> {
+    fn new(mut re: MutexGuard<'a, Slot<T, F>>, temp: &'a Temp<T, F>) -> Self {
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re.value);
+        temp.instrument.acquire::<T>();
+        Transaction {
+            re,
+            temp: Notify(temp),
+            committed: false,
+        }
+    }
+    fn lock(temp: &'a Temp<T, F>) -> PoisonResult<Self> {
+        match temp.lock_value() {
+            Ok(guard) => Ok(Transaction::new(guard, temp)),
+            Err(err) => Err(PoisonError::new(Transaction::new(err.into_inner(), temp))),
+        }
+    }
+
+    /// Ends the transaction, keeping the changes without calling the reset function.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+    /// Ends the transaction, calling the reset function immediately.
+    pub fn rollback(self) {}
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for Transaction<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::DerefMut for Transaction<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for Transaction<'a, T, F> {
+    fn drop(&mut self) {
+        if self.committed {
+            self.temp.hooks.release(&self.re.value);
+            self.temp.instrument.release::<T>();
+        } else {
+            self.temp.reset_on_drop(&mut self.re);
+        }
+    }
+}
+impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for Transaction<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("value", &self.re.value)
+            .field("committed", &self.committed)
+            .finish()
+    }
+}
+impl<'a, T: Display + Send, F: FnMut(&mut T) + Send> Display for Transaction<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// A shared reference from `Temp<T, F>`, created by [`Temp::lock_read`].
+/// It holds the lock like a [`TempRef`], but it cannot change the value, so it doesn't reset it when dropped.
+pub struct ReadRef<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: MutexGuard<'a, Slot<T, F>>,
+    _notify: Notify<'a, T, F>,
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for ReadRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsRef<T> for ReadRef<'a, T, F> {
+    fn as_ref(&self) -> &T {
+        &self.re.value
+    }
+}
+impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for ReadRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReadRef")
+            .field("value", &self.re.value)
+            .finish()
     }
 }
+impl<'a, T: Display + Send, F: FnMut(&mut T) + Send> Display for ReadRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// Archives the value alone, so `Archived<ReadRef<'_, T, F>>` is `Archived<T>`.
+///
+/// The guard holds the lock, so the value cannot change between serializing and resolving it. The archive deserializes back into a `T`.
+#[cfg(feature = "rkyv")]
+impl<'a, T: rkyv::Archive + Send, F: FnMut(&mut T) + Send> rkyv::Archive for ReadRef<'a, T, F> {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
 
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        (**self).resolve(resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<'a, T, F, S> rkyv::Serialize<S> for ReadRef<'a, T, F>
+where
+    T: rkyv::Serialize<S> + Send,
+    F: FnMut(&mut T) + Send,
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
 /// A value protected by a `Mutex` that ensures its mutable reference is always reset when dropped.
 ///
 /// `Temp<T, F>` holds a value of type `T` inside a `Mutex`, together with a reset
@@ -91,16 +619,53 @@ impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for TempRef<'a, T, F> {
 /// }
 /// assert_eq!(*workspace.lock().unwrap(), vec![0;128]);
 /// ```
-pub struct Temp<T: Send, F: FnMut(&mut T) + Send> {
-    value: Mutex<T>,
-    reset: UnsafeCell<F>,
+pub struct Temp<T: Send, F: FnMut(&mut T) + Send = fn(&mut T)> {
+    value: Mutex<Slot<T, F>>,
+    unwind: UnwindPolicy,
+    reset_poisoned: AtomicBool,
+    generation: AtomicUsize,
+    hooks: Hooks<T>,
+    instrument: Instrument,
+    clock: ResetClock,
+    // Shared by the clones of the `Temp`.
+    reset_if: Option<ResetIf<T>>,
+    // Only allocated for a fair `Temp`, so that an unfair one stays small.
+    fair: Option<Box<TicketQueue>>,
+    waiters: Waiters,
 }
 impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// A constructor of Temp<T, F>.
+    #[cfg(not(loom))]
     pub const fn new(value: T, reset: F) -> Self {
         Temp {
-            value: Mutex::new(value),
-            reset: UnsafeCell::new(reset),
+            value: Mutex::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            fair: None,
+            waiters: Waiters::new(),
+        }
+    }
+    /// A constructor of Temp<T, F>.
+    ///
+    /// It is not `const` under `cfg(loom)`, since loom's primitives cannot be created in a constant.
+    #[cfg(loom)]
+    pub fn new(value: T, reset: F) -> Self {
+        Temp {
+            value: Mutex::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            fair: None,
+            waiters: Waiters::new(),
         }
     }
     /// A constructor of Temp<T, F>.
@@ -110,10 +675,276 @@ impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     pub fn new_with(mut value: T, mut reset: F) -> Self {
         reset(&mut value);
         Temp {
-            value: Mutex::new(value),
-            reset: UnsafeCell::new(reset),
+            value: Mutex::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            fair: None,
+            waiters: Waiters::new(),
+        }
+    }
+    /// Replaces the reset function.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be using the old one.
+    pub fn set_reset(&mut self, reset: F) {
+        self.value
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .reset = reset;
+    }
+    /// Consumes the Temp, returning a new one with the same value and a reset function of another type.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    pub fn with_reset<G: FnMut(&mut T) + Send>(self, reset: G) -> Temp<T, G> {
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
+        } = self;
+        let Slot { value, .. } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: Mutex::new(Slot { value, reset }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
         }
     }
+    /// Consumes the Temp, returning a new one whose reset function calls the current one followed by `extra`.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    pub fn chain_reset<G: FnMut(&mut T) + Send>(
+        self,
+        mut extra: G,
+    ) -> Temp<T, impl FnMut(&mut T) + Send> {
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
+        } = self;
+        let Slot { value, mut reset } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: Mutex::new(Slot {
+                value,
+                reset: move |value: &mut T| {
+                    reset(value);
+                    extra(value);
+                },
+            }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
+        }
+    }
+    /// Consumes the Temp, returning a new one which publishes a [`Snapshot`](crate::watch::Snapshot)
+    /// of the value before and after every call of the reset function to the returned [`Watcher`].
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::{mutex, watch::Snapshot};
+    ///
+    /// let (workspace, mut watcher) = mutex::Temp::new(0, |n| *n = 0).watch();
+    /// *workspace.lock().unwrap() = 7;
+    /// assert_eq!(*watcher.changed(), Snapshot { dirty: 7, reset: 0 });
+    /// ```
+    #[cfg(feature = "watch")]
+    pub fn watch(self) -> (Temp<T, impl FnMut(&mut T) + Send>, Watcher<T>)
+    where
+        T: Clone + Sync,
+    {
+        let (publisher, watcher) = watch::channel();
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
+        } = self;
+        let Slot { value, mut reset } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        let temp = Temp {
+            value: Mutex::new(Slot {
+                value,
+                reset: move |value: &mut T| {
+                    let dirty = value.clone();
+                    reset(value);
+                    publisher.publish(dirty, value.clone());
+                },
+            }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            fair,
+            waiters,
+        };
+        (temp, watcher)
+    }
+    /// Sets what the guards do with the reset function when they are dropped during a panic.
+    /// The default is [`UnwindPolicy::Reset`].
+    pub fn with_unwind_policy(mut self, policy: UnwindPolicy) -> Self {
+        self.unwind = policy;
+        self
+    }
+    /// Sets the label of the `tracing` events of this `Temp`, which are recorded with its type name.
+    #[cfg(feature = "tracing")]
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.instrument.set_label(label);
+        self
+    }
+    /// Sets how long the value may go without a reset.
+    ///
+    /// Once it is older than `max_age`, the next guard which resets the value calls the reset function before it is returned.
+    /// This is only checked when such a guard is created, so no background thread is involved.
+    #[cfg(feature = "std")]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.clock.set_max_age(max_age);
+        self
+    }
+    /// Sets a predicate deciding whether a dropped guard calls the reset function, so a guard which left the value clean
+    /// doesn't reset it. Explicit resets and rolled back transactions don't ask it.
+    ///
+    /// The predicate is a `Fn`, which the clones of the `Temp` share without a lock of its own.
+    /// A predicate which keeps state has to keep it in atomics or a lock of its own.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let buffer = Temp::new(Vec::new(), |v: &mut Vec<u8>| *v = Vec::new());
+    /// let limit = 1024;
+    /// let buffer = buffer.with_reset_if(move |v| v.capacity() > limit);
+    /// buffer.lock().unwrap().extend_from_slice(b"small");
+    /// assert_eq!(buffer.lock().unwrap().as_slice(), b"small"); // Kept, since it stayed small.
+    /// ```
+    pub fn with_reset_if(mut self, reset_if: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.reset_if = Some(Arc::new(reset_if));
+        self
+    }
+    /// Sets the minimum interval between the resets of dropped guards.
+    ///
+    /// A guard dropped less than `interval` after the last reset leaves the value as it is, and the reset is postponed
+    /// until a guard which resets the value is created after the interval has passed.
+    /// So a guard may see what an earlier guard left. Explicit resets, rolled back transactions and guards dropped
+    /// during a panic always reset.
+    #[cfg(feature = "std")]
+    pub fn with_min_reset_interval(mut self, interval: Duration) -> Self {
+        self.clock.set_min_interval(interval);
+        self
+    }
+    /// Sets whether the blocking locks are granted in the order they were requested.
+    ///
+    /// `Mutex` makes no fairness promise, so a thread which keeps locking the value can starve the others.
+    /// A fair `Temp` hands out tickets to every method which blocks on the mutex, from [`Self::lock`] and
+    /// [`Self::transaction`] to [`Self::reset`], [`Self::set`], [`Self::swap`] and [`Self::wait_while`], and only the
+    /// thread whose turn it is waits on the mutex. The `try_` methods, [`Self::lock_timeout`] and the spinning of
+    /// [`Self::lock_spin`] don't queue up and can still take a free lock ahead of the waiting threads. The default is
+    /// unfair, which is faster under low contention.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let jobs = Temp::new(Vec::new(), |v: &mut Vec<u32>| v.clear()).with_fairness(true);
+    /// assert!(jobs.is_fair());
+    /// std::thread::scope(|s| {
+    ///     for n in 0..4 {
+    ///         let jobs = &jobs;
+    ///         s.spawn(move || jobs.lock().unwrap().push(n));
+    ///     }
+    /// });
+    /// assert_eq!(jobs.generation(), 4);
+    /// ```
+    pub fn with_fairness(mut self, fair: bool) -> Self {
+        self.fair = fair.then(|| Box::new(TicketQueue::new()));
+        self
+    }
+    /// Returns whether the blocking locks are granted in order, as set with [`Self::with_fairness`].
+    pub fn is_fair(&self) -> bool {
+        self.fair.is_some()
+    }
+    /// Returns the minimum interval set with [`Self::with_min_reset_interval`].
+    #[cfg(feature = "std")]
+    pub fn min_reset_interval(&self) -> Option<Duration> {
+        self.clock.min_interval()
+    }
+    /// Returns the maximum age set with [`Self::with_max_age`].
+    #[cfg(feature = "std")]
+    pub fn max_age(&self) -> Option<Duration> {
+        self.clock.max_age()
+    }
+    /// Returns the counters of the guards of this `Temp`.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.instrument.stats()
+    }
+    /// Sets the [`TempHooks`] called by the guards and the reset function.
+    #[cfg(feature = "hooks")]
+    pub fn with_hooks<H: TempHooks<T>>(mut self) -> Self {
+        self.hooks = Hooks::of::<H>();
+        self
+    }
+    /// Returns what the guards do with the reset function when they are dropped during a panic.
+    pub fn unwind_policy(&self) -> UnwindPolicy {
+        self.unwind
+    }
+    /// Returns how many times the reset function has completed on the value.
+    ///
+    /// A change of the generation tells that anything derived from the value may be stale.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.load(Ordering::Acquire)
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.store(false, Ordering::Release);
+    }
     /// Creates `TempRef`.
     /// Automatically resets itself when dropped.
     /// Acquires a mutex, blocking the current thread until it is able to do so.
@@ -125,9 +956,544 @@ impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     pub fn try_lock<'a>(&'a self) -> TryLockResult<TempRef<'a, T, F>> {
         TempRef::try_lock(self)
     }
+    /// Acquires the mutex only to read the value, blocking the current thread until it is able to do so.
+    /// The returned [`ReadRef`] doesn't reset the value when dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let counter = Temp::new(0, |n| *n = 0);
+    /// assert_eq!(*counter.lock_read().unwrap(), 0);
+    /// assert_eq!(counter.generation(), 0);
+    /// ```
+    pub fn lock_read<'a>(&'a self) -> PoisonResult<ReadRef<'a, T, F>> {
+        match self.lock_value() {
+            Ok(re) => Ok(ReadRef {
+                re,
+                _notify: Notify(self),
+            }),
+            Err(err) => Err(PoisonError::new(ReadRef {
+                re: err.into_inner(),
+                _notify: Notify(self),
+            })),
+        }
+    }
+    /// Attempts to acquire the mutex only to read the value.
+    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, [`ReadRef`] is returned.
+    pub fn try_lock_read<'a>(&'a self) -> TryLockResult<ReadRef<'a, T, F>> {
+        match self.value.try_lock() {
+            Ok(re) => Ok(ReadRef {
+                re,
+                _notify: Notify(self),
+            }),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(ReadRef {
+                    re: err.into_inner(),
+                    _notify: Notify(self),
+                })))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+    /// Returns a clone of the value, holding the mutex only while it is cloned.
+    /// The reset function is not called, and no guard is handed out.
+    ///
+    /// If the mutex is poisoned, the clone is returned inside the `PoisonError`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(vec![1, 2], |v: &mut Vec<i32>| v.clear());
+    /// assert_eq!(workspace.read_cloned().unwrap(), [1, 2]);
+    /// assert_eq!(workspace.generation(), 0);
+    /// ```
+    pub fn read_cloned(&self) -> PoisonResult<T>
+    where
+        T: Clone,
+    {
+        let _notify = Notify(self);
+        match self.lock_value() {
+            Ok(re) => Ok(re.value.clone()),
+            Err(err) => Err(PoisonError::new(err.into_inner().value.clone())),
+        }
+    }
+    /// Attempts to acquire this lock, blocking the current thread for at most `timeout`.
+    /// If the lock could not be acquired in time, then `Err(TryLockError::WouldBlock)` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::TryLockError;
+    /// use std::time::Duration;
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(0, |n| *n = 0);
+    /// let _guard = workspace.lock().unwrap();
+    /// assert!(matches!(
+    ///     workspace.lock_timeout(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    /// ```
+    #[cfg(not(loom))]
+    pub fn lock_timeout<'a>(&'a self, timeout: Duration) -> TryLockResult<TempRef<'a, T, F>> {
+        self.waiters.retry_until(timeout, || self.try_lock())
+    }
+    /// Acquires this lock, spinning with exponential backoff before blocking the current thread.
+    ///
+    /// At most `max_spins` spins are spent retrying [`Self::try_lock`], which avoids parking the thread
+    /// when the lock is only held for a very short time. If it is still locked, this falls back to [`Self::lock`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let counters = Temp::new([0u64; 8], |c| c.fill(0));
+    /// std::thread::scope(|s| {
+    ///     for i in 0..4 {
+    ///         let counters = &counters;
+    ///         s.spawn(move || counters.lock_spin(1000).unwrap()[i] += 1);
+    ///     }
+    /// });
+    /// assert_eq!(counters.generation(), 4);
+    /// ```
+    pub fn lock_spin<'a>(&'a self, max_spins: u32) -> PoisonResult<TempRef<'a, T, F>> {
+        match spin::spin(max_spins, || self.try_lock()) {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Err(err),
+            Err(TryLockError::WouldBlock) => self.lock(),
+        }
+    }
+    /// Attempts to acquire this lock, spinning at most `max_spins` times with exponential backoff.
+    /// If the lock is still held after that, then `Err(TryLockError::WouldBlock)` is returned.
+    pub fn try_lock_spin<'a>(&'a self, max_spins: u32) -> TryLockResult<TempRef<'a, T, F>> {
+        spin::spin(max_spins, || self.try_lock())
+    }
+    /// Acquires a mutex like [`Self::lock`], recovering from poisoning.
+    ///
+    /// Since the value is reset on every release, a panic while it was locked leaves nothing worth protecting.
+    /// If the mutex is poisoned, the reset function is called and the poison is cleared before the guard is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(0, |n| *n = 0);
+    /// std::thread::scope(|s| {
+    ///     let result = s.spawn(|| {
+    ///         let mut guard = workspace.lock().unwrap();
+    ///         *guard = 1;
+    ///         panic!("poisons the mutex");
+    ///     });
+    ///     assert!(result.join().is_err());
+    /// });
+    /// assert!(workspace.lock().is_err());
+    ///
+    /// assert_eq!(*workspace.lock_unpoisoned(), 0);
+    /// assert!(workspace.lock().is_ok());
+    /// ```
+    pub fn lock_unpoisoned<'a>(&'a self) -> TempRef<'a, T, F> {
+        self.lock()
+            .unwrap_or_else(|err| self.recover(err.into_inner()))
+    }
+    /// Attempts to acquire this lock like [`Self::try_lock`], recovering from poisoning.
+    /// If the lock could not be acquired at this time, then `None` is returned.
+    pub fn try_lock_unpoisoned<'a>(&'a self) -> Option<TempRef<'a, T, F>> {
+        match self.try_lock() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(err)) => Some(self.recover(err.into_inner())),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    fn recover<'a>(&'a self, mut guard: TempRef<'a, T, F>) -> TempRef<'a, T, F> {
+        guard.reset();
+        self.value.clear_poison();
+        guard
+    }
+    /// Acquires the mutex for the duration of `f`, returning its result.
+    /// The reset function is called after `f` returns, so the lock is never held longer than the closure.
+    ///
+    /// If the mutex is poisoned, `f` is still called and its result is returned inside the `PoisonError`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(Vec::new(), |v| v.clear());
+    ///
+    /// let sum: i32 = workspace
+    ///     .with_lock(|v| {
+    ///         v.extend([1, 2, 3]);
+    ///         v.iter().sum()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(sum, 6);
+    /// assert!(workspace.with_lock(|v| v.is_empty()).unwrap());
+    /// ```
+    pub fn with_lock<R, C: FnOnce(&mut T) -> R>(&self, f: C) -> PoisonResult<R> {
+        match self.lock() {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(err) => Err(PoisonError::new(f(&mut err.into_inner()))),
+        }
+    }
+    /// Attempts to acquire the mutex for the duration of `f`, returning its result.
+    /// If the lock could not be acquired at this time, then `f` is not called and Err is returned.
+    pub fn try_with_lock<R, C: FnOnce(&mut T) -> R>(&self, f: C) -> TryLockResult<R> {
+        match self.try_lock() {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new(f(
+                &mut err.into_inner(),
+            )))),
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+    /// Acquires the mutex and blocks on `condvar` while `condition` returns true, like [`Condvar::wait_while`].
+    /// The returned [`TempRef`] resets the value when dropped, as usual.
+    ///
+    /// The reset function is not called while waiting, so a producer should change the value through
+    /// [`Self::update_and_notify`] or a committed [`Transaction`].
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Condvar;
+    /// use tempref::mutex::Temp;
+    ///
+    /// let jobs = Temp::new(Vec::new(), |v: &mut Vec<u32>| v.clear());
+    /// let ready = Condvar::new();
+    /// std::thread::scope(|s| {
+    ///     let consumer = s.spawn(|| {
+    ///         let batch = jobs.wait_while(&ready, |v| v.len() < 2).unwrap();
+    ///         batch.iter().sum::<u32>()
+    ///     }); // The batch is cleared here.
+    ///     for n in [1, 2] {
+    ///         jobs.update_and_notify(&ready, |v| v.push(n)).unwrap();
+    ///     }
+    ///     assert_eq!(consumer.join().unwrap(), 3);
+    /// });
+    /// assert!(jobs.lock().unwrap().is_empty());
+    /// ```
+    pub fn wait_while<'a, C: FnMut(&mut T) -> bool>(
+        &'a self,
+        condvar: &Condvar,
+        condition: C,
+    ) -> PoisonResult<TempRef<'a, T, F>> {
+        let mut condition = condition;
+        let condition = |slot: &mut Slot<T, F>| condition(&mut slot.value);
+        let result = match self.lock_value() {
+            Ok(guard) => condvar.wait_while(guard, condition),
+            Err(err) => Err(PoisonError::new(
+                condvar
+                    .wait_while(err.into_inner(), condition)
+                    .unwrap_or_else(PoisonError::into_inner),
+            )),
+        };
+        match result {
+            Ok(guard) => Ok(TempRef::new(guard, self)),
+            Err(err) => Err(PoisonError::new(TempRef::new(err.into_inner(), self))),
+        }
+    }
+    /// Like [`Self::wait_while`], but gives up waiting after `timeout`, like [`Condvar::wait_timeout_while`].
+    /// The guard is returned either way, and [`WaitTimeoutResult::timed_out`] tells whether `condition` still held.
+    #[cfg(not(loom))]
+    pub fn wait_timeout_while<'a, C: FnMut(&mut T) -> bool>(
+        &'a self,
+        condvar: &Condvar,
+        timeout: Duration,
+        condition: C,
+    ) -> PoisonResult<(TempRef<'a, T, F>, WaitTimeoutResult)> {
+        let mut condition = condition;
+        let condition = |slot: &mut Slot<T, F>| condition(&mut slot.value);
+        let result = match self.lock_value() {
+            Ok(guard) => condvar.wait_timeout_while(guard, timeout, condition),
+            Err(err) => Err(PoisonError::new(
+                condvar
+                    .wait_timeout_while(err.into_inner(), timeout, condition)
+                    .unwrap_or_else(PoisonError::into_inner),
+            )),
+        };
+        match result {
+            Ok((guard, timed_out)) => Ok((TempRef::new(guard, self), timed_out)),
+            Err(err) => {
+                let (guard, timed_out) = err.into_inner();
+                Err(PoisonError::new((TempRef::new(guard, self), timed_out)))
+            }
+        }
+    }
+    /// Changes the value with `f` without calling the reset function, then wakes every thread waiting on `condvar`.
+    ///
+    /// If the mutex is poisoned, `f` is still called and its result is returned inside the `PoisonError`.
+    pub fn update_and_notify<R, C: FnOnce(&mut T) -> R>(
+        &self,
+        condvar: &Condvar,
+        f: C,
+    ) -> PoisonResult<R> {
+        let result = self.with_value(|slot| f(&mut slot.value));
+        condvar.notify_all();
+        result
+    }
+    /// Acquires the mutex through an `Arc`, blocking the current thread until it is able to do so.
+    /// The returned [`OwnedTempRef`] keeps the `Arc` alive and automatically resets itself when dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use tempref::mutex::{OwnedTempRef, Temp};
+    ///
+    /// struct Job {
+    ///     scratch: OwnedTempRef<Vec<u8>, fn(&mut Vec<u8>)>,
+    /// }
+    ///
+    /// let workspace = Arc::new(Temp::new(Vec::new(), Vec::clear as fn(&mut Vec<u8>)));
+    /// let mut job = Job { scratch: workspace.lock_arc().unwrap() };
+    /// job.scratch.extend_from_slice(b"data");
+    /// drop(job);
+    /// assert!(workspace.lock().unwrap().is_empty());
+    /// ```
+    pub fn lock_arc(self: &Arc<Self>) -> PoisonResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        OwnedTempRef::lock(self)
+    }
+    /// Attempts to acquire the mutex through an `Arc`.
+    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, [`OwnedTempRef`] is returned.
+    pub fn try_lock_arc(self: &Arc<Self>) -> TryLockResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        OwnedTempRef::try_lock(self)
+    }
+    /// Spawns a thread which calls the reset function every `interval` until the returned handle is dropped.
+    ///
+    /// The thread only holds a `Weak` reference, so it does not keep the `Temp` alive.
+    /// A reset is skipped while the lock is held, since the guard resets the value when it is dropped,
+    /// and while the lock is poisoned.
+    ///
+    /// # Panics
+    /// Panics if `interval` is zero, which would keep the thread spinning.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Arc::new(Temp::new(0, |n: &mut i32| *n = 0));
+    /// let resetter = workspace.spawn_resetter(Duration::from_millis(1));
+    /// *workspace.transaction().unwrap() = 1;
+    /// while workspace.generation() == 0 {
+    ///     std::thread::yield_now();
+    /// }
+    /// drop(resetter); // The thread stops here.
+    /// ```
+    #[cfg(not(loom))]
+    #[track_caller]
+    pub fn spawn_resetter(self: &Arc<Self>, interval: Duration) -> ResetterHandle
+    where
+        T: 'static,
+        F: 'static,
+    {
+        resetter::spawn(Arc::downgrade(self), interval, |temp| {
+            let _ = temp.try_reset();
+        })
+    }
+    /// Runs `f` in a [`std::thread::scope`], handing it the scope and this `Temp` to share with the threads it spawns.
+    ///
+    /// Every thread is joined before this method returns. If `reset` is true, the reset function is then invoked once
+    /// more, unless the lock is poisoned.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(Vec::new(), |v: &mut Vec<usize>| v.clear());
+    /// workspace.scope(true, |s, shared| {
+    ///     for n in 0..4 {
+    ///         s.spawn(move || shared.lock().unwrap().push(n));
+    ///     }
+    /// });
+    /// assert_eq!(workspace.generation(), 5);
+    /// ```
+    #[cfg(not(loom))]
+    pub fn scope<'env, R>(
+        &'env self,
+        reset: bool,
+        f: impl for<'scope> FnOnce(&'scope std::thread::Scope<'scope, 'env>, &'env Self) -> R,
+    ) -> R {
+        let result = std::thread::scope(|s| f(s, self));
+        if reset {
+            let _ = self.reset();
+        }
+        result
+    }
+    /// Acquires the mutex as a [`Transaction`], blocking the current thread until it is able to do so.
+    /// The changes are kept only if the transaction is committed.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(vec![0; 4], |d| d.fill(0));
+    ///
+    /// {
+    ///     let mut tx = workspace.transaction().unwrap();
+    ///     tx.fill(1);
+    ///     tx.commit();
+    /// }
+    /// assert_eq!(*workspace.lock().unwrap(), vec![1; 4]);
+    ///
+    /// {
+    ///     let mut tx = workspace.transaction().unwrap();
+    ///     tx.fill(2);
+    ///     // Dropped without commit, so the changes are rolled back by the reset function.
+    /// }
+    /// assert_eq!(workspace.into_inner().unwrap(), vec![0; 4]);
+    /// ```
+    pub fn transaction<'a>(&'a self) -> PoisonResult<Transaction<'a, T, F>> {
+        Transaction::lock(self)
+    }
     /// Consumes the Temp, returning the wrapped value.
     pub fn into_inner(self) -> PoisonResult<T> {
-        self.value.into_inner()
+        match self.value.into_inner() {
+            Ok(slot) => Ok(slot.value),
+            Err(err) => Err(PoisonError::new(err.into_inner().value)),
+        }
+    }
+    /// Consumes the Temp, returning the wrapped value and the reset function.
+    ///
+    /// If the lock is poisoned, both are returned inside the `PoisonError`.
+    pub fn into_parts(self) -> PoisonResult<(T, F)> {
+        match self.value.into_inner() {
+            Ok(Slot { value, reset }) => Ok((value, reset)),
+            Err(err) => {
+                let Slot { value, reset } = err.into_inner();
+                Err(PoisonError::new((value, reset)))
+            }
+        }
+    }
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no actual locking needs to take place.
+    /// The reset function is not called.
+    pub fn get_mut(&mut self) -> PoisonResult<&mut T> {
+        match self.value.get_mut() {
+            Ok(slot) => Ok(&mut slot.value),
+            Err(err) => Err(PoisonError::new(&mut err.into_inner().value)),
+        }
+    }
+    /// Calls `f` with a mutable reference to the wrapped value, then calls the reset function like [`TempRef::reset`].
+    ///
+    /// Since this call borrows the `Temp` mutably, no actual locking needs to take place.
+    /// If the lock is poisoned, the value is still reset and the result of `f` is returned inside the `PoisonError`.
+    pub fn get_mut_reset<R, C: FnOnce(&mut T) -> R>(&mut self, f: C) -> PoisonResult<R> {
+        // Borrowed field by field, since the value is borrowed from `self.value` while the reset runs.
+        let Temp {
+            value,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            ..
+        } = self;
+        let (slot, poisoned) = match value.get_mut() {
+            Ok(slot) => (slot, false),
+            Err(err) => (err.into_inner(), true),
+        };
+        let result = f(&mut slot.value);
+        let Slot { value, reset } = slot;
+        let mut reset = hooked_reset(reset, generation, clock, hooks, instrument);
+        unwind::reset_or_poison(value, &mut reset, || {
+            reset_poisoned.store(true, Ordering::Release)
+        });
+        if poisoned {
+            Err(PoisonError::new(result))
+        } else {
+            Ok(result)
+        }
+    }
+    /// Overwrites the wrapped value, dropping the old one.
+    ///
+    /// The reset function is not called.
+    /// If the mutex is poisoned, the value is still overwritten.
+    pub fn set(&self, value: T) -> PoisonResult<()> {
+        self.with_value(|old| old.value = value)
+    }
+    /// Overwrites the wrapped value, dropping the old one, after applying the reset function to the new `value`,
+    /// like [`Self::new_with`].
+    ///
+    /// If the mutex is poisoned, the value is still overwritten.
+    pub fn set_with(&self, mut value: T) -> PoisonResult<()> {
+        self.with_value(|old| {
+            (old.reset)(&mut value);
+            old.value = value;
+        })
+    }
+    /// Replaces the wrapped value with a new one, returning the old value, without deinitializing either one.
+    ///
+    /// The reset function is not called.
+    /// If the mutex is poisoned, the value is still replaced and the old value is returned inside the `PoisonError`.
+    pub fn replace(&self, value: T) -> PoisonResult<T> {
+        self.with_value(|old| mem::replace(&mut old.value, value))
+    }
+    /// Replaces the wrapped value with a new one computed from f, returning the old value, without deinitializing either one.
+    ///
+    /// The reset function is not called.
+    /// If the mutex is poisoned, the value is still replaced and the old value is returned inside the `PoisonError`.
+    pub fn replace_with<C: FnOnce(&mut T) -> T>(&self, f: C) -> PoisonResult<T> {
+        self.with_value(|old| {
+            let value = f(&mut old.value);
+            mem::replace(&mut old.value, value)
+        })
+    }
+    /// Takes the wrapped value, leaving `Default::default()` in its place.
+    ///
+    /// The reset function is not called.
+    /// If the mutex is poisoned, the value is still taken and returned inside the `PoisonError`.
+    pub fn take(&self) -> PoisonResult<T>
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+    /// Swaps the wrapped value of self with the wrapped value of other, without deinitializing either one.
+    ///
+    /// The reset function is not called. If either mutex is poisoned, the values are still swapped and Err is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Mutex;
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new(vec![0; 4], |v| v.fill(0));
+    /// let spare = Mutex::new(vec![1; 8]);
+    /// workspace.swap(&spare).unwrap();
+    /// assert_eq!(workspace.lock().unwrap().len(), 8);
+    /// assert_eq!(*spare.lock().unwrap(), vec![0; 4]);
+    /// ```
+    pub fn swap(&self, other: &Mutex<T>) -> PoisonResult<()> {
+        let _notify = Notify(self);
+        // Locked in the order of their addresses, like `lock_all`, so that two swaps of the same pair can't deadlock.
+        let (a, b) = if ptr::from_ref(&self.value).addr() < ptr::from_ref(other).addr() {
+            let a = self.lock_value();
+            (a, other.lock())
+        } else {
+            let b = other.lock();
+            (self.lock_value(), b)
+        };
+        let _hold = self.instrument.hold::<T>();
+        let poisoned = a.is_err() || b.is_err();
+        let mut a = a.unwrap_or_else(PoisonError::into_inner);
+        let mut b = b.unwrap_or_else(PoisonError::into_inner);
+        mem::swap(&mut a.value, &mut *b);
+        if poisoned {
+            Err(PoisonError::new(()))
+        } else {
+            Ok(())
+        }
     }
     /// Clear the poisoned state from a mutex.
     pub fn clear_poison(&self) {
@@ -142,8 +1508,10 @@ impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// This method acquires a blocking lock on the internal `Mutex<T>`.
     /// If the lock is poisoned due to a panic in another thread, it returns a `PoisonError`.
     pub fn reset(&self) -> PoisonResult<()> {
-        if let Ok(mut guard) = self.value.lock() {
-            self.get_reset()(&mut guard);
+        let _notify = Notify(self);
+        if let Ok(mut guard) = self.lock_value() {
+            let _hold = self.instrument.hold::<T>();
+            self.reset_or_poison(&mut guard);
             Ok(())
         } else {
             Err(PoisonError::new(()))
@@ -154,9 +1522,10 @@ impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// This method tries to acquire a non-blocking lock on the internal `Mutex<T>`.
     /// If the lock is already held or poisoned, it returns a `TryLockError`.
     pub fn try_reset(&self) -> TryLockResult<()> {
+        let _notify = Notify(self);
         match self.value.try_lock() {
             Ok(mut guard) => {
-                self.get_reset()(&mut guard);
+                self.reset_or_poison(&mut guard);
                 Ok(())
             },
             Err(TryLockError::Poisoned(_)) => Err(TryLockError::Poisoned(PoisonError::new(()))),
@@ -164,17 +1533,162 @@ impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
         }
     }
 
-    #[allow(clippy::mut_from_ref)]
-    fn get_reset(&self) -> &mut F {
-        unsafe { &mut *self.reset.get() }
+    fn with_value<R, C: FnOnce(&mut Slot<T, F>) -> R>(&self, f: C) -> PoisonResult<R> {
+        let _notify = Notify(self);
+        let result = self.lock_value();
+        let _hold = self.instrument.hold::<T>();
+        match result {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(err) => Err(PoisonError::new(f(&mut err.into_inner()))),
+        }
+    }
+    fn lock_value(&self) -> PoisonResult<MutexGuard<'_, Slot<T, F>>> {
+        let _turn = self.fair.as_deref().map(TicketQueue::wait);
+        self.instrument
+            .lock::<T, _>(|| self.value.try_lock(), || self.value.lock())
+    }
+    // Resets the value first if it is older than the maximum age, or a postponed reset is due.
+    fn expire(&self, slot: &mut Slot<T, F>) {
+        if self.clock.due() {
+            self.reset_or_poison(slot);
+        }
+    }
+    // The reset function followed by the `on_reset` hook, inside the span of the reset.
+    fn hooked_reset<'r>(&'r self, reset: &'r mut F) -> impl FnMut(&mut T) + 'r {
+        hooked_reset(
+            reset,
+            &self.generation,
+            &self.clock,
+            &self.hooks,
+            &self.instrument,
+        )
+    }
+    fn reset_or_poison(&self, slot: &mut Slot<T, F>) {
+        let Slot { value, reset } = slot;
+        unwind::reset_or_poison(value, &mut self.hooked_reset(reset), || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+    }
+    // Called when a guard is dropped. The reset is skipped if `reset_if` returns false,
+    // and postponed if the last one ran less than the minimum interval ago.
+    fn release(&self, slot: &mut Slot<T, F>) {
+        if !self.should_reset(&slot.value) || self.clock.postpone() {
+            self.hooks.release(&slot.value);
+            self.instrument.release::<T>();
+        } else {
+            self.reset_on_drop(slot);
+        }
+    }
+    // Asks the `with_reset_if` predicate, if there is one.
+    fn should_reset(&self, value: &T) -> bool {
+        match &self.reset_if {
+            Some(reset_if) => reset_if(value),
+            None => true,
+        }
+    }
+    fn reset_on_drop(&self, slot: &mut Slot<T, F>) {
+        let Slot { value, reset } = slot;
+        self.hooks.release(value);
+        self.instrument.release::<T>();
+        unwind::reset_on_drop(self.unwind, value, &mut self.hooked_reset(reset), || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+    }
+}
+/// A [`Temp`] whose reset function is a function pointer, which is the default of the `F` parameter.
+///
+/// Non-capturing closures coerce to function pointers, so the type can be spelled out in a `static`.
+///
+/// # Examples
+/// ```
+/// use tempref::mutex::{Temp, TempFn};
+///
+/// static SCRATCH: TempFn<[u8; 512]> = Temp::new([0; 512], |b| b.fill(0));
+///
+/// SCRATCH.lock().unwrap()[0] = 1;
+/// assert_eq!(SCRATCH.lock().unwrap()[0], 0);
+/// ```
+pub type TempFn<T> = Temp<T, fn(&mut T)>;
+
+/// A [`Temp`] whose reset function is boxed, so that the type can be named without a closure type parameter.
+///
+/// # Examples
+/// ```
+/// use tempref::mutex::BoxedTemp;
+///
+/// struct Worker {
+///     scratch: BoxedTemp<Vec<u8>>,
+/// }
+///
+/// let worker = Worker {
+///     scratch: BoxedTemp::new_boxed(Vec::new(), |v| v.clear()),
+/// };
+/// # let _ = worker.scratch;
+/// ```
+pub type BoxedTemp<T> = Temp<T, Box<dyn FnMut(&mut T) + Send>>;
+impl<T: Send> Temp<T, Box<dyn FnMut(&mut T) + Send>> {
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    pub fn new_boxed<F: FnMut(&mut T) + Send + 'static>(value: T, reset: F) -> Self {
+        Temp::new(value, Box::new(reset))
+    }
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    ///
+    /// Unlike [`Self::new_boxed`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_boxed_with<F: FnMut(&mut T) + Send + 'static>(value: T, reset: F) -> Self {
+        Temp::new_with(value, Box::new(reset))
+    }
+}
+impl<T: Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp whose reset function is shared through an `Arc`, e.g. with a `Temp` per thread.
+    ///
+    /// The returned `Temp` can be cloned, and every clone calls the same `reset`, which may keep its own state
+    /// behind atomics or a lock.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// use tempref::mutex::Temp;
+    ///
+    /// let resets = Arc::new(AtomicUsize::new(0));
+    /// let counted = Arc::clone(&resets);
+    /// let strategy = Arc::new(move |v: &mut Vec<u8>| {
+    ///     v.clear();
+    ///     counted.fetch_add(1, Ordering::Relaxed);
+    /// });
+    /// let shards: Vec<_> = (0..4).map(|_| Temp::new_shared(Vec::new(), Arc::clone(&strategy))).collect();
+    /// std::thread::scope(|s| {
+    ///     for shard in &shards {
+    ///         s.spawn(|| shard.lock().unwrap().push(1));
+    ///     }
+    /// });
+    /// assert_eq!(resets.load(Ordering::Relaxed), 4);
+    /// ```
+    pub fn new_shared<R: Fn(&mut T) + Send + Sync + ?Sized>(
+        value: T,
+        reset: Arc<R>,
+    ) -> Temp<T, impl FnMut(&mut T) + Clone + Send> {
+        Temp::new(value, move |value: &mut T| (*reset)(value))
     }
 }
 impl<T: Default + Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// Creates a new `Temp<T, F>` using `T::default()` as the initial value.
     pub fn new_default(reset: F) -> Self {
         Temp {
-            value: Mutex::new(T::default()),
-            reset: UnsafeCell::new(reset),
+            value: Mutex::new(Slot {
+                value: T::default(),
+                reset,
+            }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            fair: None,
+            waiters: Waiters::new(),
         }
     }
 
@@ -187,15 +1701,476 @@ impl<T: Default + Send, F: FnMut(&mut T) + Send> Temp<T, F> {
         let mut default = T::default();
         reset(&mut default);
         Temp {
-            value: Mutex::new(default),
-            reset: UnsafeCell::new(reset),
+            value: Mutex::new(Slot {
+                value: default,
+                reset,
+            }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            fair: None,
+            waiters: Waiters::new(),
         }
     }
 }
-unsafe impl<T: Send, F: FnMut(&mut T) + Send> Send for Temp<T, F> {}
-unsafe impl<T: Send, F: FnMut(&mut T) + Send> Sync for Temp<T, F> {}
+/// Formats the value without blocking, printing `<locked>` instead while the lock is held for writing.
 impl<T: Debug + Send, F: FnMut(&mut T) + Send> Debug for Temp<T, F> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Temp").field("value", &self.value).finish()
+        let _notify = Notify(self);
+        let mut d = f.debug_struct("Temp");
+        match self.value.try_lock() {
+            Ok(guard) => d.field("value", &guard.value),
+            Err(TryLockError::Poisoned(err)) => d.field("value", &err.into_inner().value),
+            Err(TryLockError::WouldBlock) => d.field("value", &format_args!("<locked>")),
+        };
+        d.field("poisoned", &self.value.is_poisoned()).finish()
+    }
+}
+/// Serializes the value alone, as if the `Temp` were not there.
+///
+/// Blocks until the lock is available, and fails if it is poisoned. The reset function is not called.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Send, F: FnMut(&mut T) + Send> serde::Serialize for Temp<T, F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let _notify = Notify(self);
+        match self.lock_value() {
+            Ok(slot) => slot.value.serialize(serializer),
+            Err(_) => Err(serde::ser::Error::custom(
+                "lock poison error while serializing",
+            )),
+        }
+    }
+}
+/// Deserializes the value into a `Temp` whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Default + Send> serde::Deserialize<'de>
+    for Temp<T, fn(&mut T)>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Temp::from)
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
+    /// Deserializes the value and wraps it in a `Temp` with the given `reset` function.
+    pub fn deserialize_with<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+        reset: F,
+    ) -> Result<Self, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Temp::new(value, reset))
+    }
+}
+/// Generates a `Temp` holding an arbitrary value, whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + Default + Send> proptest::arbitrary::Arbitrary
+    for Temp<T, fn(&mut T)>
+{
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(T::arbitrary_with(args), Temp::from)
+    }
+}
+/// Creates a `Temp` holding `T::default()`, whose reset function sets the value back to `T::default()`.
+impl<T: Default + Send> Default for Temp<T, fn(&mut T)> {
+    fn default() -> Self {
+        Temp::new_default(crate::reset_to_default::<T>)
+    }
+}
+/// Creates a `Temp` whose reset function sets the value to `T::default()`.
+impl<T: Default + Send> From<T> for Temp<T, fn(&mut T)> {
+    fn from(value: T) -> Self {
+        Temp::new(value, crate::reset_to_default::<T>)
+    }
+}
+impl<T: AsMut<[u8]> + Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a byte buffer such as `Vec<u8>`, `Box<[u8]>` or `[u8; N]`,
+    /// whose reset function zeroes the bytes in place with a single `memset`, keeping the length.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let buffer = Temp::zeroing(vec![0u8; 4096]);
+    /// buffer.lock().unwrap()[..5].copy_from_slice(b"hello");
+    /// assert!(buffer.lock().unwrap().iter().all(|&b| b == 0));
+    /// ```
+    pub fn zeroing(value: T) -> Self {
+        Temp::new(value, crate::zero_bytes::<T>)
+    }
+}
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+impl<T: crate::Clear + Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a collection implementing [`Clear`](crate::Clear), such as `heapless::Vec`,
+    /// whose reset function empties it in place, keeping its capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::clearing(Vec::with_capacity(64));
+    /// workspace.lock().unwrap().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.lock().unwrap().is_empty());
+    /// ```
+    pub fn clearing(value: T) -> Self {
+        Temp::new(value, T::clear)
+    }
+}
+#[cfg(feature = "allocator_api")]
+impl<T: Send, A: core::alloc::Allocator + Send>
+    Temp<alloc::vec::Vec<T, A>, fn(&mut alloc::vec::Vec<T, A>)>
+{
+    /// A constructor of an empty `Vec` workspace in `alloc`, whose reset function clears it and keeps the capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::System;
+    /// use tempref::mutex::Temp;
+    ///
+    /// let workspace = Temp::new_in(System);
+    /// workspace.lock().unwrap().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.lock().unwrap().is_empty());
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Temp::new(alloc::vec::Vec::new_in(alloc), alloc::vec::Vec::clear)
+    }
+    /// Like [`Self::new_in`], but allocates room for `capacity` elements up front.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Temp::new(
+            alloc::vec::Vec::with_capacity_in(capacity, alloc),
+            alloc::vec::Vec::clear,
+        )
+    }
+}
+/// Clones the value and the reset function, keeping the [`UnwindPolicy`] and the reset-poisoned flag.
+///
+/// The new `Temp` is not poisoned, even if this one is.
+impl<T: Clone + Send, F: FnMut(&mut T) + Send + Clone> Clone for Temp<T, F> {
+    fn clone(&self) -> Self {
+        let _notify = Notify(self);
+        let slot = self.lock_value().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: Mutex::new(Slot {
+                value: slot.value.clone(),
+                reset: slot.reset.clone(),
+            }),
+            unwind: self.unwind,
+            reset_poisoned: AtomicBool::new(self.reset_poisoned.load(Ordering::Acquire)),
+            generation: AtomicUsize::new(self.generation.load(Ordering::Acquire)),
+            hooks: self.hooks.clone(),
+            instrument: self.instrument.clone(),
+            clock: self.clock.clone(),
+            reset_if: self.reset_if.clone(),
+            fair: self.is_fair().then(|| Box::new(TicketQueue::new())),
+            waiters: Waiters::new(),
+        }
+    }
+}
+/// Compares the values of two `Temp`s, whose reset functions may differ.
+///
+/// Both `Temp`s are locked like any other access, in address order and ignoring poisoning, so comparisons in
+/// opposite directions never deadlock.
+impl<T: PartialEq + Send, F: FnMut(&mut T) + Send, G: FnMut(&mut T) + Send> PartialEq<Temp<T, G>>
+    for Temp<T, F>
+{
+    fn eq(&self, other: &Temp<T, G>) -> bool {
+        let _notify = (Notify(self), Notify(other));
+        let (a, b) = (ptr::from_ref(self).addr(), ptr::from_ref(other).addr());
+        if a == b {
+            let slot = self.lock_value().unwrap_or_else(PoisonError::into_inner);
+            // Compared with itself rather than returning `true`, so that a NaN is still unequal.
+            return T::eq(&slot.value, &slot.value);
+        }
+        if a < b {
+            let a = self.lock_value().unwrap_or_else(PoisonError::into_inner);
+            a.value
+                == other
+                    .lock_value()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .value
+        } else {
+            let b = other.lock_value().unwrap_or_else(PoisonError::into_inner);
+            self.lock_value()
+                .unwrap_or_else(PoisonError::into_inner)
+                .value
+                == b.value
+        }
+    }
+}
+impl<T: Eq + Send, F: FnMut(&mut T) + Send> Eq for Temp<T, F> {}
+/// Compares the value of a `Temp` with a plain value, ignoring poisoning.
+impl<T: PartialEq + Send, F: FnMut(&mut T) + Send> PartialEq<T> for Temp<T, F> {
+    fn eq(&self, other: &T) -> bool {
+        let _notify = Notify(self);
+        self.lock_value()
+            .unwrap_or_else(PoisonError::into_inner)
+            .value
+            == *other
+    }
+}
+/// Displays the value without blocking, or `<locked>` while the lock is held for writing.
+impl<T: Display + Send, F: FnMut(&mut T) + Send> Display for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let _notify = Notify(self);
+        match self.value.try_lock() {
+            Ok(guard) => Display::fmt(&guard.value, f),
+            Err(TryLockError::Poisoned(err)) => Display::fmt(&err.into_inner().value, f),
+            Err(TryLockError::WouldBlock) => f.write_str("<locked>"),
+        }
+    }
+}
+
+/// A cheap-to-clone handle to a [`Temp`] shared through an `Arc`.
+///
+/// It dereferences to the `Temp`, so it has the whole API of `Temp`, and clones of it refer to the same value.
+/// It also implements [`TempAccess`](crate::TempAccess), whose methods return [`Error`](crate::Error),
+/// so code which takes a workspace from many places doesn't have to name `Arc` or the lock errors of `std`.
+///
+/// # Examples
+/// ```
+/// use tempref::mutex::SharedTemp;
+///
+/// let workspace = SharedTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+/// let handle = workspace.clone();
+/// std::thread::spawn(move || handle.lock().unwrap().push(1))
+///     .join()
+///     .unwrap();
+/// assert_eq!(workspace.generation(), 1);
+/// assert!(workspace.lock().unwrap().is_empty());
+/// ```
+pub struct SharedTemp<T: Send, F: FnMut(&mut T) + Send = fn(&mut T)> {
+    temp: Arc<Temp<T, F>>,
+}
+impl<T: Send, F: FnMut(&mut T) + Send> SharedTemp<T, F> {
+    /// A constructor of SharedTemp<T, F>.
+    pub fn new(value: T, reset: F) -> Self {
+        SharedTemp::from(Temp::new(value, reset))
+    }
+    /// Returns the `Arc` behind the handle.
+    pub fn as_arc(&self) -> &Arc<Temp<T, F>> {
+        &self.temp
+    }
+    /// Consumes the handle, returning the `Arc` behind it.
+    pub fn into_arc(self) -> Arc<Temp<T, F>> {
+        self.temp
+    }
+    /// Returns true if both handles refer to the same `Temp`.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.temp, &other.temp)
+    }
+    /// Returns how many handles, and other `Arc`s, refer to the `Temp`.
+    pub fn handle_count(&self) -> usize {
+        Arc::strong_count(&self.temp)
+    }
+    /// Returns a [`WeakTemp`] which refers to the same `Temp` without keeping it alive.
+    pub fn downgrade(&self) -> WeakTemp<T, F> {
+        WeakTemp {
+            temp: Arc::downgrade(&self.temp),
+        }
+    }
+    /// Acquires the mutex, returning an [`OwnedTempRef`] which keeps the `Temp` alive by itself.
+    ///
+    /// See [`Temp::lock_arc`].
+    pub fn lock_arc(&self) -> PoisonResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        self.temp.lock_arc()
+    }
+    /// Attempts to acquire the mutex, returning an [`OwnedTempRef`] which keeps the `Temp` alive by itself.
+    ///
+    /// See [`Temp::try_lock_arc`].
+    pub fn try_lock_arc(&self) -> TryLockResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        self.temp.try_lock_arc()
+    }
+    /// Spawns a thread which calls the reset function every `interval` until the returned handle is dropped.
+    ///
+    /// See [`Temp::spawn_resetter`].
+    #[cfg(not(loom))]
+    #[track_caller]
+    pub fn spawn_resetter(&self, interval: Duration) -> ResetterHandle
+    where
+        T: 'static,
+        F: 'static,
+    {
+        self.temp.spawn_resetter(interval)
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> Clone for SharedTemp<T, F> {
+    fn clone(&self) -> Self {
+        SharedTemp {
+            temp: Arc::clone(&self.temp),
+        }
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for SharedTemp<T, F> {
+    type Target = Temp<T, F>;
+    fn deref(&self) -> &Self::Target {
+        &self.temp
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> From<Temp<T, F>> for SharedTemp<T, F> {
+    fn from(temp: Temp<T, F>) -> Self {
+        SharedTemp {
+            temp: Arc::new(temp),
+        }
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> From<Arc<Temp<T, F>>> for SharedTemp<T, F> {
+    fn from(temp: Arc<Temp<T, F>>) -> Self {
+        SharedTemp { temp }
+    }
+}
+impl<T: Debug + Send, F: FnMut(&mut T) + Send> Debug for SharedTemp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("SharedTemp").field(&*self.temp).finish()
+    }
+}
+
+/// A handle to the `Temp` of a [`SharedTemp`] which doesn't keep it alive, e.g. for metrics or debug dumps.
+///
+/// # Examples
+/// ```
+/// use tempref::mutex::SharedTemp;
+///
+/// let workspace = SharedTemp::new(0, |n: &mut i32| *n = 0);
+/// let observer = workspace.downgrade();
+/// *workspace.lock().unwrap() = 1;
+/// assert_eq!(observer.upgrade().map(|w| w.generation()), Some(1));
+/// drop(workspace);
+/// assert!(observer.upgrade().is_none());
+/// ```
+pub struct WeakTemp<T: Send, F: FnMut(&mut T) + Send = fn(&mut T)> {
+    temp: Weak<Temp<T, F>>,
+}
+impl<T: Send, F: FnMut(&mut T) + Send> WeakTemp<T, F> {
+    /// A constructor of a WeakTemp which refers to nothing, so that [`Self::upgrade`] always returns `None`.
+    pub const fn new() -> Self {
+        WeakTemp { temp: Weak::new() }
+    }
+    /// Returns a [`SharedTemp`] of the `Temp`, or `None` if it has been dropped.
+    pub fn upgrade(&self) -> Option<SharedTemp<T, F>> {
+        self.temp.upgrade().map(SharedTemp::from)
+    }
+    /// Returns how many [`SharedTemp`]s, and other `Arc`s, keep the `Temp` alive.
+    pub fn handle_count(&self) -> usize {
+        self.temp.strong_count()
+    }
+    /// Returns true if both handles refer to the same `Temp`, or both refer to nothing.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Weak::ptr_eq(&self.temp, &other.temp)
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> Clone for WeakTemp<T, F> {
+    fn clone(&self) -> Self {
+        WeakTemp {
+            temp: Weak::clone(&self.temp),
+        }
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> Default for WeakTemp<T, F> {
+    fn default() -> Self {
+        WeakTemp::new()
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> Debug for WeakTemp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("WeakTemp")
+    }
+}
+
+/// A process-wide [`Temp`] which is initialized on first use.
+///
+/// The value is created by `init` and the reset function is `reset`, both of which are plain function pointers,
+/// so that the type can be named in a `static`. Non-capturing closures can be used for both.
+/// It dereferences to the underlying [`Temp`], initializing it if necessary.
+/// The [`static_temp!`](crate::static_temp) macro is a shorthand for declaring one.
+///
+/// # Examples
+/// ```
+/// use tempref::mutex::StaticTemp;
+///
+/// static SCRATCH: StaticTemp<Vec<u8>> = StaticTemp::new(|| vec![0; 512], |v| v.fill(0));
+///
+/// {
+///     let mut guard = SCRATCH.lock().unwrap();
+///     guard.fill(1);
+/// }
+/// assert_eq!(*SCRATCH.lock().unwrap(), vec![0; 512]);
+/// ```
+#[cfg(not(loom))]
+pub struct StaticTemp<T: Send> {
+    cell: OnceLock<TempFn<T>>,
+    init: fn() -> T,
+    reset: fn(&mut T),
+}
+#[cfg(not(loom))]
+impl<T: Send> StaticTemp<T> {
+    /// A constructor of `StaticTemp<T>`. Neither function is called until the first use.
+    pub const fn new(init: fn() -> T, reset: fn(&mut T)) -> Self {
+        StaticTemp {
+            cell: OnceLock::new(),
+            init,
+            reset,
+        }
+    }
+    /// Returns the underlying [`Temp`], initializing it if necessary.
+    pub fn get(&self) -> &TempFn<T> {
+        self.cell
+            .get_or_init(|| Temp::new((self.init)(), self.reset))
+    }
+}
+#[cfg(not(loom))]
+impl<T: Send> core::ops::Deref for StaticTemp<T> {
+    type Target = TempFn<T>;
+    fn deref(&self) -> &Self::Target {
+        self.get()
+    }
+}
+#[cfg(not(loom))]
+impl<T: Debug + Send> Debug for StaticTemp<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StaticTemp")
+            .field("temp", &self.cell.get())
+            .finish()
     }
 }
+
+/// Declares a [`mutex::StaticTemp`](crate::mutex::StaticTemp), a process-wide `Temp` which is initialized on first use.
+///
+/// # Examples
+/// ```
+/// tempref::static_temp! {
+///     /// A buffer shared by the whole process.
+///     pub static SCRATCH: Vec<u8> = vec![0; 512], |v| v.fill(0);
+/// }
+///
+/// {
+///     let mut guard = SCRATCH.lock().unwrap();
+///     guard.fill(1);
+/// }
+/// assert_eq!(*SCRATCH.lock().unwrap(), vec![0; 512]);
+/// ```
+#[cfg(not(loom))]
+#[macro_export]
+macro_rules! static_temp {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $ty:ty = $init:expr, $reset:expr;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::mutex::StaticTemp<$ty> =
+            $crate::mutex::StaticTemp::new(|| $init, $reset);
+    };
+}
@@ -0,0 +1,88 @@
+//! Per-thread resettable values for rayon workers. This module requires std and the `rayon` feature.
+
+extern crate std;
+
+use core::fmt::Debug;
+use std::boxed::Box;
+use std::sync::{PoisonError, TryLockError};
+
+use crate::mutex::{Temp, TempRef};
+
+/// A set of values where every rayon worker thread has its own value, each of which is reset when released.
+///
+/// [`Self::get`] hands out the value of the current worker, so a parallel iterator can use scratch memory
+/// without contention. Threads outside of the rayon pool share one extra value, which is locked as usual.
+///
+/// Since the values are reset on every release, poisoning is ignored.
+///
+/// # Examples
+/// ```
+/// use rayon::prelude::*;
+/// use tempref::per_thread::TempPerThread;
+///
+/// let scratch = TempPerThread::new(Vec::new, |v: &mut Vec<u32>| v.clear());
+///
+/// let total: u32 = (0..100u32)
+///     .into_par_iter()
+///     .map(|n| {
+///         let mut buffer = scratch.get();
+///         buffer.extend(0..n);
+///         buffer.iter().sum::<u32>()
+///     })
+///     .sum();
+/// assert_eq!(total, (0..100u32).map(|n| (0..n).sum::<u32>()).sum::<u32>());
+/// ```
+pub struct TempPerThread<T: Send, F: FnMut(&mut T) + Send> {
+    slots: Box<[Temp<T, F>]>,
+}
+impl<T: Send, F: FnMut(&mut T) + Send + Clone> TempPerThread<T, F> {
+    /// Creates a value for every thread of the current rayon pool using `init`, each with a clone of `reset`.
+    pub fn new<C: FnMut() -> T>(init: C, reset: F) -> Self {
+        Self::with_threads(rayon::current_num_threads(), init, reset)
+    }
+    /// Creates a value for each of `threads` rayon worker threads using `init`, each with a clone of `reset`.
+    ///
+    /// Use this when the values are used in a pool other than the current one.
+    pub fn with_threads<C: FnMut() -> T>(threads: usize, mut init: C, reset: F) -> Self {
+        TempPerThread {
+            slots: (0..=threads)
+                .map(|_| Temp::new(init(), reset.clone()))
+                .collect(),
+        }
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> TempPerThread<T, F> {
+    /// Acquires the value of the current thread.
+    ///
+    /// On a rayon worker this never blocks. Other threads block until the shared value is available.
+    ///
+    /// # Panics
+    /// Panics if the value of the current worker is already in use, for example when the guard is held
+    /// across a nested parallel call which runs another task on the same worker.
+    pub fn get<'a>(&'a self) -> TempRef<'a, T, F> {
+        let shared = self.slots.len() - 1;
+        match rayon::current_thread_index() {
+            Some(index) if index < shared => match self.slots[index].try_lock() {
+                Ok(guard) => guard,
+                Err(TryLockError::Poisoned(err)) => err.into_inner(),
+                Err(TryLockError::WouldBlock) => {
+                    panic!("the value of this rayon worker is already in use")
+                }
+            },
+            _ => self.slots[shared]
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner),
+        }
+    }
+    /// Returns every value, including the one shared by threads outside of the rayon pool.
+    pub fn as_slice(&self) -> &[Temp<T, F>] {
+        &self.slots
+    }
+}
+impl<T: Debug + Send, F: FnMut(&mut T) + Send> Debug for TempPerThread<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempPerThread")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
@@ -0,0 +1,115 @@
+//! A pool of resettable values built on [`mutex::Temp`](crate::mutex::Temp). This module requires std.
+
+extern crate std;
+
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::boxed::Box;
+use std::sync::{PoisonError, TryLockError};
+
+use crate::mutex::{Temp, TempRef};
+
+type PoisonResult<T> = Result<T, PoisonError<T>>;
+type TryLockResult<T> = Result<T, TryLockError<T>>;
+
+/// A pool of values protected by their own `Mutex`, each of which is reset when it is returned.
+///
+/// [`Self::checkout`] hands out a [`TempRef`] to one of the values which is not in use.
+/// Dropping the guard calls the reset function and puts the value back into the pool,
+/// so that contention is spread over the pool instead of one lock.
+///
+/// # Examples
+/// ```
+/// use tempref::pool::TempPool;
+///
+/// let pool = TempPool::from_fn(4, |_| vec![0u8; 1024], |v| v.fill(0));
+///
+/// std::thread::scope(|s| {
+///     for n in 0..8 {
+///         let pool = &pool;
+///         s.spawn(move || {
+///             let mut buffer = pool.checkout().unwrap();
+///             buffer.fill(n);
+///         });
+///     }
+/// });
+/// assert!(pool.as_slice().iter().all(|temp| temp.lock().unwrap().iter().all(|b| *b == 0)));
+/// ```
+pub struct TempPool<T: Send, F: FnMut(&mut T) + Send> {
+    slots: Box<[Temp<T, F>]>,
+    next: AtomicUsize,
+}
+impl<T: Send, F: FnMut(&mut T) + Send + Clone> TempPool<T, F> {
+    /// Creates a pool holding every value of `values`, each with a clone of `reset`.
+    ///
+    /// # Panics
+    /// Panics if `values` is empty.
+    pub fn new<I: IntoIterator<Item = T>>(values: I, reset: F) -> Self {
+        let slots: Box<[Temp<T, F>]> = values
+            .into_iter()
+            .map(|value| Temp::new(value, reset.clone()))
+            .collect();
+        assert!(!slots.is_empty(), "TempPool requires at least one value");
+        TempPool {
+            slots,
+            next: AtomicUsize::new(0),
+        }
+    }
+    /// Creates a pool holding `len` values returned by `f`, each with a clone of `reset`.
+    ///
+    /// # Panics
+    /// Panics if `len` is zero.
+    pub fn from_fn<C: FnMut(usize) -> T>(len: usize, f: C, reset: F) -> Self {
+        Self::new((0..len).map(f), reset)
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send> TempPool<T, F> {
+    /// Returns the number of values in the pool.
+    pub fn len(&self) -> usize {
+        self.slots.len()
+    }
+    /// Returns `true` if the pool holds no values. A pool is never empty.
+    pub fn is_empty(&self) -> bool {
+        self.slots.is_empty()
+    }
+    /// Returns the values in the pool.
+    pub fn as_slice(&self) -> &[Temp<T, F>] {
+        &self.slots
+    }
+    /// Checks out a value which is not in use.
+    /// If every value is in use, blocks the current thread until one of them is returned.
+    pub fn checkout<'a>(&'a self) -> PoisonResult<TempRef<'a, T, F>> {
+        let start = self.start();
+        match self.scan(start) {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Err(err),
+            Err(TryLockError::WouldBlock) => self.slots[start].lock(),
+        }
+    }
+    /// Attempts to check out a value which is not in use.
+    /// If every value is in use, then Err is returned.
+    pub fn try_checkout<'a>(&'a self) -> TryLockResult<TempRef<'a, T, F>> {
+        self.scan(self.start())
+    }
+
+    fn start(&self) -> usize {
+        self.next.fetch_add(1, Ordering::Relaxed) % self.slots.len()
+    }
+    fn scan<'a>(&'a self, start: usize) -> TryLockResult<TempRef<'a, T, F>> {
+        let len = self.slots.len();
+        for offset in 0..len {
+            match self.slots[(start + offset) % len].try_lock() {
+                Err(TryLockError::WouldBlock) => continue,
+                result => return result,
+            }
+        }
+        Err(TryLockError::WouldBlock)
+    }
+}
+impl<T: Debug + Send, F: FnMut(&mut T) + Send> Debug for TempPool<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempPool")
+            .field("slots", &self.slots)
+            .finish()
+    }
+}
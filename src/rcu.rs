@@ -0,0 +1,136 @@
+//! Read-copy-update version of TempRef, using [`arc_swap`].
+//!
+//! Readers get an `Arc<T>` snapshot without taking a lock. A writer mutates a clone of the current value,
+//! which is reset and then swapped in when its guard is dropped, so readers never see a half-written value.
+
+extern crate std;
+
+use core::{
+    fmt::Debug,
+    mem::ManuallyDrop,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+
+use arc_swap::ArcSwap;
+
+use crate::Error;
+
+/// A mutable reference to a clone of the value of [`Temp<T, F>`].
+/// When it is dropped, it calls the reset function and publishes the clone to readers.
+///
+/// If it is dropped while the thread is panicking, the clone is discarded and readers keep the previous value.
+pub struct TempRef<'a, T: Clone, F: FnMut(&mut T) + Send> {
+    value: ManuallyDrop<T>,
+    reset: MutexGuard<'a, F>,
+    current: &'a ArcSwap<T>,
+    generation: &'a AtomicUsize,
+}
+impl<'a, T: Clone, F: FnMut(&mut T) + Send> core::ops::Deref for TempRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+impl<'a, T: Clone, F: FnMut(&mut T) + Send> core::ops::DerefMut for TempRef<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+impl<'a, T: Clone, F: FnMut(&mut T) + Send> Drop for TempRef<'a, T, F> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            // SAFETY: `value` is not used after this.
+            unsafe { ManuallyDrop::drop(&mut self.value) };
+            return;
+        }
+        (self.reset)(&mut self.value);
+        // SAFETY: `value` is not used after this. If the reset function panicked, it is leaked instead.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        self.current.store(Arc::new(value));
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+/// A value which is read through `Arc` snapshots and reset after every write.
+///
+/// # Examples
+/// ```
+/// use tempref::rcu::Temp;
+///
+/// let config = Temp::new(vec![1, 2], |v| v.truncate(2));
+/// let before = config.load();
+/// {
+///     let mut guard = config.write();
+///     guard.push(3);
+///     assert_eq!(*config.load(), vec![1, 2]); // Readers don't see the clone being written.
+/// } // truncate(2) is called here, and then the clone is swapped in.
+/// assert_eq!(*before, vec![1, 2]);
+/// assert!(!std::sync::Arc::ptr_eq(&before, &config.load()));
+/// ```
+pub struct Temp<T: Clone, F: FnMut(&mut T) + Send = fn(&mut T)> {
+    current: ArcSwap<T>,
+    reset: Mutex<F>,
+    generation: AtomicUsize,
+}
+impl<T: Clone, F: FnMut(&mut T) + Send> Temp<T, F> {
+    /// A constructor of Temp<T, F>.
+    pub fn new(value: T, reset: F) -> Self {
+        Temp {
+            current: ArcSwap::from_pointee(value),
+            reset: Mutex::new(reset),
+            generation: AtomicUsize::new(0),
+        }
+    }
+    /// A constructor of Temp<T, F>.
+    ///
+    /// Unlike [`Self::new`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_with(mut value: T, mut reset: F) -> Self {
+        reset(&mut value);
+        Temp::new(value, reset)
+    }
+    /// Returns a snapshot of the current value without taking a lock.
+    pub fn load(&self) -> Arc<T> {
+        self.current.load_full()
+    }
+    /// Returns a [`TempRef`] to a clone of the current value, blocking while another writer is active.
+    ///
+    /// The value is only replaced when the guard is dropped, so a panicking writer cannot poison it.
+    pub fn write(&self) -> TempRef<'_, T, F> {
+        let reset = self.reset.lock().unwrap_or_else(PoisonError::into_inner);
+        self.guard(reset)
+    }
+    /// Returns a [`TempRef`] to a clone of the current value, or [`Error::WouldBlock`] if another writer is active.
+    pub fn try_write(&self) -> Result<TempRef<'_, T, F>, Error> {
+        let reset = match self.reset.try_lock() {
+            Ok(reset) => reset,
+            Err(TryLockError::Poisoned(err)) => err.into_inner(),
+            Err(TryLockError::WouldBlock) => return Err(Error::WouldBlock),
+        };
+        Ok(self.guard(reset))
+    }
+    fn guard<'a>(&'a self, reset: MutexGuard<'a, F>) -> TempRef<'a, T, F> {
+        TempRef {
+            value: ManuallyDrop::new(T::clone(&self.current.load())),
+            reset,
+            current: &self.current,
+            generation: &self.generation,
+        }
+    }
+    /// Returns how many reset values have been published.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+    /// Consumes the Temp, returning the current snapshot.
+    pub fn into_inner(self) -> Arc<T> {
+        self.current.into_inner()
+    }
+}
+impl<T: Clone + Debug, F: FnMut(&mut T) + Send> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Temp")
+            .field("value", &*self.current.load())
+            .finish()
+    }
+}
@@ -0,0 +1,206 @@
+//! Reentrant version of TempRef. This module requires std.
+//!
+//! A thread holding a [`TempRef`] can lock the same [`Temp`] again without deadlocking.
+//! The guards share the value through a `RefCell`, and only the outermost one calls the reset function when dropped.
+
+extern crate std;
+
+use core::{
+    cell::{Cell, RefCell, UnsafeCell},
+    fmt::Debug,
+    marker::PhantomData,
+    sync::atomic::{AtomicBool, AtomicUsize, Ordering},
+};
+use std::sync::{Condvar, Mutex, PoisonError};
+
+use crate::{Error, UnwindPolicy, unwind};
+
+std::thread_local! {
+    // Its address identifies the current thread while it is alive.
+    static THREAD_TOKEN: u8 = const { 0 };
+}
+fn thread_token() -> usize {
+    THREAD_TOKEN.with(|token| token as *const u8 as usize)
+}
+
+/// A reentrant lock of [`Temp<T, F>`], which dereferences to the `RefCell` holding the value.
+/// When the outermost guard of a thread is dropped, it calls the reset function.
+pub struct TempRef<'a, T, F: FnMut(&mut T)> {
+    temp: &'a Temp<T, F>,
+    // The guard must be dropped by the thread owning the lock.
+    _not_send: PhantomData<*const ()>,
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::Deref for TempRef<'a, T, F> {
+    type Target = RefCell<T>;
+    fn deref(&self) -> &Self::Target {
+        &self.temp.value
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> Drop for TempRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.temp.release();
+    }
+}
+
+/// A value wrapper like [`mutex::Temp`](crate::mutex::Temp) whose lock can be taken again by the thread holding it.
+///
+/// # Examples
+/// ```
+/// use tempref::reentrant::Temp;
+///
+/// let log = Temp::new(Vec::new(), |v: &mut Vec<&str>| v.clear());
+/// let callback = || log.lock().borrow_mut().push("inner");
+/// {
+///     let outer = log.lock();
+///     outer.borrow_mut().push("outer");
+///     callback(); // Does not deadlock, and does not reset.
+///     assert_eq!(*outer.borrow(), ["outer", "inner"]);
+/// } // The reset function is called here.
+/// assert!(log.lock().borrow().is_empty());
+/// ```
+pub struct Temp<T, F: FnMut(&mut T) = fn(&mut T)> {
+    locked: Mutex<bool>,
+    unlocked: Condvar,
+    owner: AtomicUsize,
+    depth: Cell<usize>,
+    value: RefCell<T>,
+    reset: UnsafeCell<F>,
+    reset_poisoned: AtomicBool,
+    generation: AtomicUsize,
+}
+// SAFETY: `depth`, `value` and `reset` are only accessed by the thread which owns the lock.
+unsafe impl<T: Send, F: FnMut(&mut T) + Send> Sync for Temp<T, F> {}
+impl<T, F: FnMut(&mut T)> Temp<T, F> {
+    /// A constructor of Temp<T, F>.
+    pub const fn new(value: T, reset: F) -> Self {
+        Temp {
+            locked: Mutex::new(false),
+            unlocked: Condvar::new(),
+            owner: AtomicUsize::new(0),
+            depth: Cell::new(0),
+            value: RefCell::new(value),
+            reset: UnsafeCell::new(reset),
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+        }
+    }
+    /// A constructor of Temp<T, F>.
+    ///
+    /// Unlike [`Self::new`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_with(mut value: T, mut reset: F) -> Self {
+        reset(&mut value);
+        Temp::new(value, reset)
+    }
+    /// Acquires the lock, blocking the current thread until it is able to do so.
+    /// If the current thread already holds it, returns another guard immediately.
+    pub fn lock(&self) -> TempRef<'_, T, F> {
+        let token = thread_token();
+        if self.owner.load(Ordering::Relaxed) != token {
+            let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+            while *locked {
+                locked = self
+                    .unlocked
+                    .wait(locked)
+                    .unwrap_or_else(PoisonError::into_inner);
+            }
+            *locked = true;
+            self.owner.store(token, Ordering::Relaxed);
+        }
+        self.enter()
+    }
+    /// Attempts to acquire the lock.
+    /// If another thread holds it, then [`Error::WouldBlock`] is returned.
+    pub fn try_lock(&self) -> Result<TempRef<'_, T, F>, Error> {
+        let token = thread_token();
+        if self.owner.load(Ordering::Relaxed) != token {
+            let mut locked = self.locked.lock().unwrap_or_else(PoisonError::into_inner);
+            if *locked {
+                return Err(Error::WouldBlock);
+            }
+            *locked = true;
+            self.owner.store(token, Ordering::Relaxed);
+        }
+        Ok(self.enter())
+    }
+    /// Returns how many times the outermost guard has reset the value.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    ///
+    /// The lock is released even if the reset function panics.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.load(Ordering::Acquire)
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.store(false, Ordering::Release);
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be alive and the reset function is not called.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+
+    fn enter(&self) -> TempRef<'_, T, F> {
+        self.depth.set(self.depth.get() + 1);
+        TempRef {
+            temp: self,
+            _not_send: PhantomData,
+        }
+    }
+    fn release(&self) {
+        let depth = self.depth.get() - 1;
+        self.depth.set(depth);
+        if depth > 0 {
+            return;
+        }
+        // Declared before the borrow of the value, so it unlocks after the borrow ends, even if the reset panics.
+        let _unlock = Unlock(self);
+        // Every guard has been dropped, so nothing borrows the value.
+        let value = &mut *self.value.borrow_mut();
+        // SAFETY: Only the thread owning the lock accesses `reset`.
+        let reset = unsafe { &mut *self.reset.get() };
+        unwind::reset_on_drop(UnwindPolicy::Reset, value, reset, || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+        self.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+// Gives up the lock of the current thread when dropped.
+struct Unlock<'a, T, F: FnMut(&mut T)>(&'a Temp<T, F>);
+impl<'a, T, F: FnMut(&mut T)> Drop for Unlock<'a, T, F> {
+    fn drop(&mut self) {
+        let temp = self.0;
+        temp.depth.set(0);
+        temp.owner.store(0, Ordering::Relaxed);
+        *temp.locked.lock().unwrap_or_else(PoisonError::into_inner) = false;
+        temp.unlocked.notify_one();
+    }
+}
+/// Formats the value without blocking or resetting it, printing `<locked>` instead while another thread holds the lock.
+impl<T: Debug, F: FnMut(&mut T)> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("Temp");
+        // Holding `locked` while it is false keeps other threads from taking the lock.
+        let locked = self.locked.try_lock();
+        let free = matches!(&locked, Ok(locked) if !**locked);
+        if free || self.owner.load(Ordering::Relaxed) == thread_token() {
+            match self.value.try_borrow() {
+                Ok(value) => d.field("value", &*value),
+                Err(_) => d.field("value", &format_args!("<borrowed>")),
+            };
+        } else {
+            d.field("value", &format_args!("<locked>"));
+        }
+        d.finish()
+    }
+}
@@ -0,0 +1,107 @@
+//! A process-wide registry of `Temp`s under string names. This module requires std.
+//!
+//! A `Temp` shared through an `Arc` is [`register`]ed under a name, and can then be looked up with [`get`], reset with
+//! [`reset`] or listed with [`names`] from anywhere in the program, e.g. by a debug dump or an admin endpoint which
+//! frees the scratch memory of a whole application.
+//!
+//! The registry only keeps the `Arc`, so a registered `Temp` lives until it is [`unregister`]ed.
+
+extern crate std;
+
+use alloc::{
+    collections::{BTreeMap, btree_map::Entry},
+    string::String,
+    sync::Arc,
+    vec::Vec,
+};
+use core::any::Any;
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::{Error, ResetErrors, TempLike};
+
+struct Registered {
+    temp: Arc<dyn TempLike + Send + Sync>,
+    any: Arc<dyn Any + Send + Sync>,
+}
+
+static REGISTRY: Mutex<BTreeMap<String, Registered>> = Mutex::new(BTreeMap::new());
+
+// The map is never left half-updated by a panic, so a poisoned registry is used as usual.
+fn registry() -> MutexGuard<'static, BTreeMap<String, Registered>> {
+    REGISTRY.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Registers `temp` under `name`, or gives it back if another `Temp` is already registered under `name`.
+///
+/// # Examples
+/// ```
+/// use std::sync::Arc;
+/// use tempref::{mutex, registry};
+///
+/// let buf = Arc::new(mutex::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear()));
+/// registry::register("http.request_buf", buf.clone()).unwrap();
+/// assert!(registry::register("http.request_buf", buf.clone()).is_err());
+///
+/// buf.set(vec![1, 2, 3]).unwrap(); // Not reset by a guard.
+/// assert_eq!(registry::reset("http.request_buf"), Some(Ok(())));
+/// assert!(buf.lock_read().unwrap().is_empty());
+/// assert!(registry::unregister("http.request_buf"));
+/// ```
+pub fn register<T>(name: impl Into<String>, temp: Arc<T>) -> Result<(), Arc<T>>
+where
+    T: TempLike + Any + Send + Sync,
+{
+    let mut registry = registry();
+    match registry.entry(name.into()) {
+        Entry::Occupied(_) => Err(temp),
+        Entry::Vacant(entry) => {
+            entry.insert(Registered {
+                temp: temp.clone(),
+                any: temp,
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Removes the `Temp` registered under `name`, returning false if there is none.
+pub fn unregister(name: &str) -> bool {
+    registry().remove(name).is_some()
+}
+
+/// Returns the `Temp` registered under `name`, or None if there is none or it is not a `T`.
+///
+/// `T` must be named, so a `Temp` which is looked up should have a nameable reset function, as `TempFn` does.
+pub fn get<T: Any + Send + Sync>(name: &str) -> Option<Arc<T>> {
+    let any = registry().get(name)?.any.clone();
+    any.downcast().ok()
+}
+
+/// Returns true if a `Temp` is registered under `name`.
+pub fn contains(name: &str) -> bool {
+    registry().contains_key(name)
+}
+
+/// Resets the `Temp` registered under `name`, or returns None if there is none.
+///
+/// The registry is not locked while the `Temp` is reset, so a `mutex` or `rwlock` `Temp` which is locked elsewhere
+/// only blocks this call.
+pub fn reset(name: &str) -> Option<Result<(), Error>> {
+    let temp = registry().get(name)?.temp.clone();
+    Some(temp.reset_temp())
+}
+
+/// Resets every registered `Temp` in the order of [`names`], and returns the failures if any, with their positions in
+/// that order.
+pub fn reset_all() -> Result<(), ResetErrors> {
+    let temps: Vec<_> = registry()
+        .values()
+        .map(|entry| entry.temp.clone())
+        .collect();
+    crate::reset_all(temps.iter().map(|temp| &**temp))
+}
+
+/// Returns the names of the registered `Temp`s, in sorted order.
+pub fn names() -> Vec<String> {
+    registry().keys().cloned().collect()
+}
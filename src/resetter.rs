@@ -0,0 +1,82 @@
+//! Periodic resets of the std backends from a background thread. This module requires std.
+
+extern crate std;
+
+use core::time::Duration;
+use std::sync::{Arc, Condvar, Mutex, PoisonError, Weak};
+use std::thread::{self, JoinHandle};
+
+/// A background thread which resets a `Temp` periodically, returned by `spawn_resetter()`.
+///
+/// Dropping the handle stops the thread and waits for it to finish.
+/// The thread also finishes by itself once the `Temp` has been dropped.
+#[must_use = "dropping the handle stops the thread"]
+pub struct ResetterHandle {
+    stopped: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+impl ResetterHandle {
+    /// Stops the thread and waits for it to finish, like dropping the handle.
+    pub fn stop(self) {}
+}
+impl Drop for ResetterHandle {
+    fn drop(&mut self) {
+        let (stopped, wake) = &*self.stopped;
+        *stopped.lock().unwrap_or_else(PoisonError::into_inner) = true;
+        wake.notify_one();
+        if let Some(thread) = self.thread.take() {
+            // A panic of the reset function has already been reported by the thread.
+            let _ = thread.join();
+        }
+    }
+}
+impl core::fmt::Debug for ResetterHandle {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ResetterHandle").finish_non_exhaustive()
+    }
+}
+
+// Spawns a thread calling `reset` on `temp` every `interval` until the handle is dropped or `temp` is gone.
+// Panics if `interval` is zero, which would keep the thread spinning.
+#[track_caller]
+pub(crate) fn spawn<T: Send + Sync + 'static>(
+    temp: Weak<T>,
+    interval: Duration,
+    reset: fn(&T),
+) -> ResetterHandle {
+    assert!(
+        !interval.is_zero(),
+        "the interval of a resetter must not be zero"
+    );
+    let stopped = Arc::new((Mutex::new(false), Condvar::new()));
+    let thread = {
+        let stopped = stopped.clone();
+        thread::Builder::new()
+            .name("tempref-resetter".into())
+            .spawn(move || {
+                let (stopped, wake) = &*stopped;
+                loop {
+                    let (guard, _) = wake
+                        .wait_timeout_while(
+                            stopped.lock().unwrap_or_else(PoisonError::into_inner),
+                            interval,
+                            |stopped| !*stopped,
+                        )
+                        .unwrap_or_else(PoisonError::into_inner);
+                    if *guard {
+                        return;
+                    }
+                    drop(guard);
+                    match temp.upgrade() {
+                        Some(temp) => reset(&temp),
+                        None => return,
+                    }
+                }
+            })
+            .expect("failed to spawn the resetter thread")
+    };
+    ResetterHandle {
+        stopped,
+        thread: Some(thread),
+    }
+}
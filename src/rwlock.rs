@@ -2,70 +2,846 @@
 
 extern crate std;
 
-use core::cell::UnsafeCell;
-use core::fmt::Debug;
-use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError};
+use core::fmt::{Debug, Display};
+use core::marker::PhantomData;
+use core::mem;
+use core::mem::ManuallyDrop;
+use core::ptr::{self, NonNull};
+use core::time::Duration;
+use std::boxed::Box;
+use std::rc::Rc;
+use std::sync::{Arc, PoisonError, TryLockError};
+
+#[cfg(feature = "hooks")]
+use crate::TempHooks;
+use crate::clock::ResetClock;
+use crate::hooks::Hooks;
+use crate::instrument::Instrument;
+#[cfg(feature = "stats")]
+use crate::instrument::Stats;
+#[cfg(not(loom))]
+use crate::resetter::{self, ResetterHandle};
+#[cfg(feature = "upgradable")]
+use crate::sync::MutexGuard;
+#[cfg(loom)]
+use crate::sync::Poison;
+use crate::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use crate::sync::{Mutex, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use crate::timeout::Waiters;
+#[cfg(feature = "watch")]
+use crate::watch::{self, Watcher};
+use crate::{UnwindPolicy, spin, unwind};
 
 type WriteResult<T> = Result<T, PoisonError<T>>;
+type ReadResult<T> = Result<T, PoisonError<T>>;
 type TryLockResult<T> = Result<T, TryLockError<T>>;
+// The gate which every writer passes through, and the write lock taken after it.
+type Gated<'a, T, F> = (GateGuard<'a>, RwLockWriteGuard<'a, Slot<T, F>>);
+
+// The gate which every writer passes through with the `upgradable` feature, so that an upgradable reader or a
+// downgraded `TempRef` can keep the other writers out. Without the feature nothing can keep a writer out, so the gate
+// is never locked and a writer only takes the write lock.
+#[cfg(feature = "upgradable")]
+struct Gate(Mutex<()>);
+#[cfg(not(feature = "upgradable"))]
+struct Gate;
+#[cfg(feature = "upgradable")]
+type GateGuard<'a> = MutexGuard<'a, ()>;
+#[cfg(not(feature = "upgradable"))]
+type GateGuard<'a> = PhantomData<&'a ()>;
+impl Gate {
+    #[cfg(all(feature = "upgradable", not(loom)))]
+    const fn new() -> Self {
+        Gate(Mutex::new(()))
+    }
+    #[cfg(all(feature = "upgradable", loom))]
+    fn new() -> Self {
+        Gate(Mutex::new(()))
+    }
+    #[cfg(not(feature = "upgradable"))]
+    const fn new() -> Self {
+        Gate
+    }
+    // The gate guards no data, so it is passed through even if it is poisoned.
+    #[cfg(feature = "upgradable")]
+    fn lock(&self) -> GateGuard<'_> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+    #[cfg(feature = "upgradable")]
+    fn try_lock(&self) -> Option<GateGuard<'_>> {
+        match self.0.try_lock() {
+            Ok(gate) => Some(gate),
+            Err(TryLockError::Poisoned(err)) => Some(err.into_inner()),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+    #[cfg(not(feature = "upgradable"))]
+    fn lock(&self) -> GateGuard<'_> {
+        PhantomData
+    }
+    #[cfg(not(feature = "upgradable"))]
+    fn try_lock(&self) -> Option<GateGuard<'_>> {
+        Some(PhantomData)
+    }
+}
+
+// The predicate set with `with_reset_if()`.
+type ResetIf<T> = Arc<dyn Fn(&T) -> bool + Send + Sync>;
+
+// The value and its reset function, locked together so that only a writer can call the reset function.
+struct Slot<T, F> {
+    value: T,
+    reset: F,
+}
+
+// The reset function followed by the `on_reset` hook, inside the span of the reset.
+// It borrows the fields of the `Temp` it uses, so that `get_mut_reset` can call it while the value is borrowed.
+fn hooked_reset<'r, T, F: FnMut(&mut T)>(
+    reset: &'r mut F,
+    generation: &'r AtomicUsize,
+    clock: &'r ResetClock,
+    hooks: &'r Hooks<T>,
+    instrument: &'r Instrument,
+) -> impl FnMut(&mut T) + 'r {
+    move |value: &mut T| {
+        let _span = instrument.enter_reset::<T>();
+        reset(value);
+        generation.fetch_add(1, Ordering::Release);
+        clock.reset();
+        hooks.reset(value);
+    }
+}
+
+// The `Temp` of a lock which is held, which wakes up the threads in `read_timeout` and `write_timeout` when it is
+// dropped. It is declared after the guards of the locks, so that it is dropped once they have been released.
+struct Notify<'a, T: Send, F: FnMut(&mut T) + Send>(&'a Temp<T, F>);
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for Notify<'a, T, F> {
+    type Target = Temp<T, F>;
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for Notify<'a, T, F> {
+    fn drop(&mut self) {
+        self.0.waiters.notify();
+    }
+}
 
 /// A mutable reference wrapper from [`Temp<T, F>`].
 ///
 /// When dropped, it automatically calls the reset function on the underlying value.
 /// This ensures that temporary mutations never leave the value in an inconsistent state.
-pub struct TempRef<'a, T: Send, F: FnMut(&mut T) + Sync> {
-    re: RwLockWriteGuard<'a, T>,
-    reset: &'a mut F,
+///
+/// With the `upgradable` feature, it keeps holding the gate which every writer passes through, so that `downgrade()`
+/// can write again without another writer in between. Without it, the guard only holds the write lock.
+pub struct TempRef<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: RwLockWriteGuard<'a, Slot<T, F>>,
+    gate: GateGuard<'a>,
+    temp: Notify<'a, T, F>,
 }
-impl<'a, T: Send, F: FnMut(&mut T) + Sync> TempRef<'a, T, F> {
-    fn new(re: RwLockWriteGuard<'a, T>, reset: &'a mut F) -> Self {
-        TempRef { re, reset }
+impl<'a, T: Send, F: FnMut(&mut T) + Send> TempRef<'a, T, F> {
+    fn new((gate, mut re): Gated<'a, T, F>, temp: &'a Temp<T, F>) -> Self {
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re.value);
+        temp.instrument.acquire::<T>();
+        TempRef {
+            re,
+            gate,
+            temp: Notify(temp),
+        }
     }
     fn write(temp: &'a Temp<T, F>) -> WriteResult<Self> {
-        let reset = unsafe { &mut *temp.reset.get() };
-        match temp.value.write() {
-            Ok(guard) => Ok(TempRef::new(guard, reset)),
-            Err(err) => Err(PoisonError::new(TempRef::new(err.into_inner(), reset))),
+        match temp.write_gated() {
+            Ok(guard) => Ok(TempRef::new(guard, temp)),
+            Err(err) => Err(PoisonError::new(TempRef::new(err.into_inner(), temp))),
         }
     }
     fn try_write(temp: &'a Temp<T, F>) -> TryLockResult<Self> {
-        let reset = unsafe { &mut *temp.reset.get() };
-        match temp.value.try_write() {
-            Ok(guard) => Ok(TempRef::new(guard, reset)),
+        match temp.try_write_gated() {
+            Ok(guard) => Ok(TempRef::new(guard, temp)),
             Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new(
-                TempRef::new(err.into_inner(), reset),
+                TempRef::new(err.into_inner(), temp),
             ))),
             Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
         }
     }
 
     /// Invokes the reset function on the internal value.
+    ///
+    /// If the reset function panics, the `Temp` is marked as [reset-poisoned](Temp::is_reset_poisoned).
     pub fn reset(&mut self) {
-        (self.reset)(&mut self.re);
+        self.temp.reset_or_poison(&mut self.re);
+    }
+    /// Atomically downgrades the write lock into a [`ReadAfterWrite`] without calling the reset function.
+    ///
+    /// The reset function is called when the returned guard is dropped, after the read lock is released and the
+    /// write lock is acquired again. The guard keeps other writers out until then, so the reset function runs on
+    /// the value as this guard left it.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::{Temp, TempRef};
+    ///
+    /// let workspace = Temp::new(0, |n| *n = 0);
+    ///
+    /// {
+    ///     let mut guard = workspace.write().unwrap();
+    ///     *guard = 1;
+    ///     let published = TempRef::downgrade(guard);
+    ///     assert_eq!(*published, 1);
+    ///     assert_eq!(*workspace.read().unwrap(), 1);
+    /// }
+    /// assert_eq!(*workspace.read().unwrap(), 0);
+    /// ```
+    #[cfg(all(feature = "upgradable", not(loom)))]
+    pub fn downgrade(orig: TempRef<'a, T, F>) -> ReadAfterWrite<'a, T, F> {
+        let ((gate, re), temp) = orig.into_parts();
+        ReadAfterWrite {
+            re: ManuallyDrop::new(RwLockWriteGuard::downgrade(re)),
+            gate: ManuallyDrop::new(gate),
+            temp,
+        }
+    }
+    /// Makes a [`MappedTempRef`] for a component of the write-locked data.
+    ///
+    /// The reset function still runs on the whole value when the returned reference is dropped.
+    pub fn map<U: ?Sized, M: FnOnce(&mut T) -> &mut U>(
+        mut orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, U, F> {
+        let value = NonNull::from(f(&mut orig));
+        MappedTempRef::new(value, Owner::Unique(orig))
+    }
+    /// Splits a `TempRef` into multiple [`MappedTempRef`]s for different components of the write-locked data.
+    ///
+    /// The reset function runs on the whole value once both returned references are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, M: FnOnce(&mut T) -> (&mut U, &mut V)>(
+        mut orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, U, F>, MappedTempRef<'a, T, V, F>) {
+        let (a, b) = f(&mut orig);
+        let (a, b) = (NonNull::from(a), NonNull::from(b));
+        let owner = Rc::new(orig);
+        (
+            MappedTempRef::new(a, Owner::Shared(owner.clone())),
+            MappedTempRef::new(b, Owner::Shared(owner)),
+        )
+    }
+    /// Moves the value out of the guard, leaving `T::default()` in its place, without cloning it.
+    ///
+    /// If `reset` is true, the reset function runs on the default value when the guard is dropped as usual.
+    /// Otherwise the default value is left as it is.
+    pub fn take(orig: TempRef<'a, T, F>, reset: bool) -> T
+    where
+        T: Default,
+    {
+        let mut orig = orig;
+        let value = mem::take(&mut *orig);
+        if !reset {
+            let ((gate, re), temp) = orig.into_parts();
+            temp.hooks.release(&re.value);
+            temp.instrument.release::<T>();
+            // The write lock is released before the gate.
+            drop((re, gate));
+        }
+        value
+    }
+
+    fn into_parts(self) -> (Gated<'a, T, F>, Notify<'a, T, F>) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `re`, `gate` and `temp` are moved out exactly once.
+        unsafe {
+            (
+                (ptr::read(&this.gate), ptr::read(&this.re)),
+                ptr::read(&this.temp),
+            )
+        }
     }
 }
-impl<'a, T: Send, F: FnMut(&mut T) + Sync> core::ops::Deref for TempRef<'a, T, F> {
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for TempRef<'a, T, F> {
     type Target = T;
     fn deref(&self) -> &Self::Target {
-        &self.re
+        &self.re.value
     }
 }
-impl<'a, T: Send, F: FnMut(&mut T) + Sync> core::ops::DerefMut for TempRef<'a, T, F> {
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::DerefMut for TempRef<'a, T, F> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.re
+        &mut self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsRef<T> for TempRef<'a, T, F> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsMut<T> for TempRef<'a, T, F> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::borrow::Borrow<T> for TempRef<'a, T, F> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::borrow::BorrowMut<T> for TempRef<'a, T, F> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T: core::fmt::Write + Send, F: FnMut(&mut T) + Send> core::fmt::Write
+    for TempRef<'a, T, F>
+{
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.re.value.write_str(s)
+    }
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        self.re.value.write_char(c)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        self.re.value.write_fmt(args)
+    }
+}
+impl<'a, T: std::io::Read + Send, F: FnMut(&mut T) + Send> std::io::Read for TempRef<'a, T, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.re.value.read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        self.re.value.read_vectored(bufs)
+    }
+    fn read_to_end(&mut self, buf: &mut std::vec::Vec<u8>) -> std::io::Result<usize> {
+        self.re.value.read_to_end(buf)
+    }
+    fn read_to_string(&mut self, buf: &mut std::string::String) -> std::io::Result<usize> {
+        self.re.value.read_to_string(buf)
+    }
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.re.value.read_exact(buf)
+    }
+}
+impl<'a, T: std::io::Write + Send, F: FnMut(&mut T) + Send> std::io::Write for TempRef<'a, T, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.re.value.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.re.value.write_vectored(bufs)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.re.value.flush()
+    }
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.re.value.write_all(buf)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> std::io::Result<()> {
+        self.re.value.write_fmt(args)
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for TempRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.temp.release(&mut self.re);
+    }
+}
+impl<'a, T: Send + Debug, F: FnMut(&mut T) + Send> Debug for TempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempRef")
+            .field("value", &self.re.value)
+            .finish()
+    }
+}
+impl<'a, T: Send + Display, F: FnMut(&mut T) + Send> Display for TempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// Archives the value alone, so `Archived<TempRef<'_, T, F>>` is `Archived<T>`.
+///
+/// The guard holds the lock, so the value cannot change between serializing and resolving it. The archive deserializes back into a `T`.
+#[cfg(feature = "rkyv")]
+impl<'a, T: rkyv::Archive + Send, F: FnMut(&mut T) + Send> rkyv::Archive for TempRef<'a, T, F> {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        (**self).resolve(resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<'a, T, F, S> rkyv::Serialize<S> for TempRef<'a, T, F>
+where
+    T: rkyv::Serialize<S> + Send,
+    F: FnMut(&mut T) + Send,
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
+
+/// A shared reference downgraded from [`TempRef`].
+///
+/// Created by [`TempRef::downgrade`].
+/// When dropped, it releases the read lock, acquires the write lock again and calls the reset function.
+/// It keeps other writers and upgradable readers out until then, so no other modification can happen in between,
+/// but other readers can still read the value.
+///
+/// Dropping it blocks until the other readers are gone, so it deadlocks if the same thread still holds a read guard
+/// of the same `Temp`.
+#[cfg(all(feature = "upgradable", not(loom)))]
+pub struct ReadAfterWrite<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: ManuallyDrop<RwLockReadGuard<'a, Slot<T, F>>>,
+    gate: ManuallyDrop<MutexGuard<'a, ()>>,
+    temp: Notify<'a, T, F>,
+}
+#[cfg(all(feature = "upgradable", not(loom)))]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> ReadAfterWrite<'a, T, F> {
+    /// Returns the underlying read guard, so that the reset function is not called at all.
+    ///
+    /// Other writers can acquire the lock once the returned guard and the other readers are gone.
+    pub fn dismiss(self) -> ReadRef<'a, T, F> {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `re` and `gate` are taken exactly once.
+        let (re, gate) = unsafe {
+            (
+                ManuallyDrop::take(&mut this.re),
+                ManuallyDrop::take(&mut this.gate),
+            )
+        };
+        this.temp.hooks.release(&re.value);
+        this.temp.instrument.release::<T>();
+        drop(gate);
+        ReadRef {
+            re,
+            _notify: Notify(this.temp.0),
+        }
+    }
+}
+#[cfg(all(feature = "upgradable", not(loom)))]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for ReadAfterWrite<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+#[cfg(all(feature = "upgradable", not(loom)))]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for ReadAfterWrite<'a, T, F> {
+    fn drop(&mut self) {
+        // SAFETY: `re` is not used after this point.
+        unsafe { ManuallyDrop::drop(&mut self.re) };
+        // The gate is still held, so only readers can be ahead of this. The lock is still counted as held by the
+        // deadlock check and the stats, so it is acquired directly.
+        let mut guard = self
+            .temp
+            .value
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        self.temp.release(&mut guard);
+        drop(guard);
+        // SAFETY: `gate` is not used after this point, and it is released after the write lock.
+        unsafe { ManuallyDrop::drop(&mut self.gate) };
+    }
+}
+#[cfg(all(feature = "upgradable", not(loom)))]
+impl<'a, T: Send + Debug, F: FnMut(&mut T) + Send> Debug for ReadAfterWrite<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReadAfterWrite")
+            .field("value", &self.re.value)
+            .finish()
+    }
+}
+#[cfg(all(feature = "upgradable", not(loom)))]
+impl<'a, T: Send + Display, F: FnMut(&mut T) + Send> Display for ReadAfterWrite<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A shared reference which can be upgraded to a [`TempRef`].
+///
+/// Created by [`Temp::upgradable_read`]. Only one upgradable reader can exist at a time,
+/// and no writer can acquire the lock while it is held, so upgrading never lets another writer in between.
+#[cfg(feature = "upgradable")]
+pub struct UpgradableRead<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: RwLockReadGuard<'a, Slot<T, F>>,
+    gate: MutexGuard<'a, ()>,
+    temp: Notify<'a, T, F>,
+}
+#[cfg(feature = "upgradable")]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> UpgradableRead<'a, T, F> {
+    fn new(
+        re: RwLockReadGuard<'a, Slot<T, F>>,
+        gate: MutexGuard<'a, ()>,
+        temp: &'a Temp<T, F>,
+    ) -> Self {
+        UpgradableRead {
+            re,
+            gate,
+            temp: Notify(temp),
+        }
+    }
+    fn read(temp: &'a Temp<T, F>) -> ReadResult<Self> {
+        // Only the gate can block, since a holder of the gate is the only thread which can write.
+        let gate = temp
+            .instrument
+            .lock::<T, _>(|| temp.gate.0.try_lock(), || temp.gate.0.lock())
+            .unwrap_or_else(PoisonError::into_inner);
+        match temp.value.read() {
+            Ok(guard) => Ok(UpgradableRead::new(guard, gate, temp)),
+            Err(err) => Err(PoisonError::new(UpgradableRead::new(
+                err.into_inner(),
+                gate,
+                temp,
+            ))),
+        }
+    }
+    fn try_read(temp: &'a Temp<T, F>) -> TryLockResult<Self> {
+        let Some(gate) = temp.gate.try_lock() else {
+            return Err(TryLockError::WouldBlock);
+        };
+        match temp.value.try_read() {
+            Ok(guard) => Ok(UpgradableRead::new(guard, gate, temp)),
+            Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new(
+                UpgradableRead::new(err.into_inner(), gate, temp),
+            ))),
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Upgrades the read access to exclusive write access, blocking the current thread until the other readers are gone.
+    /// The returned `TempRef` automatically resets itself when dropped.
+    pub fn upgrade(orig: UpgradableRead<'a, T, F>) -> WriteResult<TempRef<'a, T, F>> {
+        let UpgradableRead { re, gate, temp } = orig;
+        drop(re);
+        let temp = temp.0;
+        // The gate is handed over to the guard, so no writer can get in between.
+        match temp.value.write() {
+            Ok(guard) => Ok(TempRef::new((gate, guard), temp)),
+            Err(err) => Err(PoisonError::new(TempRef::new(
+                (gate, err.into_inner()),
+                temp,
+            ))),
+        }
+    }
+}
+#[cfg(feature = "upgradable")]
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for UpgradableRead<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+#[cfg(feature = "upgradable")]
+impl<'a, T: Send + Debug, F: FnMut(&mut T) + Send> Debug for UpgradableRead<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UpgradableRead")
+            .field("value", &self.re.value)
+            .finish()
+    }
+}
+#[cfg(feature = "upgradable")]
+impl<'a, T: Send + Display, F: FnMut(&mut T) + Send> Display for UpgradableRead<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A mutable reference to a component of a value write-locked from [`Temp<T, F>`].
+///
+/// Created by [`TempRef::map`] and [`TempRef::map_split`].
+/// The reset function runs on the whole value when the last reference mapped from the same `TempRef` is dropped.
+pub struct MappedTempRef<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> {
+    value: NonNull<U>,
+    owner: Owner<'a, T, F>,
+    marker: PhantomData<&'a mut U>,
+}
+enum Owner<'a, T: Send, F: FnMut(&mut T) + Send> {
+    Unique(TempRef<'a, T, F>),
+    Shared(Rc<TempRef<'a, T, F>>),
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> MappedTempRef<'a, T, U, F> {
+    fn new(value: NonNull<U>, owner: Owner<'a, T, F>) -> Self {
+        MappedTempRef {
+            value,
+            owner,
+            marker: PhantomData,
+        }
+    }
+
+    /// Makes a new `MappedTempRef` for a component of the write-locked data.
+    pub fn map<V: ?Sized, M: FnOnce(&mut U) -> &mut V>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, V, F> {
+        let MappedTempRef {
+            mut value, owner, ..
+        } = orig;
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        let value = NonNull::from(f(unsafe { value.as_mut() }));
+        MappedTempRef::new(value, owner)
+    }
+    /// Splits a `MappedTempRef` into multiple `MappedTempRef`s for different components of the write-locked data.
+    pub fn map_split<V: ?Sized, W: ?Sized, M: FnOnce(&mut U) -> (&mut V, &mut W)>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, V, F>, MappedTempRef<'a, T, W, F>) {
+        let MappedTempRef {
+            mut value, owner, ..
+        } = orig;
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        let (a, b) = f(unsafe { value.as_mut() });
+        let (a, b) = (NonNull::from(a), NonNull::from(b));
+        let owner = match owner {
+            Owner::Unique(guard) => Rc::new(guard),
+            Owner::Shared(guard) => guard,
+        };
+        (
+            MappedTempRef::new(a, Owner::Shared(owner.clone())),
+            MappedTempRef::new(b, Owner::Shared(owner)),
+        )
+    }
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> core::ops::Deref
+    for MappedTempRef<'a, T, U, F>
+{
+    type Target = U;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        unsafe { self.value.as_ref() }
+    }
+}
+impl<'a, T: Send, U: ?Sized, F: FnMut(&mut T) + Send> core::ops::DerefMut
+    for MappedTempRef<'a, T, U, F>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: `value` points into the data locked by `owner`, which is still alive.
+        unsafe { self.value.as_mut() }
+    }
+}
+impl<'a, T: Send, U: Debug + ?Sized, F: FnMut(&mut T) + Send> Debug for MappedTempRef<'a, T, U, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MappedTempRef")
+            .field("value", &&**self)
+            .finish()
+    }
+}
+impl<'a, T: Send, U: Display + ?Sized, F: FnMut(&mut T) + Send> Display
+    for MappedTempRef<'a, T, U, F>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A mutable reference from an `Arc<Temp<T, F>>`.
+///
+/// Created by [`Temp::write_arc`]. Unlike [`TempRef`], it keeps the `Temp` alive by itself and has no lifetime,
+/// so it can be stored in `'static` contexts.
+/// When dropped, it calls the reset function before releasing the `Arc`.
+///
+/// It is not `Send`, and a `Send` version is not provided: it holds a guard of `std`, which has to be released by the
+/// thread that acquired the lock, since some platforms unlock a lock only from its owning thread. To move the work to
+/// another thread, send a clone of the `Arc` and call `write_arc()` there.
+///
+/// Moving it into a spawned thread does not compile:
+/// ```compile_fail
+/// use std::sync::Arc;
+/// use tempref::rwlock::Temp;
+///
+/// let workspace = Arc::new(Temp::new(0, |n| *n = 0));
+/// let guard = workspace.write_arc().unwrap();
+/// std::thread::spawn(move || drop(guard));
+/// ```
+pub struct OwnedTempRef<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> {
+    // Declared before `temp` so that it is dropped first.
+    guard: TempRef<'static, T, F>,
+    temp: Arc<Temp<T, F>>,
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> OwnedTempRef<T, F> {
+    fn write(temp: &Arc<Temp<T, F>>) -> WriteResult<Self> {
+        let temp = Arc::clone(temp);
+        // SAFETY: the guard borrows the `Temp` owned by `temp`, which is dropped after the guard.
+        let target: &'static Temp<T, F> = unsafe { &*Arc::as_ptr(&temp) };
+        match TempRef::write(target) {
+            Ok(guard) => Ok(OwnedTempRef { guard, temp }),
+            Err(err) => Err(PoisonError::new(OwnedTempRef {
+                guard: err.into_inner(),
+                temp,
+            })),
+        }
+    }
+    fn try_write(temp: &Arc<Temp<T, F>>) -> TryLockResult<Self> {
+        let temp = Arc::clone(temp);
+        // SAFETY: the guard borrows the `Temp` owned by `temp`, which is dropped after the guard.
+        let target: &'static Temp<T, F> = unsafe { &*Arc::as_ptr(&temp) };
+        match TempRef::try_write(target) {
+            Ok(guard) => Ok(OwnedTempRef { guard, temp }),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(OwnedTempRef {
+                    guard: err.into_inner(),
+                    temp,
+                })))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+
+    /// Invokes the reset function on the internal value.
+    pub fn reset(&mut self) {
+        self.guard.reset();
+    }
+    /// Returns the `Arc` which owns the locked `Temp`.
+    pub fn temp(&self) -> &Arc<Temp<T, F>> {
+        &self.temp
+    }
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> core::ops::Deref for OwnedTempRef<T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+impl<T: Send + 'static, F: FnMut(&mut T) + Send + 'static> core::ops::DerefMut
+    for OwnedTempRef<T, F>
+{
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+impl<T: Debug + Send + 'static, F: FnMut(&mut T) + Send + 'static> Debug for OwnedTempRef<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("OwnedTempRef")
+            .field("value", &*self.guard)
+            .finish()
+    }
+}
+impl<T: Display + Send + 'static, F: FnMut(&mut T) + Send + 'static> Display
+    for OwnedTempRef<T, F>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A transactional mutable reference from [`Temp<T, F>`].
+///
+/// Changes made through it are kept only if [`Self::commit`] is called.
+/// Otherwise the reset function is called when it is dropped,
+/// including while unwinding from a panic unless the `Temp` uses [`UnwindPolicy::Skip`].
+pub struct Transaction<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: RwLockWriteGuard<'a, Slot<T, F>>,
+    temp: Notify<'a, T, F>,
+    committed: bool,
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Transaction<'a, T, F> {
+    fn new(mut re: RwLockWriteGuard<'a, Slot<T, F>>, temp: &'a Temp<T, F>) -> Self {
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re.value);
+        temp.instrument.acquire::<T>();
+        Transaction {
+            re,
+            temp: Notify(temp),
+            committed: false,
+        }
+    }
+    fn write(temp: &'a Temp<T, F>) -> WriteResult<Self> {
+        match temp.write_value() {
+            Ok(guard) => Ok(Transaction::new(guard, temp)),
+            Err(err) => Err(PoisonError::new(Transaction::new(err.into_inner(), temp))),
+        }
+    }
+
+    /// Ends the transaction, keeping the changes without calling the reset function.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+    /// Ends the transaction, calling the reset function immediately.
+    pub fn rollback(self) {}
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for Transaction<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::DerefMut for Transaction<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re.value
     }
 }
-impl<'a, T: Send, F: FnMut(&mut T) + Sync> Drop for TempRef<'a, T, F> {
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for Transaction<'a, T, F> {
     fn drop(&mut self) {
-        (self.reset)(&mut self.re);
+        if self.committed {
+            self.temp.hooks.release(&self.re.value);
+            self.temp.instrument.release::<T>();
+        } else {
+            self.temp.reset_on_drop(&mut self.re);
+        }
+    }
+}
+impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for Transaction<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Transaction")
+            .field("value", &self.re.value)
+            .field("committed", &self.committed)
+            .finish()
+    }
+}
+impl<'a, T: Display + Send, F: FnMut(&mut T) + Send> Display for Transaction<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// A shared reference from [`Temp<T, F>`], created by [`Temp::read`].
+/// It cannot change the value, so it doesn't reset it when dropped.
+pub struct ReadRef<'a, T: Send, F: FnMut(&mut T) + Send> {
+    re: RwLockReadGuard<'a, Slot<T, F>>,
+    _notify: Notify<'a, T, F>,
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for ReadRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> AsRef<T> for ReadRef<'a, T, F> {
+    fn as_ref(&self) -> &T {
+        &self.re.value
+    }
+}
+impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for ReadRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ReadRef")
+            .field("value", &self.re.value)
+            .finish()
     }
 }
-impl<'a, T: Send + Debug, F: FnMut(&mut T) + Sync> Debug for TempRef<'a, T, F> {
+impl<'a, T: Display + Send, F: FnMut(&mut T) + Send> Display for ReadRef<'a, T, F> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("TempRef").field("value", &self.re).finish()
+        Display::fmt(&**self, f)
     }
 }
+/// Archives the value alone, so `Archived<ReadRef<'_, T, F>>` is `Archived<T>`.
+///
+/// The guard holds the lock, so the value cannot change between serializing and resolving it. The archive deserializes back into a `T`.
+#[cfg(feature = "rkyv")]
+impl<'a, T: rkyv::Archive + Send, F: FnMut(&mut T) + Send> rkyv::Archive for ReadRef<'a, T, F> {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
 
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        (**self).resolve(resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<'a, T, F, S> rkyv::Serialize<S> for ReadRef<'a, T, F>
+where
+    T: rkyv::Serialize<S> + Send,
+    F: FnMut(&mut T) + Send,
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
 /// A value protected by `RwLock` that ensures its mutable reference is always reset when dropped.
 ///
 /// `Temp<T, F>` holds a value of type `T` inside an `RwLock`, together with a reset
@@ -91,16 +867,52 @@ impl<'a, T: Send + Debug, F: FnMut(&mut T) + Sync> Debug for TempRef<'a, T, F> {
 /// }
 /// assert_eq!(*workspace.read().unwrap(), vec![0;128]);
 /// ```
-pub struct Temp<T: Send, F: FnMut(&mut T) + Sync> {
-    value: RwLock<T>,
-    reset: UnsafeCell<F>,
+pub struct Temp<T: Send, F: FnMut(&mut T) + Send = fn(&mut T)> {
+    value: RwLock<Slot<T, F>>,
+    unwind: UnwindPolicy,
+    reset_poisoned: AtomicBool,
+    generation: AtomicUsize,
+    hooks: Hooks<T>,
+    instrument: Instrument,
+    clock: ResetClock,
+    // Shared by the clones of the `Temp`.
+    reset_if: Option<ResetIf<T>>,
+    gate: Gate,
+    waiters: Waiters,
 }
-impl<T: Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
+impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// A constructor of Temp<T, F>.
+    #[cfg(not(loom))]
     pub const fn new(value: T, reset: F) -> Self {
         Temp {
-            value: RwLock::new(value),
-            reset: UnsafeCell::new(reset),
+            value: RwLock::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            gate: Gate::new(),
+            waiters: Waiters::new(),
+        }
+    }
+    /// A constructor of Temp<T, F>.
+    ///
+    /// It is not `const` under `cfg(loom)`, since loom's primitives cannot be created in a constant.
+    #[cfg(loom)]
+    pub fn new(value: T, reset: F) -> Self {
+        Temp {
+            value: RwLock::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            gate: Gate::new(),
+            waiters: Waiters::new(),
         }
     }
     /// A constructor of Temp<T, F>.
@@ -110,37 +922,684 @@ impl<T: Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
     pub fn new_with(mut value: T, mut reset: F) -> Self {
         reset(&mut value);
         Temp {
-            value: RwLock::new(value),
-            reset: UnsafeCell::new(reset),
+            value: RwLock::new(Slot { value, reset }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            gate: Gate::new(),
+            waiters: Waiters::new(),
         }
     }
-    /// Locks this Temp with shared read access, blocking the current thread until it can be acquired.
-    pub fn read<'a>(
-        &'a self,
-    ) -> Result<RwLockReadGuard<'a, T>, PoisonError<RwLockReadGuard<'a, T>>> {
-        self.value.read()
+    /// Replaces the reset function.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be using the old one.
+    pub fn set_reset(&mut self, reset: F) {
+        self.value
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+            .reset = reset;
     }
-    /// Acquires an exclusive write lock on this `Temp`, blocking the current thread until the lock is available.
-    /// The returned `TempRef` automatically resets itself when dropped.
-    pub fn write<'a>(&'a self) -> WriteResult<TempRef<'a, T, F>> {
-        TempRef::write(self)
+    /// Consumes the Temp, returning a new one with the same value and a reset function of another type.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    pub fn with_reset<G: FnMut(&mut T) + Send>(self, reset: G) -> Temp<T, G> {
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        } = self;
+        let Slot { value, .. } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: RwLock::new(Slot { value, reset }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        }
     }
-    /// Attempts to acquire this Temp with shared read access.
-    /// If the access could not be granted at this time, then Err is returned. Otherwise, an RAII guard is returned which will release the shared access when it is dropped.
-    pub fn try_read<'a>(
-        &'a self,
-    ) -> Result<RwLockReadGuard<'a, T>, TryLockError<RwLockReadGuard<'a, T>>> {
-        self.value.try_read()
+    /// Consumes the Temp, returning a new one whose reset function calls the current one followed by `extra`.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    pub fn chain_reset<G: FnMut(&mut T) + Send + Sync>(
+        self,
+        mut extra: G,
+    ) -> Temp<T, impl FnMut(&mut T) + Send + Sync>
+    where
+        F: Sync,
+    {
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        } = self;
+        let Slot { value, mut reset } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: RwLock::new(Slot {
+                value,
+                reset: move |value: &mut T| {
+                    reset(value);
+                    extra(value);
+                },
+            }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        }
     }
-    /// Attempts to lock this Temp with exclusive write access.
-    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, TempRef is returned which will release the lock when it is dropped.
-    /// Automatically resets itself when dropped.
-    pub fn try_write<'a>(&'a self) -> TryLockResult<TempRef<'a, T, F>> {
+    /// Consumes the Temp, returning a new one which publishes a [`Snapshot`](crate::watch::Snapshot)
+    /// of the value before and after every call of the reset function to the returned [`Watcher`].
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    /// The new `Temp` is not poisoned, even if this one is.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::{rwlock, watch::Snapshot};
+    ///
+    /// let (workspace, mut watcher) = rwlock::Temp::new(0, |n| *n = 0).watch();
+    /// *workspace.write().unwrap() = 7;
+    /// assert_eq!(*watcher.changed(), Snapshot { dirty: 7, reset: 0 });
+    /// ```
+    #[cfg(feature = "watch")]
+    pub fn watch(self) -> (Temp<T, impl FnMut(&mut T) + Send + Sync>, Watcher<T>)
+    where
+        T: Clone + Sync,
+        F: Sync,
+    {
+        let (publisher, watcher) = watch::channel();
+        let Temp {
+            value,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        } = self;
+        let Slot { value, mut reset } = value.into_inner().unwrap_or_else(PoisonError::into_inner);
+        let temp = Temp {
+            value: RwLock::new(Slot {
+                value,
+                reset: move |value: &mut T| {
+                    let dirty = value.clone();
+                    reset(value);
+                    publisher.publish(dirty, value.clone());
+                },
+            }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            reset_if,
+            gate,
+            waiters,
+        };
+        (temp, watcher)
+    }
+    /// Sets what the guards do with the reset function when they are dropped during a panic.
+    /// The default is [`UnwindPolicy::Reset`].
+    pub fn with_unwind_policy(mut self, policy: UnwindPolicy) -> Self {
+        self.unwind = policy;
+        self
+    }
+    /// Sets the label of the `tracing` events of this `Temp`, which are recorded with its type name.
+    #[cfg(feature = "tracing")]
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.instrument.set_label(label);
+        self
+    }
+    /// Sets how long the value may go without a reset.
+    ///
+    /// Once it is older than `max_age`, the next guard which resets the value calls the reset function before it is returned.
+    /// This is only checked when such a guard is created, so no background thread is involved.
+    #[cfg(feature = "std")]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.clock.set_max_age(max_age);
+        self
+    }
+    /// Sets a predicate deciding whether a dropped guard calls the reset function, so a guard which left the value clean
+    /// doesn't reset it. Explicit resets and rolled back transactions don't ask it.
+    ///
+    /// The predicate is a `Fn`, which the clones of the `Temp` share without a lock of its own.
+    /// A predicate which keeps state has to keep it in atomics or a lock of its own.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let buffer = Temp::new(Vec::new(), |v: &mut Vec<u8>| *v = Vec::new());
+    /// let limit = 1024;
+    /// let buffer = buffer.with_reset_if(move |v| v.capacity() > limit);
+    /// buffer.write().unwrap().extend_from_slice(b"small");
+    /// assert_eq!(buffer.read().unwrap().as_slice(), b"small"); // Kept, since it stayed small.
+    /// ```
+    pub fn with_reset_if(mut self, reset_if: impl Fn(&T) -> bool + Send + Sync + 'static) -> Self {
+        self.reset_if = Some(Arc::new(reset_if));
+        self
+    }
+    /// Sets the minimum interval between the resets of dropped guards.
+    ///
+    /// A guard dropped less than `interval` after the last reset leaves the value as it is, and the reset is postponed
+    /// until a guard which resets the value is created after the interval has passed.
+    /// So a guard may see what an earlier guard left. Explicit resets, rolled back transactions and guards dropped
+    /// during a panic always reset.
+    #[cfg(feature = "std")]
+    pub fn with_min_reset_interval(mut self, interval: Duration) -> Self {
+        self.clock.set_min_interval(interval);
+        self
+    }
+    /// Returns the minimum interval set with [`Self::with_min_reset_interval`].
+    #[cfg(feature = "std")]
+    pub fn min_reset_interval(&self) -> Option<Duration> {
+        self.clock.min_interval()
+    }
+    /// Returns the maximum age set with [`Self::with_max_age`].
+    #[cfg(feature = "std")]
+    pub fn max_age(&self) -> Option<Duration> {
+        self.clock.max_age()
+    }
+    /// Returns the counters of the guards of this `Temp`.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.instrument.stats()
+    }
+    /// Sets the [`TempHooks`] called by the guards and the reset function.
+    #[cfg(feature = "hooks")]
+    pub fn with_hooks<H: TempHooks<T>>(mut self) -> Self {
+        self.hooks = Hooks::of::<H>();
+        self
+    }
+    /// Returns what the guards do with the reset function when they are dropped during a panic.
+    pub fn unwind_policy(&self) -> UnwindPolicy {
+        self.unwind
+    }
+    /// Returns how many times the reset function has completed on the value.
+    ///
+    /// A change of the generation tells that anything derived from the value may be stale.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.load(Ordering::Acquire)
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.store(false, Ordering::Release);
+    }
+    /// Locks this Temp with shared read access, blocking the current thread until it can be acquired.
+    /// The returned [`ReadRef`] doesn't reset the value when dropped.
+    pub fn read<'a>(&'a self) -> ReadResult<ReadRef<'a, T, F>> {
+        match self.read_value() {
+            Ok(re) => Ok(ReadRef {
+                re,
+                _notify: Notify(self),
+            }),
+            Err(err) => Err(PoisonError::new(ReadRef {
+                re: err.into_inner(),
+                _notify: Notify(self),
+            })),
+        }
+    }
+    /// Returns a clone of the value, holding shared read access only while it is cloned.
+    /// The reset function is not called.
+    ///
+    /// If the lock is poisoned, the clone is returned inside the `PoisonError`.
+    pub fn read_cloned(&self) -> Result<T, PoisonError<T>>
+    where
+        T: Clone,
+    {
+        let _notify = Notify(self);
+        let result = self.read_value();
+        let _hold = self.instrument.hold::<T>();
+        match result {
+            Ok(re) => Ok(re.value.clone()),
+            Err(err) => Err(PoisonError::new(err.into_inner().value.clone())),
+        }
+    }
+    /// Acquires an exclusive write lock on this `Temp`, blocking the current thread until the lock is available.
+    /// The returned `TempRef` automatically resets itself when dropped.
+    pub fn write<'a>(&'a self) -> WriteResult<TempRef<'a, T, F>> {
+        TempRef::write(self)
+    }
+    /// Attempts to acquire this Temp with shared read access.
+    /// If the access could not be granted at this time, then Err is returned. Otherwise, an RAII guard is returned which will release the shared access when it is dropped.
+    pub fn try_read<'a>(&'a self) -> TryLockResult<ReadRef<'a, T, F>> {
+        match self.value.try_read() {
+            Ok(re) => Ok(ReadRef {
+                re,
+                _notify: Notify(self),
+            }),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(ReadRef {
+                    re: err.into_inner(),
+                    _notify: Notify(self),
+                })))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+    /// Attempts to acquire this Temp with shared read access, blocking the current thread for at most `timeout`.
+    /// If the access could not be granted in time, then `Err(TryLockError::WouldBlock)` is returned.
+    #[cfg(not(loom))]
+    pub fn read_timeout<'a>(&'a self, timeout: Duration) -> TryLockResult<ReadRef<'a, T, F>> {
+        self.waiters.retry_until(timeout, || self.try_read())
+    }
+    /// Attempts to lock this Temp with exclusive write access.
+    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, TempRef is returned which will release the lock when it is dropped.
+    /// Automatically resets itself when dropped.
+    pub fn try_write<'a>(&'a self) -> TryLockResult<TempRef<'a, T, F>> {
         TempRef::try_write(self)
     }
+    /// Attempts to lock this Temp with exclusive write access, blocking the current thread for at most `timeout`.
+    /// If the lock could not be acquired in time, then `Err(TryLockError::WouldBlock)` is returned.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::TryLockError;
+    /// use std::time::Duration;
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Temp::new(0, |n| *n = 0);
+    /// let _reader = workspace.read().unwrap();
+    /// assert!(matches!(
+    ///     workspace.write_timeout(Duration::from_millis(10)),
+    ///     Err(TryLockError::WouldBlock)
+    /// ));
+    /// ```
+    #[cfg(not(loom))]
+    pub fn write_timeout<'a>(&'a self, timeout: Duration) -> TryLockResult<TempRef<'a, T, F>> {
+        self.waiters.retry_until(timeout, || self.try_write())
+    }
+    /// Locks this Temp with exclusive write access, spinning with exponential backoff before blocking the current thread.
+    ///
+    /// At most `max_spins` spins are spent retrying [`Self::try_write`], which avoids parking the thread
+    /// when the lock is only held for a very short time. If it is still locked, this falls back to [`Self::write`].
+    pub fn write_spin<'a>(&'a self, max_spins: u32) -> WriteResult<TempRef<'a, T, F>> {
+        match spin::spin(max_spins, || self.try_write()) {
+            Ok(guard) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => Err(err),
+            Err(TryLockError::WouldBlock) => self.write(),
+        }
+    }
+    /// Attempts to lock this Temp with exclusive write access, spinning at most `max_spins` times with exponential backoff.
+    /// If the lock is still held after that, then `Err(TryLockError::WouldBlock)` is returned.
+    pub fn try_write_spin<'a>(&'a self, max_spins: u32) -> TryLockResult<TempRef<'a, T, F>> {
+        spin::spin(max_spins, || self.try_write())
+    }
+    /// Locks this Temp with exclusive write access like [`Self::write`], recovering from poisoning.
+    ///
+    /// Since the value is reset on every release, a panic while it was locked leaves nothing worth protecting.
+    /// If the lock is poisoned, the reset function is called and the poison is cleared before the guard is returned.
+    pub fn write_unpoisoned<'a>(&'a self) -> TempRef<'a, T, F> {
+        self.write()
+            .unwrap_or_else(|err| self.recover(err.into_inner()))
+    }
+    /// Attempts to lock this Temp with exclusive write access like [`Self::try_write`], recovering from poisoning.
+    /// If the lock could not be acquired at this time, then `None` is returned.
+    pub fn try_write_unpoisoned<'a>(&'a self) -> Option<TempRef<'a, T, F>> {
+        match self.try_write() {
+            Ok(guard) => Some(guard),
+            Err(TryLockError::Poisoned(err)) => Some(self.recover(err.into_inner())),
+            Err(TryLockError::WouldBlock) => None,
+        }
+    }
+
+    fn recover<'a>(&'a self, mut guard: TempRef<'a, T, F>) -> TempRef<'a, T, F> {
+        guard.reset();
+        self.value.clear_poison();
+        guard
+    }
+    /// Locks this Temp with shared read access for the duration of `f`, returning its result.
+    /// Like [`Self::read`], the reset function is not called.
+    ///
+    /// If the lock is poisoned, `f` is still called and its result is returned inside the `PoisonError`.
+    pub fn with_read<R, C: FnOnce(&T) -> R>(&self, f: C) -> ReadResult<R> {
+        match self.read() {
+            Ok(guard) => Ok(f(&guard)),
+            Err(err) => Err(PoisonError::new(f(&err.into_inner()))),
+        }
+    }
+    /// Locks this Temp with exclusive write access for the duration of `f`, returning its result.
+    /// The reset function is called after `f` returns, so the lock is never held longer than the closure.
+    ///
+    /// If the lock is poisoned, `f` is still called and its result is returned inside the `PoisonError`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Temp::new(Vec::new(), |v| v.clear());
+    ///
+    /// let len = workspace
+    ///     .with_write(|v| {
+    ///         v.extend([1, 2, 3]);
+    ///         v.len()
+    ///     })
+    ///     .unwrap();
+    /// assert_eq!(len, 3);
+    /// assert!(workspace.with_read(|v| v.is_empty()).unwrap());
+    /// ```
+    pub fn with_write<R, C: FnOnce(&mut T) -> R>(&self, f: C) -> WriteResult<R> {
+        match self.write() {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(err) => Err(PoisonError::new(f(&mut err.into_inner()))),
+        }
+    }
+    /// Acquires an exclusive write lock through an `Arc`, blocking the current thread until the lock is available.
+    /// The returned [`OwnedTempRef`] keeps the `Arc` alive and automatically resets itself when dropped.
+    pub fn write_arc(self: &Arc<Self>) -> WriteResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        OwnedTempRef::write(self)
+    }
+    /// Attempts to lock this Temp with exclusive write access through an `Arc`.
+    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, [`OwnedTempRef`] is returned.
+    pub fn try_write_arc(self: &Arc<Self>) -> TryLockResult<OwnedTempRef<T, F>>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        OwnedTempRef::try_write(self)
+    }
+    /// Spawns a thread which calls the reset function every `interval` until the returned handle is dropped.
+    ///
+    /// The thread only holds a `Weak` reference, so it does not keep the `Temp` alive.
+    /// A reset is skipped while the lock is held, since the guard resets the value when it is dropped,
+    /// and while the lock is poisoned.
+    ///
+    /// # Panics
+    /// Panics if `interval` is zero, which would keep the thread spinning.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::sync::Arc;
+    /// use std::time::Duration;
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Arc::new(Temp::new(0, |n: &mut i32| *n = 0));
+    /// let resetter = workspace.spawn_resetter(Duration::from_millis(1));
+    /// *workspace.transaction().unwrap() = 1;
+    /// while workspace.generation() == 0 {
+    ///     std::thread::yield_now();
+    /// }
+    /// drop(resetter); // The thread stops here.
+    /// ```
+    #[cfg(not(loom))]
+    #[track_caller]
+    pub fn spawn_resetter(self: &Arc<Self>, interval: Duration) -> ResetterHandle
+    where
+        T: Sync + 'static,
+        F: Sync + 'static,
+    {
+        resetter::spawn(Arc::downgrade(self), interval, |temp| {
+            let _ = temp.try_reset();
+        })
+    }
+    /// Runs `f` in a [`std::thread::scope`], handing it the scope and this `Temp` to share with the threads it spawns.
+    ///
+    /// Every thread is joined before this method returns. If `reset` is true, the reset function is then invoked once
+    /// more, unless the lock is poisoned.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Temp::new(Vec::new(), |v: &mut Vec<usize>| v.clear());
+    /// workspace.scope(true, |s, shared| {
+    ///     for n in 0..4 {
+    ///         s.spawn(move || shared.write().unwrap().push(n));
+    ///     }
+    /// });
+    /// assert_eq!(workspace.generation(), 5);
+    /// ```
+    #[cfg(not(loom))]
+    pub fn scope<'env, R>(
+        &'env self,
+        reset: bool,
+        f: impl for<'scope> FnOnce(&'scope std::thread::Scope<'scope, 'env>, &'env Self) -> R,
+    ) -> R {
+        let result = std::thread::scope(|s| f(s, self));
+        if reset {
+            let _ = self.reset();
+        }
+        result
+    }
+    /// Acquires an exclusive write lock on this `Temp` as a [`Transaction`], blocking the current thread until the lock is available.
+    /// The changes are kept only if the transaction is committed.
+    pub fn transaction<'a>(&'a self) -> WriteResult<Transaction<'a, T, F>> {
+        Transaction::write(self)
+    }
+    /// Locks this Temp with upgradable read access, blocking the current thread until it can be acquired.
+    ///
+    /// Other readers are allowed at the same time, but writers and other upgradable readers are not.
+    /// The returned guard can be upgraded to a [`TempRef`] with [`UpgradableRead::upgrade`].
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::{Temp, UpgradableRead};
+    ///
+    /// let workspace = Temp::new(vec![0; 4], |v| v.fill(0));
+    ///
+    /// let guard = workspace.upgradable_read().unwrap();
+    /// assert_eq!(*workspace.read().unwrap(), vec![0; 4]);
+    /// if guard.iter().all(|n| *n == 0) {
+    ///     let mut guard = UpgradableRead::upgrade(guard).unwrap();
+    ///     guard.fill(1);
+    /// }
+    /// assert_eq!(*workspace.read().unwrap(), vec![0; 4]);
+    /// ```
+    #[cfg(feature = "upgradable")]
+    pub fn upgradable_read<'a>(&'a self) -> ReadResult<UpgradableRead<'a, T, F>> {
+        UpgradableRead::read(self)
+    }
+    /// Attempts to acquire this Temp with upgradable read access.
+    /// If the access could not be granted at this time, then Err is returned.
+    #[cfg(feature = "upgradable")]
+    pub fn try_upgradable_read<'a>(&'a self) -> TryLockResult<UpgradableRead<'a, T, F>> {
+        UpgradableRead::try_read(self)
+    }
     /// Consumes this Temp, returning the underlying data.
     pub fn into_inner(self) -> WriteResult<T> {
-        self.value.into_inner()
+        match self.value.into_inner() {
+            Ok(slot) => Ok(slot.value),
+            Err(err) => Err(PoisonError::new(err.into_inner().value)),
+        }
+    }
+    /// Consumes the Temp, returning the wrapped value and the reset function.
+    ///
+    /// If the lock is poisoned, both are returned inside the `PoisonError`.
+    pub fn into_parts(self) -> WriteResult<(T, F)> {
+        match self.value.into_inner() {
+            Ok(Slot { value, reset }) => Ok((value, reset)),
+            Err(err) => {
+                let Slot { value, reset } = err.into_inner();
+                Err(PoisonError::new((value, reset)))
+            }
+        }
+    }
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no actual locking needs to take place.
+    /// The reset function is not called.
+    pub fn get_mut(&mut self) -> WriteResult<&mut T> {
+        match self.value.get_mut() {
+            Ok(slot) => Ok(&mut slot.value),
+            Err(err) => Err(PoisonError::new(&mut err.into_inner().value)),
+        }
+    }
+    /// Calls `f` with a mutable reference to the wrapped value, then calls the reset function like [`TempRef::reset`].
+    ///
+    /// Since this call borrows the `Temp` mutably, no actual locking needs to take place.
+    /// If the lock is poisoned, the value is still reset and the result of `f` is returned inside the `PoisonError`.
+    pub fn get_mut_reset<R, C: FnOnce(&mut T) -> R>(&mut self, f: C) -> WriteResult<R> {
+        // Borrowed field by field, since the value is borrowed from `self.value` while the reset runs.
+        let Temp {
+            value,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            ..
+        } = self;
+        let (slot, poisoned) = match value.get_mut() {
+            Ok(slot) => (slot, false),
+            Err(err) => (err.into_inner(), true),
+        };
+        let result = f(&mut slot.value);
+        let Slot { value, reset } = slot;
+        let mut reset = hooked_reset(reset, generation, clock, hooks, instrument);
+        unwind::reset_or_poison(value, &mut reset, || {
+            reset_poisoned.store(true, Ordering::Release)
+        });
+        if poisoned {
+            Err(PoisonError::new(result))
+        } else {
+            Ok(result)
+        }
+    }
+    /// Locks the value for writing and calls `f` with it, so that `f` can split it into parts, such as a [`TempField`]
+    /// for every field of a struct, which are locked and reset independently of each other until `f` returns.
+    ///
+    /// The reset function of the `Temp` is not called; each part is reset by its own handle.
+    /// If the lock is poisoned, `f` is still called and its result is returned inside the `PoisonError`.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::{Temp, TempField};
+    ///
+    /// #[derive(Default)]
+    /// struct Scratch {
+    ///     ids: Vec<u32>,
+    ///     names: String,
+    /// }
+    ///
+    /// let workspace = Temp::new(Scratch::default(), |s| *s = Scratch::default());
+    /// workspace
+    ///     .project(|s| {
+    ///         let ids = TempField::new(&mut s.ids, Vec::clear);
+    ///         let names = TempField::new(&mut s.names, String::clear);
+    ///         std::thread::scope(|scope| {
+    ///             scope.spawn(|| ids.write().unwrap().push(1));
+    ///             scope.spawn(|| names.write().unwrap().push_str("a"));
+    ///         });
+    ///         assert!(ids.read().unwrap().is_empty());
+    ///         assert_eq!(names.generation(), 1);
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn project<R, C: FnOnce(&mut T) -> R>(&self, f: C) -> WriteResult<R> {
+        self.with_value(|slot| f(&mut slot.value))
+    }
+    /// Locks the value for writing and calls `f` with the [`TempField`]s of its fields, as [`TempProject`] splits it.
+    ///
+    /// Like [`Self::project`], the reset function of the `Temp` is not called.
+    pub fn project_fields<R, C: FnOnce(T::Fields<'_>) -> R>(&self, f: C) -> WriteResult<R>
+    where
+        T: TempProject,
+    {
+        self.project(|value| f(T::project(value)))
+    }
+    /// Overwrites the wrapped value under the write lock, dropping the old one.
+    ///
+    /// The reset function is not called.
+    /// If the lock is poisoned, the value is still overwritten.
+    pub fn set(&self, value: T) -> WriteResult<()> {
+        self.with_value(|old| old.value = value)
+    }
+    /// Overwrites the wrapped value, dropping the old one, after applying the reset function to the new `value`,
+    /// like [`Self::new_with`].
+    ///
+    /// If the lock is poisoned, the value is still overwritten.
+    pub fn set_with(&self, mut value: T) -> WriteResult<()> {
+        self.with_value(|old| {
+            (old.reset)(&mut value);
+            old.value = value;
+        })
+    }
+    /// Replaces the wrapped value with a new one under the write lock, returning the old value.
+    ///
+    /// The reset function is not called.
+    /// If the lock is poisoned, the value is still replaced and the old value is returned inside the `PoisonError`.
+    pub fn replace(&self, value: T) -> WriteResult<T> {
+        self.with_value(|old| mem::replace(&mut old.value, value))
+    }
+    /// Replaces the wrapped value with a new one computed from f under the write lock, returning the old value.
+    ///
+    /// The reset function is not called.
+    /// If the lock is poisoned, the value is still replaced and the old value is returned inside the `PoisonError`.
+    pub fn replace_with<C: FnOnce(&mut T) -> T>(&self, f: C) -> WriteResult<T> {
+        self.with_value(|old| {
+            let value = f(&mut old.value);
+            mem::replace(&mut old.value, value)
+        })
+    }
+    /// Takes the wrapped value under the write lock, leaving `Default::default()` in its place.
+    ///
+    /// The reset function is not called.
+    /// If the lock is poisoned, the value is still taken and returned inside the `PoisonError`.
+    pub fn take(&self) -> WriteResult<T>
+    where
+        T: Default,
+    {
+        self.replace(T::default())
+    }
+    /// Swaps the wrapped value of self with the wrapped value of other, holding both write locks.
+    ///
+    /// The reset function is not called. If either lock is poisoned, the values are still swapped and Err is returned.
+    pub fn swap(&self, other: &RwLock<T>) -> WriteResult<()> {
+        let _notify = Notify(self);
+        let (a, b) = (self.write_value(), other.write());
+        let poisoned = a.is_err() || b.is_err();
+        let mut a = a.unwrap_or_else(PoisonError::into_inner);
+        let mut b = b.unwrap_or_else(PoisonError::into_inner);
+        mem::swap(&mut a.value, &mut *b);
+        if poisoned {
+            Err(PoisonError::new(()))
+        } else {
+            Ok(())
+        }
     }
     /// Clear the poisoned state from a lock.
     pub fn clear_poison(&self) {
@@ -155,8 +1614,9 @@ impl<T: Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
     /// This method acquires a blocking write lock on the internal value.
     /// If the lock is poisoned, it returns a `PoisonError`.
     pub fn reset(&self) -> WriteResult<()> {
-        if let Ok(mut guard) = self.value.write() {
-            self.get_reset()(&mut guard);
+        let _notify = Notify(self);
+        if let Ok(mut guard) = self.write_value() {
+            self.reset_or_poison(&mut guard);
             Ok(())
         } else {
             Err(PoisonError::new(()))
@@ -167,9 +1627,10 @@ impl<T: Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
     /// This method tries to acquire a non-blocking write lock on the internal value.
     /// If the lock cannot be immediately acquired, it returns a `TryLockError`.
     pub fn try_reset(&self) -> TryLockResult<()> {
-        match self.value.try_write() {
+        let _notify = Notify(self);
+        match self.try_write_value() {
             Ok(mut guard) => {
-                self.get_reset()(&mut guard);
+                self.reset_or_poison(&mut guard);
                 Ok(())
             },
             Err(TryLockError::Poisoned(_)) => Err(TryLockError::Poisoned(PoisonError::new(()))),
@@ -177,17 +1638,180 @@ impl<T: Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
         }
     }
 
-    #[allow(clippy::mut_from_ref)]
-    fn get_reset(&self) -> &mut F {
-        unsafe { &mut *self.reset.get() }
+    fn with_value<R, C: FnOnce(&mut Slot<T, F>) -> R>(&self, f: C) -> WriteResult<R> {
+        let _notify = Notify(self);
+        match self.write_value() {
+            Ok(mut guard) => Ok(f(&mut guard)),
+            Err(err) => Err(PoisonError::new(f(&mut err.into_inner()))),
+        }
+    }
+    // Resets the value first if it is older than the maximum age, or a postponed reset is due.
+    fn expire(&self, slot: &mut Slot<T, F>) {
+        if self.clock.due() {
+            self.reset_or_poison(slot);
+        }
+    }
+    // The reset function followed by the `on_reset` hook, inside the span of the reset.
+    fn hooked_reset<'r>(&'r self, reset: &'r mut F) -> impl FnMut(&mut T) + 'r {
+        hooked_reset(
+            reset,
+            &self.generation,
+            &self.clock,
+            &self.hooks,
+            &self.instrument,
+        )
+    }
+    fn reset_or_poison(&self, slot: &mut Slot<T, F>) {
+        let Slot { value, reset } = slot;
+        unwind::reset_or_poison(value, &mut self.hooked_reset(reset), || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+    }
+    // Called when a guard is dropped. The reset is skipped if `reset_if` returns false,
+    // and postponed if the last one ran less than the minimum interval ago.
+    fn release(&self, slot: &mut Slot<T, F>) {
+        if !self.should_reset(&slot.value) || self.clock.postpone() {
+            self.hooks.release(&slot.value);
+            self.instrument.release::<T>();
+        } else {
+            self.reset_on_drop(slot);
+        }
+    }
+    // Asks the `with_reset_if` predicate, if there is one.
+    fn should_reset(&self, value: &T) -> bool {
+        match &self.reset_if {
+            Some(reset_if) => reset_if(value),
+            None => true,
+        }
+    }
+    fn reset_on_drop(&self, slot: &mut Slot<T, F>) {
+        let Slot { value, reset } = slot;
+        self.hooks.release(value);
+        self.instrument.release::<T>();
+        unwind::reset_on_drop(self.unwind, value, &mut self.hooked_reset(reset), || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+    }
+    // Every write access passes through `gate`, so that with the `upgradable` feature an upgradable reader holding it
+    // can upgrade atomically, and a `TempRef` holding it can be downgraded and written again.
+    fn read_value(&self) -> ReadResult<RwLockReadGuard<'_, Slot<T, F>>> {
+        self.instrument
+            .lock::<T, _>(|| self.value.try_read(), || self.value.read())
+    }
+    fn write_gated(&self) -> WriteResult<Gated<'_, T, F>> {
+        self.instrument.lock::<T, _>(
+            || self.try_write_gated(),
+            || {
+                let gate = self.gate.lock();
+                match self.value.write() {
+                    Ok(guard) => Ok((gate, guard)),
+                    Err(err) => Err(PoisonError::new((gate, err.into_inner()))),
+                }
+            },
+        )
+    }
+    fn try_write_gated(&self) -> TryLockResult<Gated<'_, T, F>> {
+        let Some(gate) = self.gate.try_lock() else {
+            return Err(TryLockError::WouldBlock);
+        };
+        match self.value.try_write() {
+            Ok(guard) => Ok((gate, guard)),
+            Err(TryLockError::Poisoned(err)) => Err(TryLockError::Poisoned(PoisonError::new((
+                gate,
+                err.into_inner(),
+            )))),
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+    // The gate is released at once, since the value is only written for the duration of the call.
+    fn write_value(&self) -> WriteResult<RwLockWriteGuard<'_, Slot<T, F>>> {
+        match self.write_gated() {
+            Ok((_, guard)) => Ok(guard),
+            Err(err) => Err(PoisonError::new(err.into_inner().1)),
+        }
+    }
+    fn try_write_value(&self) -> TryLockResult<RwLockWriteGuard<'_, Slot<T, F>>> {
+        match self.try_write_gated() {
+            Ok((_, guard)) => Ok(guard),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(err.into_inner().1)))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
     }
 }
-impl<T: Default + Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
+/// A [`Temp`] whose reset function is a function pointer, which is the default of the `F` parameter.
+///
+/// Non-capturing closures coerce to function pointers, so the type can be spelled out in a `static`.
+///
+/// # Examples
+/// ```
+/// use tempref::rwlock::{Temp, TempFn};
+///
+/// static SCRATCH: TempFn<[u8; 512]> = Temp::new([0; 512], |b| b.fill(0));
+///
+/// SCRATCH.write().unwrap()[0] = 1;
+/// assert_eq!(SCRATCH.read().unwrap()[0], 0);
+/// ```
+pub type TempFn<T> = Temp<T, fn(&mut T)>;
+
+/// A [`Temp`] whose reset function is boxed, so that the type can be named without a closure type parameter.
+///
+/// # Examples
+/// ```
+/// use tempref::rwlock::BoxedTemp;
+///
+/// struct Worker {
+///     scratch: BoxedTemp<Vec<u8>>,
+/// }
+///
+/// let worker = Worker {
+///     scratch: BoxedTemp::new_boxed(Vec::new(), |v| v.clear()),
+/// };
+/// # let _ = worker.scratch;
+/// ```
+pub type BoxedTemp<T> = Temp<T, Box<dyn FnMut(&mut T) + Send + Sync>>;
+impl<T: Send> Temp<T, Box<dyn FnMut(&mut T) + Send + Sync>> {
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    pub fn new_boxed<F: FnMut(&mut T) + Send + Sync + 'static>(value: T, reset: F) -> Self {
+        Temp::new(value, Box::new(reset))
+    }
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    ///
+    /// Unlike [`Self::new_boxed`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_boxed_with<F: FnMut(&mut T) + Send + Sync + 'static>(value: T, reset: F) -> Self {
+        Temp::new_with(value, Box::new(reset))
+    }
+}
+impl<T: Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp whose reset function is shared through an `Arc`, e.g. with a `Temp` per thread.
+    ///
+    /// The returned `Temp` can be cloned, and every clone calls the same `reset`.
+    pub fn new_shared<R: Fn(&mut T) + Send + Sync + ?Sized>(
+        value: T,
+        reset: Arc<R>,
+    ) -> Temp<T, impl FnMut(&mut T) + Clone + Send + Sync> {
+        Temp::new(value, move |value: &mut T| (*reset)(value))
+    }
+}
+impl<T: Default + Send, F: FnMut(&mut T) + Send> Temp<T, F> {
     /// Creates a new `Temp<T, F>` using `T::default()` as the initial value.
     pub fn new_default(reset: F) -> Self {
         Temp {
-            value: RwLock::new(T::default()),
-            reset: UnsafeCell::new(reset),
+            value: RwLock::new(Slot {
+                value: T::default(),
+                reset,
+            }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            gate: Gate::new(),
+            waiters: Waiters::new(),
         }
     }
     /// Creates a new `Temp<T, F>` using `T::default()` as the initial value,
@@ -199,15 +1823,436 @@ impl<T: Default + Send, F: FnMut(&mut T) + Sync> Temp<T, F> {
         let mut default = T::default();
         reset(&mut default);
         Temp {
-            value: RwLock::new(default),
-            reset: UnsafeCell::new(reset),
+            value: RwLock::new(Slot {
+                value: default,
+                reset,
+            }),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            reset_if: None,
+            gate: Gate::new(),
+            waiters: Waiters::new(),
+        }
+    }
+}
+/// Formats the value without blocking, printing `<locked>` instead while the lock is held for writing.
+impl<T: Debug + Send, F: FnMut(&mut T) + Send> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let _notify = Notify(self);
+        let mut d = f.debug_struct("Temp");
+        match self.value.try_read() {
+            Ok(guard) => d.field("value", &guard.value),
+            Err(TryLockError::Poisoned(err)) => d.field("value", &err.into_inner().value),
+            Err(TryLockError::WouldBlock) => d.field("value", &format_args!("<locked>")),
+        };
+        d.field("poisoned", &self.value.is_poisoned()).finish()
+    }
+}
+/// Serializes the value alone, as if the `Temp` were not there.
+///
+/// Blocks until the lock is available, and fails if it is poisoned. The reset function is not called.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize + Send, F: FnMut(&mut T) + Send> serde::Serialize for Temp<T, F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let _notify = Notify(self);
+        match self.read_value() {
+            Ok(slot) => slot.value.serialize(serializer),
+            Err(_) => Err(serde::ser::Error::custom(
+                "lock poison error while serializing",
+            )),
+        }
+    }
+}
+/// Deserializes the value into a `Temp` whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Default + Send> serde::Deserialize<'de>
+    for Temp<T, fn(&mut T)>
+{
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Temp::from)
+    }
+}
+#[cfg(feature = "serde")]
+impl<T: Send, F: FnMut(&mut T) + Send> Temp<T, F> {
+    /// Deserializes the value and wraps it in a `Temp` with the given `reset` function.
+    pub fn deserialize_with<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+        reset: F,
+    ) -> Result<Self, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Temp::new(value, reset))
+    }
+}
+/// Generates a `Temp` holding an arbitrary value, whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + Default + Send> proptest::arbitrary::Arbitrary
+    for Temp<T, fn(&mut T)>
+{
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(T::arbitrary_with(args), Temp::from)
+    }
+}
+/// Creates a `Temp` holding `T::default()`, whose reset function sets the value back to `T::default()`.
+impl<T: Default + Send> Default for Temp<T, fn(&mut T)> {
+    fn default() -> Self {
+        Temp::new_default(crate::reset_to_default::<T>)
+    }
+}
+/// Creates a `Temp` whose reset function sets the value to `T::default()`.
+impl<T: Default + Send> From<T> for Temp<T, fn(&mut T)> {
+    fn from(value: T) -> Self {
+        Temp::new(value, crate::reset_to_default::<T>)
+    }
+}
+impl<T: AsMut<[u8]> + Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a byte buffer such as `Vec<u8>`, `Box<[u8]>` or `[u8; N]`,
+    /// whose reset function zeroes the bytes in place with a single `memset`, keeping the length.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let buffer = Temp::zeroing(vec![0u8; 4096]);
+    /// buffer.write().unwrap()[..5].copy_from_slice(b"hello");
+    /// assert!(buffer.read().unwrap().iter().all(|&b| b == 0));
+    /// ```
+    pub fn zeroing(value: T) -> Self {
+        Temp::new(value, crate::zero_bytes::<T>)
+    }
+}
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+impl<T: crate::Clear + Send> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a collection implementing [`Clear`](crate::Clear), such as `heapless::Vec`,
+    /// whose reset function empties it in place, keeping its capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Temp::clearing(Vec::with_capacity(64));
+    /// workspace.write().unwrap().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.read().unwrap().is_empty());
+    /// ```
+    pub fn clearing(value: T) -> Self {
+        Temp::new(value, T::clear)
+    }
+}
+#[cfg(feature = "allocator_api")]
+impl<T: Send, A: core::alloc::Allocator + Send>
+    Temp<alloc::vec::Vec<T, A>, fn(&mut alloc::vec::Vec<T, A>)>
+{
+    /// A constructor of an empty `Vec` workspace in `alloc`, whose reset function clears it and keeps the capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::System;
+    /// use tempref::rwlock::Temp;
+    ///
+    /// let workspace = Temp::new_in(System);
+    /// workspace.write().unwrap().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.read().unwrap().is_empty());
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Temp::new(alloc::vec::Vec::new_in(alloc), alloc::vec::Vec::clear)
+    }
+    /// Like [`Self::new_in`], but allocates room for `capacity` elements up front.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Temp::new(
+            alloc::vec::Vec::with_capacity_in(capacity, alloc),
+            alloc::vec::Vec::clear,
+        )
+    }
+}
+/// Clones the value and the reset function, keeping the [`UnwindPolicy`] and the reset-poisoned flag.
+///
+/// The new `Temp` is not poisoned, even if this one is.
+impl<T: Clone + Send, F: FnMut(&mut T) + Send + Clone> Clone for Temp<T, F> {
+    fn clone(&self) -> Self {
+        let _notify = Notify(self);
+        let slot = self.read_value().unwrap_or_else(PoisonError::into_inner);
+        Temp {
+            value: RwLock::new(Slot {
+                value: slot.value.clone(),
+                reset: slot.reset.clone(),
+            }),
+            unwind: self.unwind,
+            reset_poisoned: AtomicBool::new(self.reset_poisoned.load(Ordering::Acquire)),
+            generation: AtomicUsize::new(self.generation.load(Ordering::Acquire)),
+            hooks: self.hooks.clone(),
+            instrument: self.instrument.clone(),
+            clock: self.clock.clone(),
+            reset_if: self.reset_if.clone(),
+            gate: Gate::new(),
+            waiters: Waiters::new(),
+        }
+    }
+}
+/// Compares the values of two `Temp`s, whose reset functions may differ.
+///
+/// Both `Temp`s are locked like any other access, in address order and ignoring poisoning, so comparisons in
+/// opposite directions never deadlock.
+impl<T: PartialEq + Send, F: FnMut(&mut T) + Send, G: FnMut(&mut T) + Send> PartialEq<Temp<T, G>>
+    for Temp<T, F>
+{
+    fn eq(&self, other: &Temp<T, G>) -> bool {
+        let _notify = (Notify(self), Notify(other));
+        let (a, b) = (ptr::from_ref(self).addr(), ptr::from_ref(other).addr());
+        if a == b {
+            let slot = self.read_value().unwrap_or_else(PoisonError::into_inner);
+            // Compared with itself rather than returning `true`, so that a NaN is still unequal.
+            return T::eq(&slot.value, &slot.value);
+        }
+        if a < b {
+            let a = self.read_value().unwrap_or_else(PoisonError::into_inner);
+            a.value
+                == other
+                    .read_value()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .value
+        } else {
+            let b = other.read_value().unwrap_or_else(PoisonError::into_inner);
+            self.read_value()
+                .unwrap_or_else(PoisonError::into_inner)
+                .value
+                == b.value
+        }
+    }
+}
+impl<T: Eq + Send, F: FnMut(&mut T) + Send> Eq for Temp<T, F> {}
+/// Compares the value of a `Temp` with a plain value, ignoring poisoning.
+impl<T: PartialEq + Send, F: FnMut(&mut T) + Send> PartialEq<T> for Temp<T, F> {
+    fn eq(&self, other: &T) -> bool {
+        let _notify = Notify(self);
+        self.read_value()
+            .unwrap_or_else(PoisonError::into_inner)
+            .value
+            == *other
+    }
+}
+/// Displays the value without blocking, or `<locked>` while the lock is held for writing.
+impl<T: Display + Send, F: FnMut(&mut T) + Send> Display for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let _notify = Notify(self);
+        match self.value.try_read() {
+            Ok(guard) => Display::fmt(&guard.value, f),
+            Err(TryLockError::Poisoned(err)) => Display::fmt(&err.into_inner().value, f),
+            Err(TryLockError::WouldBlock) => f.write_str("<locked>"),
+        }
+    }
+}
+
+/// A value which can be split into a [`TempField`] for each of its fields by [`Temp::project_fields`].
+///
+/// With the `derive` feature, `#[derive(TempProject)]` implements it for a struct with named fields, generating a
+/// `<Name>Fields` struct of the `TempField`s, with an accessor for each field which returns a guard resetting only
+/// that field.
+///
+/// # Examples
+/// ```
+/// # #[cfg(feature = "derive")]
+/// # {
+/// use tempref::TempProject;
+/// use tempref::rwlock::Temp;
+///
+/// #[derive(Default, TempProject)]
+/// struct Scratch {
+///     #[temp(reset = Vec::clear)]
+///     ids: Vec<u32>,
+///     name: String,
+/// }
+///
+/// let workspace = Temp::new(Scratch::default(), |s| *s = Scratch::default());
+/// workspace
+///     .project_fields(|fields| {
+///         fields.ids().push(1);
+///         fields.name().push('a');
+///         assert!(fields.ids().is_empty());
+///         assert_eq!(fields.name.generation(), 1);
+///     })
+///     .unwrap();
+/// # }
+/// ```
+pub trait TempProject: Send {
+    /// The [`TempField`]s of the fields.
+    type Fields<'a>
+    where
+        Self: 'a;
+    /// Splits `value` into the [`TempField`]s of its fields.
+    fn project(value: &mut Self) -> Self::Fields<'_>;
+}
+
+/// A part of the value of a [`Temp`], such as a field of a struct, split off by [`Temp::project`].
+///
+/// It has its own lock and reset function, so the parts of one value can be written concurrently,
+/// and a guard only resets its own part when it is dropped.
+pub struct TempField<'a, A: Send, R: FnMut(&mut A) + Send = fn(&mut A)> {
+    value: RwLock<&'a mut A>,
+    reset: Mutex<R>,
+    reset_poisoned: AtomicBool,
+    generation: AtomicUsize,
+}
+impl<'a, A: Send, R: FnMut(&mut A) + Send> TempField<'a, A, R> {
+    /// A constructor of TempField<'a, A, R>, whose reset function `reset` is called on `value` by its guards.
+    pub fn new(value: &'a mut A, reset: R) -> Self {
+        TempField {
+            value: RwLock::new(value),
+            reset: Mutex::new(reset),
+            reset_poisoned: AtomicBool::new(false),
+            generation: AtomicUsize::new(0),
+        }
+    }
+    /// Locks the part with shared read access, blocking the current thread until it can be acquired.
+    pub fn read<'b>(
+        &'b self,
+    ) -> Result<FieldReadRef<'b, 'a, A>, PoisonError<FieldReadRef<'b, 'a, A>>> {
+        match self.value.read() {
+            Ok(re) => Ok(FieldReadRef { re }),
+            Err(err) => Err(PoisonError::new(FieldReadRef {
+                re: err.into_inner(),
+            })),
+        }
+    }
+    /// Locks the part with exclusive write access, blocking the current thread until it can be acquired.
+    /// The returned [`FieldRef`] resets the part when dropped.
+    pub fn write<'b>(&'b self) -> WriteResult<FieldRef<'b, 'a, A, R>> {
+        match self.value.write() {
+            Ok(re) => Ok(FieldRef { re, field: self }),
+            Err(err) => Err(PoisonError::new(FieldRef {
+                re: err.into_inner(),
+                field: self,
+            })),
+        }
+    }
+    /// Attempts to lock the part with exclusive write access.
+    /// If the lock could not be acquired at this time, then Err is returned. Otherwise, [`FieldRef`] is returned.
+    pub fn try_write<'b>(&'b self) -> TryLockResult<FieldRef<'b, 'a, A, R>> {
+        match self.value.try_write() {
+            Ok(re) => Ok(FieldRef { re, field: self }),
+            Err(TryLockError::Poisoned(err)) => {
+                Err(TryLockError::Poisoned(PoisonError::new(FieldRef {
+                    re: err.into_inner(),
+                    field: self,
+                })))
+            }
+            Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+        }
+    }
+    /// Locks the part with exclusive write access like [`Self::write`], ignoring poisoning.
+    ///
+    /// The guard resets the part when dropped, so a panic while it was held doesn't leave it half-written.
+    pub fn write_unpoisoned<'b>(&'b self) -> FieldRef<'b, 'a, A, R> {
+        self.write().unwrap_or_else(PoisonError::into_inner)
+    }
+    /// Returns how many times the reset function has completed on the part.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Acquire)
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the part half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.load(Ordering::Acquire)
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.store(false, Ordering::Release);
+    }
+
+    // The reset function followed by the count of the generation.
+    fn counted_reset(&self) -> impl FnMut(&mut A) + '_ {
+        // A reset function which panicked poisons the lock, but `reset_poisoned` already records that.
+        let mut reset = self.reset.lock().unwrap_or_else(PoisonError::into_inner);
+        move |value: &mut A| {
+            (*reset)(value);
+            self.generation.fetch_add(1, Ordering::Release);
         }
     }
+    fn reset_value(&self, value: &mut A) {
+        unwind::reset_or_poison(value, &mut self.counted_reset(), || {
+            self.reset_poisoned.store(true, Ordering::Release)
+        });
+    }
+}
+impl<'a, A: Debug + Send, R: FnMut(&mut A) + Send> Debug for TempField<'a, A, R> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("TempField");
+        match self.value.try_read() {
+            Ok(guard) => d.field("value", &**guard),
+            Err(TryLockError::Poisoned(err)) => d.field("value", &**err.into_inner()),
+            Err(TryLockError::WouldBlock) => d.field("value", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
+
+/// A shared reference from [`TempField`], which doesn't reset the part when dropped.
+pub struct FieldReadRef<'b, 'a, A: Send> {
+    re: RwLockReadGuard<'b, &'a mut A>,
+}
+impl<'b, 'a, A: Send> core::ops::Deref for FieldReadRef<'b, 'a, A> {
+    type Target = A;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'b, 'a, A: Debug + Send> Debug for FieldReadRef<'b, 'a, A> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("FieldReadRef")
+            .field("value", &**self.re)
+            .finish()
+    }
+}
+
+/// A mutable reference from [`TempField`].
+/// When it is dropped, it calls the reset function of the part.
+pub struct FieldRef<'b, 'a, A: Send, R: FnMut(&mut A) + Send = fn(&mut A)> {
+    re: RwLockWriteGuard<'b, &'a mut A>,
+    field: &'b TempField<'a, A, R>,
+}
+impl<'b, 'a, A: Send, R: FnMut(&mut A) + Send> FieldRef<'b, 'a, A, R> {
+    /// Invokes the reset function on the part.
+    ///
+    /// If the reset function panics, the part is marked as [reset-poisoned](TempField::is_reset_poisoned).
+    pub fn reset(&mut self) {
+        self.field.reset_value(&mut self.re);
+    }
+}
+impl<'b, 'a, A: Send, R: FnMut(&mut A) + Send> core::ops::Deref for FieldRef<'b, 'a, A, R> {
+    type Target = A;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'b, 'a, A: Send, R: FnMut(&mut A) + Send> core::ops::DerefMut for FieldRef<'b, 'a, A, R> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'b, 'a, A: Send, R: FnMut(&mut A) + Send> Drop for FieldRef<'b, 'a, A, R> {
+    fn drop(&mut self) {
+        let field = self.field;
+        unwind::reset_on_drop(
+            UnwindPolicy::Reset,
+            &mut **self.re,
+            &mut field.counted_reset(),
+            || field.reset_poisoned.store(true, Ordering::Release),
+        );
+    }
 }
-unsafe impl<T: Send, F: FnMut(&mut T) + Sync> Send for Temp<T, F> {}
-unsafe impl<T: Send, F: FnMut(&mut T) + Sync> Sync for Temp<T, F> {}
-impl<T: Debug + Send, F: FnMut(&mut T) + Sync> Debug for Temp<T, F> {
+impl<'b, 'a, A: Debug + Send, R: FnMut(&mut A) + Send> Debug for FieldRef<'b, 'a, A, R> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.debug_struct("Temp").field("value", &self.value).finish()
+        f.debug_struct("FieldRef")
+            .field("value", &**self.re)
+            .finish()
     }
 }
@@ -0,0 +1,127 @@
+//! Helpers for `crossbeam` scoped threads and channels. This module requires std and the `crossbeam` feature.
+//!
+//! [`scope`] shares a [`mutex::Temp`](crate::mutex::Temp) with the workers of a `crossbeam::scope`, and [`lend`]
+//! turns a guard, such as a [`TempPool`](crate::pool::TempPool) checkout, into a [`Lent`] value which can be sent
+//! through a `crossbeam::channel` and goes back to its `Temp` when it is dropped on the other side.
+
+extern crate std;
+
+use core::fmt::Debug;
+use core::mem::ManuallyDrop;
+
+use crossbeam::thread::Scope;
+
+use crate::mutex::{Temp, TempRef};
+
+/// Runs `f` in a `crossbeam::scope`, handing it the scope and `temp` to share with the workers it spawns.
+///
+/// Every worker is joined before this function returns, and `Err` is returned if any of them panicked.
+/// If `reset` is true, the reset function is then invoked once more, unless the lock is poisoned.
+///
+/// # Examples
+/// ```
+/// use tempref::{mutex::Temp, scoped};
+///
+/// let workspace = Temp::new(Vec::new(), |v: &mut Vec<usize>| v.clear());
+/// scoped::scope(&workspace, true, |s, shared| {
+///     for n in 0..4 {
+///         s.spawn(move |_| shared.lock().unwrap().push(n));
+///     }
+/// })
+/// .unwrap();
+/// assert_eq!(workspace.generation(), 5);
+/// ```
+pub fn scope<'env, T, F, R>(
+    temp: &'env Temp<T, F>,
+    reset: bool,
+    f: impl FnOnce(&Scope<'env>, &'env Temp<T, F>) -> R,
+) -> std::thread::Result<R>
+where
+    T: Send,
+    F: FnMut(&mut T) + Send,
+{
+    let result = crossbeam::scope(|s| f(s, temp));
+    if reset {
+        let _ = temp.reset();
+    }
+    result
+}
+
+/// Moves the value out of `guard` and unlocks the `Temp`, leaving `T::default()` in its place until the returned
+/// [`Lent`] is dropped.
+///
+/// Unlike the guard, a `Lent` can be sent to another thread, e.g. through a `crossbeam::channel`.
+///
+/// # Examples
+/// ```
+/// use crossbeam::channel;
+/// use tempref::{pool::TempPool, scoped};
+///
+/// let pool = TempPool::from_fn(2, |_| String::with_capacity(64), |s| s.clear());
+/// let (tx, rx) = channel::bounded(2);
+/// crossbeam::scope(|s| {
+///     for n in 0..2 {
+///         let (pool, tx) = (&pool, tx.clone());
+///         s.spawn(move |_| {
+///             let mut line = pool.checkout().unwrap();
+///             line.push_str(&format!("worker {n}"));
+///             tx.send(scoped::lend(line)).unwrap();
+///         });
+///     }
+/// })
+/// .unwrap();
+/// let mut lines: Vec<_> = rx.try_iter().collect();
+/// lines.sort_by(|a, b| a.cmp(b));
+/// assert_eq!(*lines[0], "worker 0");
+/// drop(lines); // Each line goes back to its slot and is cleared.
+/// assert!(pool.as_slice().iter().all(|t| t.lock().unwrap().capacity() >= 64));
+/// ```
+pub fn lend<'a, T, F>(guard: TempRef<'a, T, F>) -> Lent<'a, T, F>
+where
+    T: Send + Default,
+    F: FnMut(&mut T) + Send,
+{
+    let temp = guard.temp();
+    Lent {
+        value: ManuallyDrop::new(TempRef::take(guard, false)),
+        temp,
+    }
+}
+
+/// A value moved out of a [`Temp<T, F>`] by [`lend`], which can be sent to another thread.
+///
+/// When it is dropped, it locks the `Temp`, puts the value back in place of the default one, and calls the reset
+/// function. The lock is taken even if it is poisoned.
+pub struct Lent<'a, T: Send, F: FnMut(&mut T) + Send> {
+    value: ManuallyDrop<T>,
+    temp: &'a Temp<T, F>,
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Lent<'a, T, F> {
+    /// Returns the `Temp` the value goes back to.
+    pub fn temp(&self) -> &'a Temp<T, F> {
+        self.temp
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::Deref for Lent<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> core::ops::DerefMut for Lent<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.value
+    }
+}
+impl<'a, T: Debug + Send, F: FnMut(&mut T) + Send> Debug for Lent<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_tuple("Lent").field(&*self.value).finish()
+    }
+}
+impl<'a, T: Send, F: FnMut(&mut T) + Send> Drop for Lent<'a, T, F> {
+    fn drop(&mut self) {
+        // SAFETY: `value` is never used again.
+        let value = unsafe { ManuallyDrop::take(&mut self.value) };
+        *self.temp.lock_unpoisoned() = value;
+    }
+}
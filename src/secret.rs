@@ -0,0 +1,115 @@
+//! Single thread version of TempRef for secrets, which are only reachable inside a closure.
+//!
+//! Unlike `unsync::TempRef`, the guard of [`SecretTemp`] has no `Deref`, so the secret cannot be reached by accident,
+//! e.g. by formatting the guard or passing it where a `&T` is expected. It is exposed with
+//! [`SecretRef::expose_secret`], the reset function wipes it when the guard is dropped, and `Debug` prints
+//! `[REDACTED]` instead of the value.
+
+use core::{
+    fmt::Debug,
+    sync::atomic::{self, Ordering},
+};
+
+use crate::{Error, unsync};
+
+/// A guard of [`SecretTemp<T, F>`], through which the secret is only reachable inside a closure.
+/// When it is dropped, it calls the reset function, which wipes the secret.
+pub struct SecretRef<'a, T, F: FnMut(&mut T)> {
+    re: unsync::TempRef<'a, T, F>,
+}
+impl<'a, T, F: FnMut(&mut T)> SecretRef<'a, T, F> {
+    /// Calls `f` with the secret, returning its result.
+    pub fn expose_secret<R>(&mut self, f: impl FnOnce(&mut T) -> R) -> R {
+        f(&mut self.re)
+    }
+    /// Calls `f` with the secret immutably, returning its result.
+    pub fn expose_secret_ref<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.re)
+    }
+    /// Invokes the reset function on the secret now.
+    pub fn reset(&mut self) {
+        self.re.reset();
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> Debug for SecretRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SecretRef([REDACTED])")
+    }
+}
+
+/// A secret which is wiped by the reset function whenever a guard is dropped.
+///
+/// # Examples
+/// ```
+/// use tempref::secret::SecretTemp;
+///
+/// let key = SecretTemp::zeroing([0u8; 32]);
+/// let checksum = key.with_secret(|k| {
+///     k.copy_from_slice(&[7; 32]); // E.g. derived from a password.
+///     k.iter().map(|&b| b as u32).sum::<u32>()
+/// });
+/// assert_eq!(checksum, 7 * 32);
+/// assert_eq!(format!("{key:?}"), "SecretTemp([REDACTED])");
+/// key.borrow_mut().expose_secret_ref(|k| assert_eq!(*k, [0; 32]));
+/// ```
+pub struct SecretTemp<T, F: FnMut(&mut T) = fn(&mut T)> {
+    inner: unsync::Temp<T, F>,
+}
+impl<T, F: FnMut(&mut T)> SecretTemp<T, F> {
+    /// A constructor of SecretTemp<T, F>, whose reset function `wipe` should erase the secret.
+    pub const fn new(value: T, wipe: F) -> Self {
+        SecretTemp {
+            inner: unsync::Temp::new(value, wipe),
+        }
+    }
+    /// Returns a guard of the secret.
+    ///
+    /// # Panics
+    /// Panics if the secret is already borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> SecretRef<'_, T, F> {
+        SecretRef {
+            re: self.inner.borrow_mut(),
+        }
+    }
+    /// Returns a guard of the secret, or [`Error::Borrow`] if it is already borrowed.
+    pub fn try_borrow_mut(&self) -> Result<SecretRef<'_, T, F>, Error> {
+        Ok(SecretRef {
+            re: self.inner.try_borrow_mut()?,
+        })
+    }
+    /// Calls `f` with the secret, then wipes it.
+    ///
+    /// # Panics
+    /// Panics if the secret is already borrowed.
+    #[track_caller]
+    pub fn with_secret<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        self.borrow_mut().expose_secret(f)
+    }
+    /// Returns how many times the secret has been wiped.
+    pub fn generation(&self) -> usize {
+        self.inner.generation()
+    }
+}
+impl<T: AsMut<[u8]>> SecretTemp<T, fn(&mut T)> {
+    /// A constructor of a SecretTemp for bytes such as `[u8; N]` or `Vec<u8>`, whose reset function zeroes them.
+    ///
+    /// Unlike `Temp::zeroing`, the bytes are written with volatile writes, so the compiler cannot remove the wipe
+    /// even if the bytes are never read again.
+    pub fn zeroing(value: T) -> Self {
+        SecretTemp::new(value, wipe_bytes::<T>)
+    }
+}
+impl<T, F: FnMut(&mut T)> Debug for SecretTemp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SecretTemp([REDACTED])")
+    }
+}
+
+fn wipe_bytes<T: AsMut<[u8]>>(value: &mut T) {
+    for byte in value.as_mut() {
+        // SAFETY: `byte` is a valid, aligned and exclusive reference.
+        unsafe { core::ptr::write_volatile(byte, 0) };
+    }
+    atomic::compiler_fence(Ordering::SeqCst);
+}
@@ -0,0 +1,206 @@
+//! Seqlock version of TempRef for `Copy` values. This module doesn't require std.
+//!
+//! Readers never write to shared memory: [`Temp::read`] copies the value and retries if a writer was active meanwhile,
+//! so frequent reads don't contend with each other. Writers are serialized by spinning, so this suits data which is
+//! read far more often than it is written.
+//!
+//! Like other seqlocks, a read racing a write copies the value with a volatile read and then discards it. The copy is
+//! kept as a `MaybeUninit<T>` until the sequence number is checked, so a torn copy is never taken as a `T`.
+
+use core::{
+    cell::UnsafeCell,
+    fmt::Debug,
+    hint,
+    mem::MaybeUninit,
+    ptr,
+    sync::atomic::{self, AtomicBool, AtomicUsize, Ordering},
+};
+
+use crate::{Error, UnwindPolicy, unwind};
+
+/// A mutable reference from [`Temp<T, F>`].
+/// When it is dropped, it calls the reset function and then publishes the value to readers.
+pub struct TempRef<'a, T: Copy, F: FnMut(&mut T)> {
+    temp: &'a Temp<T, F>,
+    _publish: Publish<'a>,
+}
+impl<'a, T: Copy, F: FnMut(&mut T)> core::ops::Deref for TempRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The sequence number is odd, so this guard is the only writer. Readers never make a reference to the
+        // value: they only copy it through a raw `*const MaybeUninit<T>` and discard the copy unless the sequence
+        // number is unchanged, so this is the only reference while the guard is alive.
+        unsafe { &*self.temp.value.get() }
+    }
+}
+impl<'a, T: Copy, F: FnMut(&mut T)> core::ops::DerefMut for TempRef<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: As in `deref`.
+        unsafe { &mut *self.temp.value.get() }
+    }
+}
+impl<'a, T: Copy, F: FnMut(&mut T)> Drop for TempRef<'a, T, F> {
+    fn drop(&mut self) {
+        // SAFETY: Only the writer calls the reset function, and there is one writer at a time.
+        let reset = unsafe { &mut *self.temp.reset.get() };
+        let poisoned = &self.temp.reset_poisoned;
+        unwind::reset_on_drop(UnwindPolicy::Reset, &mut **self, reset, || {
+            poisoned.store(true, Ordering::Release)
+        });
+        // `_publish` is dropped after this, even if the reset function panics.
+    }
+}
+
+// Makes the sequence number even again when a `TempRef` is dropped.
+struct Publish<'a> {
+    seq: &'a AtomicUsize,
+    start: usize,
+}
+impl<'a> Drop for Publish<'a> {
+    fn drop(&mut self) {
+        self.seq
+            .store(self.start.wrapping_add(2), Ordering::Release);
+    }
+}
+
+/// A `Copy` value behind a seqlock, which calls the reset function before every write is published.
+///
+/// # Examples
+/// ```
+/// use tempref::seqlock::Temp;
+///
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Stats { frames: u32, dropped: u32 }
+///
+/// let stats = Temp::new(Stats { frames: 10, dropped: 0 }, |s| s.dropped = 0);
+/// {
+///     let mut guard = stats.write();
+///     guard.frames += 1;
+///     guard.dropped += 3;
+/// } // dropped is reset here, and then readers see the new value.
+/// assert_eq!(stats.read(), Stats { frames: 11, dropped: 0 });
+/// ```
+pub struct Temp<T: Copy, F: FnMut(&mut T) = fn(&mut T)> {
+    seq: AtomicUsize,
+    value: UnsafeCell<T>,
+    reset: UnsafeCell<F>,
+    reset_poisoned: AtomicBool,
+}
+// SAFETY: Readers only copy the value out, and the value and the reset function are only mutated by the single writer.
+unsafe impl<T: Copy + Send, F: FnMut(&mut T) + Send> Sync for Temp<T, F> {}
+impl<T: Copy, F: FnMut(&mut T)> Temp<T, F> {
+    /// A constructor of Temp<T, F>.
+    pub const fn new(value: T, reset: F) -> Self {
+        Temp {
+            seq: AtomicUsize::new(0),
+            value: UnsafeCell::new(value),
+            reset: UnsafeCell::new(reset),
+            reset_poisoned: AtomicBool::new(false),
+        }
+    }
+    /// A constructor of Temp<T, F>.
+    ///
+    /// Unlike [`Self::new`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_with(mut value: T, mut reset: F) -> Self {
+        reset(&mut value);
+        Temp::new(value, reset)
+    }
+    /// Returns a copy of the value, spinning while a writer is active.
+    ///
+    /// This never returns while the current thread holds a [`TempRef`] of the same `Temp`.
+    pub fn read(&self) -> T {
+        loop {
+            match self.try_read() {
+                Ok(value) => return value,
+                Err(_) => hint::spin_loop(),
+            }
+        }
+    }
+    /// Returns a copy of the value, or [`Error::WouldBlock`] if a writer was active while copying it.
+    pub fn try_read(&self) -> Result<T, Error> {
+        let start = self.seq.load(Ordering::Acquire);
+        if start % 2 == 1 {
+            return Err(Error::WouldBlock);
+        }
+        // SAFETY: The pointer is valid and aligned, and any bytes are a valid `MaybeUninit<T>`, so a copy torn by a
+        // concurrent writer is read without being taken as a `T`. No reference to the value is made here.
+        let value = unsafe { ptr::read_volatile(self.value.get().cast::<MaybeUninit<T>>()) };
+        atomic::fence(Ordering::Acquire);
+        if self.seq.load(Ordering::Relaxed) == start {
+            // SAFETY: No writer was active while copying, so the copy is the published value, which is a valid `T`.
+            Ok(unsafe { value.assume_init() })
+        } else {
+            Err(Error::WouldBlock)
+        }
+    }
+    /// Returns how many writes have been published, each of which called the reset function.
+    pub fn generation(&self) -> usize {
+        self.seq.load(Ordering::Acquire) / 2
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.load(Ordering::Acquire)
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.store(false, Ordering::Release);
+    }
+    /// Returns a [`TempRef`], spinning while another writer is active.
+    pub fn write(&self) -> TempRef<'_, T, F> {
+        loop {
+            match self.try_write() {
+                Ok(guard) => return guard,
+                Err(_) => hint::spin_loop(),
+            }
+        }
+    }
+    /// Returns a [`TempRef`], or [`Error::WouldBlock`] if another writer is active.
+    pub fn try_write(&self) -> Result<TempRef<'_, T, F>, Error> {
+        let start = self.seq.load(Ordering::Relaxed);
+        if start % 2 == 1
+            || self
+                .seq
+                .compare_exchange(
+                    start,
+                    start.wrapping_add(1),
+                    Ordering::Acquire,
+                    Ordering::Relaxed,
+                )
+                .is_err()
+        {
+            return Err(Error::WouldBlock);
+        }
+        // Readers must see the odd sequence number before any write to the value.
+        atomic::fence(Ordering::Release);
+        Ok(TempRef {
+            temp: self,
+            _publish: Publish {
+                seq: &self.seq,
+                start,
+            },
+        })
+    }
+    /// Returns a mutable reference to the value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be alive and the reset function is not called.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Consumes the Temp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+impl<T: Copy + Debug, F: FnMut(&mut T)> Debug for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("Temp");
+        match self.try_read() {
+            Ok(value) => d.field("value", &value),
+            Err(_) => d.field("value", &format_args!("<locked>")),
+        };
+        d.finish()
+    }
+}
@@ -0,0 +1,106 @@
+//! Shards of [`mutex::Temp`](crate::mutex::Temp) spread over threads. This module requires std.
+
+extern crate std;
+
+use core::fmt::Debug;
+use core::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{PoisonError, TryLockError};
+
+use crate::mutex::{Temp, TempRef};
+
+type PoisonResult<T> = Result<T, PoisonError<T>>;
+type TryLockResult<T> = Result<T, TryLockError<T>>;
+
+std::thread_local! {
+    // The shard of the current thread, assigned round-robin on first use.
+    static THREAD_INDEX: usize = {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        NEXT.fetch_add(1, Ordering::Relaxed)
+    };
+}
+
+/// `N` independent [`Temp`]s, each of which is locked by the threads routed to it.
+///
+/// Every thread is assigned a shard round-robin the first time it uses any `ShardedTemp`,
+/// so with `N` at least the number of threads, [`Self::lock`] does not contend.
+///
+/// # Examples
+/// ```
+/// use tempref::sharded::ShardedTemp;
+///
+/// let scratch: ShardedTemp<Vec<u8>, _, 8> = ShardedTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+///
+/// std::thread::scope(|s| {
+///     for n in 0..16 {
+///         let scratch = &scratch;
+///         s.spawn(move || {
+///             let mut buffer = scratch.lock().unwrap();
+///             buffer.push(n);
+///             assert_eq!(*buffer, [n]);
+///         });
+///     }
+/// });
+/// ```
+pub struct ShardedTemp<T: Send, F: FnMut(&mut T) + Send, const N: usize> {
+    shards: [Temp<T, F>; N],
+}
+impl<T: Send, F: FnMut(&mut T) + Send + Clone, const N: usize> ShardedTemp<T, F, N> {
+    /// Creates `N` shards holding clones of `value`, each with a clone of `reset`.
+    ///
+    /// # Panics
+    /// Panics if `N` is zero.
+    pub fn new(value: T, reset: F) -> Self
+    where
+        T: Clone,
+    {
+        Self::from_fn(|_| value.clone(), reset)
+    }
+    /// Creates `N` shards holding the values returned by `f`, each with a clone of `reset`.
+    ///
+    /// # Panics
+    /// Panics if `N` is zero.
+    pub fn from_fn<C: FnMut(usize) -> T>(mut f: C, reset: F) -> Self {
+        assert!(N > 0, "ShardedTemp requires at least one shard");
+        ShardedTemp {
+            shards: core::array::from_fn(|index| Temp::new(f(index), reset.clone())),
+        }
+    }
+}
+impl<T: Send, F: FnMut(&mut T) + Send, const N: usize> ShardedTemp<T, F, N> {
+    /// Returns the shards.
+    pub fn as_slice(&self) -> &[Temp<T, F>] {
+        &self.shards
+    }
+    /// Returns the shard the current thread is routed to.
+    pub fn shard(&self) -> &Temp<T, F> {
+        &self.shards[THREAD_INDEX.with(|index| *index) % N]
+    }
+    /// Acquires the lock of the current thread's shard, blocking the current thread until it is able to do so.
+    pub fn lock(&self) -> PoisonResult<TempRef<'_, T, F>> {
+        self.shard().lock()
+    }
+    /// Attempts to acquire the lock of the current thread's shard.
+    /// If the lock is held by another thread routed to the same shard, then Err is returned.
+    pub fn try_lock(&self) -> TryLockResult<TempRef<'_, T, F>> {
+        self.shard().try_lock()
+    }
+    /// Invokes the reset function on every shard, blocking on each lock in turn.
+    ///
+    /// Poisoned shards are skipped, and a `PoisonError` is returned if there was any.
+    pub fn reset_all(&self) -> PoisonResult<()> {
+        let mut result = Ok(());
+        for shard in &self.shards {
+            if let Err(err) = shard.reset() {
+                result = Err(err);
+            }
+        }
+        result
+    }
+}
+impl<T: Debug + Send, F: FnMut(&mut T) + Send, const N: usize> Debug for ShardedTemp<T, F, N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ShardedTemp")
+            .field("shards", &self.shards)
+            .finish()
+    }
+}
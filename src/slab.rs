@@ -0,0 +1,192 @@
+//! Keyed arena of values sharing one reset function. This module requires alloc.
+//!
+//! [`TempSlab::insert`] stores a value in a free slot and returns its key, e.g. for the scratch state of a connection
+//! or an entity. [`TempSlab::get_mut`] returns a guard of one slot, which resets only that value when it is dropped,
+//! and [`TempSlab::remove`] resets the value before freeing its slot for the next `insert`.
+
+use alloc::vec::Vec;
+use core::fmt::Debug;
+
+enum Slot<T> {
+    Occupied(T),
+    Vacant(Option<usize>),
+}
+
+/// A mutable reference to a slot of [`TempSlab<T, F>`].
+///
+/// When dropped, it calls the reset function on the value of the slot.
+pub struct SlabRef<'a, T, F: FnMut(&mut T)> {
+    value: &'a mut T,
+    reset: &'a mut F,
+    generation: &'a mut usize,
+    key: usize,
+}
+impl<'a, T, F: FnMut(&mut T)> SlabRef<'a, T, F> {
+    /// Returns the key of the slot.
+    pub fn key(&self) -> usize {
+        self.key
+    }
+    /// Invokes the reset function on the value now.
+    pub fn reset(&mut self) {
+        (self.reset)(self.value);
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::Deref for SlabRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        self.value
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::DerefMut for SlabRef<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.value
+    }
+}
+impl<'a, T: Debug, F: FnMut(&mut T)> Debug for SlabRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SlabRef")
+            .field("key", &self.key)
+            .field("value", &self.value)
+            .finish()
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> Drop for SlabRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.reset();
+        *self.generation += 1;
+    }
+}
+
+/// A keyed arena whose values are reset whenever a guard of their slot is dropped, and before they are removed.
+///
+/// Keys are the indices of the slots, which are reused after [`Self::remove`], so a key must not be used after its
+/// value has been removed.
+///
+/// # Examples
+/// ```
+/// use tempref::slab::TempSlab;
+///
+/// let mut connections = TempSlab::new(|buf: &mut Vec<u8>| buf.clear());
+/// let a = connections.insert(Vec::with_capacity(1024));
+/// let b = connections.insert(Vec::with_capacity(1024));
+/// {
+///     let mut buf = connections.get_mut(a).unwrap();
+///     buf.extend_from_slice(b"GET / HTTP/1.1");
+///     assert_eq!(buf.len(), 14);
+/// }
+/// assert!(connections.get(a).unwrap().is_empty());
+///
+/// let buf = connections.remove(b).unwrap();
+/// assert!(buf.capacity() >= 1024);
+/// assert_eq!(connections.insert(Vec::new()), b); // The slot is reused.
+/// ```
+pub struct TempSlab<T, F: FnMut(&mut T) = fn(&mut T)> {
+    slots: Vec<Slot<T>>,
+    next_free: Option<usize>,
+    len: usize,
+    reset: F,
+    generation: usize,
+}
+impl<T, F: FnMut(&mut T)> TempSlab<T, F> {
+    /// A constructor of an empty TempSlab<T, F>.
+    pub const fn new(reset: F) -> Self {
+        TempSlab {
+            slots: Vec::new(),
+            next_free: None,
+            len: 0,
+            reset,
+            generation: 0,
+        }
+    }
+    /// A constructor of an empty TempSlab<T, F> with room for `capacity` values.
+    pub fn with_capacity(capacity: usize, reset: F) -> Self {
+        TempSlab {
+            slots: Vec::with_capacity(capacity),
+            ..TempSlab::new(reset)
+        }
+    }
+    /// Stores `value` in a free slot, and returns its key.
+    pub fn insert(&mut self, value: T) -> usize {
+        self.len += 1;
+        match self.next_free {
+            Some(key) => {
+                let Slot::Vacant(next) = self.slots[key] else {
+                    unreachable!("the free list only holds vacant slots")
+                };
+                self.next_free = next;
+                self.slots[key] = Slot::Occupied(value);
+                key
+            }
+            None => {
+                self.slots.push(Slot::Occupied(value));
+                self.slots.len() - 1
+            }
+        }
+    }
+    /// Returns the value of the slot `key`, or `None` if it is vacant.
+    pub fn get(&self, key: usize) -> Option<&T> {
+        match self.slots.get(key)? {
+            Slot::Occupied(value) => Some(value),
+            Slot::Vacant(_) => None,
+        }
+    }
+    /// Returns a guard of the slot `key`, which resets its value when dropped, or `None` if it is vacant.
+    pub fn get_mut(&mut self, key: usize) -> Option<SlabRef<'_, T, F>> {
+        match self.slots.get_mut(key)? {
+            Slot::Occupied(value) => Some(SlabRef {
+                value,
+                reset: &mut self.reset,
+                generation: &mut self.generation,
+                key,
+            }),
+            Slot::Vacant(_) => None,
+        }
+    }
+    /// Resets the value of the slot `key`, frees the slot and returns the value, or returns `None` if it is vacant.
+    pub fn remove(&mut self, key: usize) -> Option<T> {
+        if !self.contains(key) {
+            return None;
+        }
+        let Slot::Occupied(mut value) =
+            core::mem::replace(&mut self.slots[key], Slot::Vacant(self.next_free))
+        else {
+            unreachable!("the slot is occupied")
+        };
+        self.next_free = Some(key);
+        self.len -= 1;
+        (self.reset)(&mut value);
+        self.generation += 1;
+        Some(value)
+    }
+    /// Returns true if the slot `key` holds a value.
+    pub fn contains(&self, key: usize) -> bool {
+        self.get(key).is_some()
+    }
+    /// Returns how many values are stored.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if no value is stored.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns an iterator over the keys and values of the occupied slots, in the order of their keys.
+    pub fn iter(&self) -> impl Iterator<Item = (usize, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(key, slot)| match slot {
+                Slot::Occupied(value) => Some((key, value)),
+                Slot::Vacant(_) => None,
+            })
+    }
+    /// Returns how many times a value has been reset, by a guard or by [`Self::remove`].
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+}
+impl<T: Debug, F: FnMut(&mut T)> Debug for TempSlab<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
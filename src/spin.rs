@@ -0,0 +1,36 @@
+//! Bounded spinning before taking a lock of the std backends. This module requires std.
+
+extern crate std;
+
+use core::hint;
+use std::sync::TryLockError;
+
+/// The longest run of spins between two attempts.
+const MAX_BACKOFF: u32 = 64;
+
+/// Calls `attempt` until it stops returning `WouldBlock`, spinning `max_spins` times in total between the attempts.
+///
+/// The runs of spins double after every failed attempt, up to [`MAX_BACKOFF`], so a lock which is released right
+/// away is taken with little delay while a longer wait does not hammer its cache line.
+pub(crate) fn spin<G>(
+    max_spins: u32,
+    mut attempt: impl FnMut() -> Result<G, TryLockError<G>>,
+) -> Result<G, TryLockError<G>> {
+    let mut spins = 0;
+    let mut backoff = 1;
+    loop {
+        match attempt() {
+            Err(TryLockError::WouldBlock) => {}
+            result => return result,
+        }
+        if spins >= max_spins {
+            return Err(TryLockError::WouldBlock);
+        }
+        let run = backoff.min(max_spins - spins);
+        for _ in 0..run {
+            hint::spin_loop();
+        }
+        spins += run;
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
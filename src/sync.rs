@@ -0,0 +1,63 @@
+//! The synchronization primitives of the concurrent backends, which are those of `loom` under `cfg(loom)`.
+//!
+//! `Arc` stays std's, so that `lock_arc()` and the like can still take `self: &Arc<Self>`.
+//! loom's locks are never poisoned, so their poisoning methods are shimmed here. What needs a clock, a background
+//! thread, `OnceLock` or `RwLockWriteGuard::downgrade` is left out of the backends under `cfg(loom)`, and `new()` is not `const`.
+
+extern crate std;
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic;
+#[cfg(not(loom))]
+pub(crate) use std::sync::Condvar;
+#[cfg(not(loom))]
+pub(crate) use std::sync::Mutex;
+#[cfg(all(any(feature = "mutex", feature = "upgradable"), not(loom)))]
+pub(crate) use std::sync::MutexGuard;
+#[cfg(all(feature = "rwlock", not(loom)))]
+pub(crate) use std::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+#[cfg(loom)]
+pub(crate) use loom::sync::Condvar;
+#[cfg(all(any(feature = "mutex", feature = "upgradable"), loom))]
+pub(crate) use loom::sync::MutexGuard;
+#[cfg(loom)]
+pub(crate) use loom::sync::{Mutex, atomic};
+#[cfg(all(feature = "rwlock", loom))]
+pub(crate) use loom::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+/// The poisoning methods of the std locks, which loom's locks lack since they are never poisoned.
+#[cfg(loom)]
+pub(crate) trait Poison {
+    fn is_poisoned(&self) -> bool {
+        false
+    }
+    fn clear_poison(&self) {}
+}
+#[cfg(loom)]
+impl<T> Poison for Mutex<T> {}
+#[cfg(all(feature = "rwlock", loom))]
+impl<T> Poison for RwLock<T> {}
+
+/// `Condvar::wait_while`, which loom's `Condvar` lacks.
+#[cfg(all(feature = "mutex", loom))]
+pub(crate) trait WaitWhile {
+    fn wait_while<'a, T>(
+        &self,
+        guard: MutexGuard<'a, T>,
+        condition: impl FnMut(&mut T) -> bool,
+    ) -> std::sync::LockResult<MutexGuard<'a, T>>;
+}
+#[cfg(all(feature = "mutex", loom))]
+impl WaitWhile for Condvar {
+    fn wait_while<'a, T>(
+        &self,
+        mut guard: MutexGuard<'a, T>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> std::sync::LockResult<MutexGuard<'a, T>> {
+        while condition(&mut *guard) {
+            guard = self.wait(guard)?;
+        }
+        Ok(guard)
+    }
+}
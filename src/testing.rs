@@ -0,0 +1,203 @@
+//! A mock `Temp` for testing code which depends on [`TempAccess`]. This module requires std.
+//!
+//! [`MockTemp`] behaves like a `mutex::Temp`, but it records every borrow and reset as a [`MockEvent`],
+//! and the next accesses can be scripted to fail with an [`Error`], such as a poisoned or a contended lock.
+
+extern crate std;
+
+use core::fmt::Debug;
+use std::collections::VecDeque;
+use std::sync::{Mutex, MutexGuard, PoisonError, TryLockError};
+use std::vec::Vec;
+
+use crate::{Error, TempAccess};
+
+/// Something which happened to a [`MockTemp`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockEvent {
+    /// A guard was handed out.
+    Borrow,
+    /// The reset function was called, by a guard or by [`TempAccess::reset`].
+    Reset,
+    /// An access failed with the error, either scripted or real.
+    Fail(Error),
+}
+
+/// A mutable reference from [`MockTemp<T, F>`].
+/// When it is dropped, it calls the reset function and records [`MockEvent::Reset`].
+pub struct MockTempRef<'a, T, F: FnMut(&mut T)> {
+    re: MutexGuard<'a, T>,
+    temp: &'a MockTemp<T, F>,
+}
+impl<'a, T, F: FnMut(&mut T)> MockTempRef<'a, T, F> {
+    /// Invokes the reset function on the value.
+    pub fn reset(&mut self) {
+        self.temp.reset_value(&mut self.re);
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::Deref for MockTempRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::DerefMut for MockTempRef<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T: Debug, F: FnMut(&mut T)> Debug for MockTempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("MockTempRef")
+            .field("value", &*self.re)
+            .finish()
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> Drop for MockTempRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+/// A [`TempAccess`] which records what is done to it and fails on demand.
+///
+/// # Examples
+/// ```
+/// use tempref::testing::{MockEvent, MockTemp};
+/// use tempref::{Error, TempAccess};
+///
+/// fn append<W: TempAccess<Value = Vec<u8>>>(workspace: &W, data: &[u8]) -> Result<usize, Error> {
+///     let mut buf = workspace.try_access()?;
+///     buf.extend_from_slice(data);
+///     Ok(buf.len())
+/// }
+///
+/// let mock = MockTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+/// assert_eq!(append(&mock, b"abc"), Ok(3));
+///
+/// mock.fail_next(Error::WouldBlock);
+/// assert_eq!(append(&mock, b"abc"), Err(Error::WouldBlock));
+/// assert_eq!(
+///     mock.events(),
+///     [MockEvent::Borrow, MockEvent::Reset, MockEvent::Fail(Error::WouldBlock)]
+/// );
+/// ```
+pub struct MockTemp<T, F: FnMut(&mut T) = fn(&mut T)> {
+    value: Mutex<T>,
+    reset: Mutex<F>,
+    events: Mutex<Vec<MockEvent>>,
+    failures: Mutex<VecDeque<Error>>,
+}
+impl<T, F: FnMut(&mut T)> MockTemp<T, F> {
+    /// A constructor of MockTemp<T, F>.
+    pub const fn new(value: T, reset: F) -> Self {
+        MockTemp {
+            value: Mutex::new(value),
+            reset: Mutex::new(reset),
+            events: Mutex::new(Vec::new()),
+            failures: Mutex::new(VecDeque::new()),
+        }
+    }
+    /// Makes the next access, or reset, fail with `err` instead of touching the value.
+    ///
+    /// Failures are queued, so calling this several times scripts several failures in order.
+    pub fn fail_next(&self, err: Error) {
+        lock(&self.failures).push_back(err);
+    }
+    /// Returns every event recorded so far, oldest first.
+    pub fn events(&self) -> Vec<MockEvent> {
+        lock(&self.events).clone()
+    }
+    /// Forgets the recorded events.
+    pub fn clear_events(&self) {
+        lock(&self.events).clear();
+    }
+    /// Returns how many guards have been handed out.
+    pub fn borrows(&self) -> usize {
+        self.count(MockEvent::Borrow)
+    }
+    /// Returns how many times the reset function has been called.
+    pub fn resets(&self) -> usize {
+        self.count(MockEvent::Reset)
+    }
+    /// Consumes the MockTemp, returning the value.
+    pub fn into_inner(self) -> T {
+        self.value
+            .into_inner()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn count(&self, event: MockEvent) -> usize {
+        lock(&self.events).iter().filter(|&&e| e == event).count()
+    }
+    fn record(&self, event: MockEvent) {
+        lock(&self.events).push(event);
+    }
+    fn scripted(&self) -> Result<(), Error> {
+        match lock(&self.failures).pop_front() {
+            Some(err) => Err(self.fail(err)),
+            None => Ok(()),
+        }
+    }
+    fn fail(&self, err: Error) -> Error {
+        self.record(MockEvent::Fail(err));
+        err
+    }
+    fn reset_value(&self, value: &mut T) {
+        (lock(&self.reset))(value);
+        self.record(MockEvent::Reset);
+    }
+    fn guard<'a>(&'a self, re: MutexGuard<'a, T>) -> MockTempRef<'a, T, F> {
+        self.record(MockEvent::Borrow);
+        MockTempRef { re, temp: self }
+    }
+}
+impl<T, F: FnMut(&mut T)> TempAccess for MockTemp<T, F> {
+    type Value = T;
+    type Guard<'a>
+        = MockTempRef<'a, T, F>
+    where
+        Self: 'a;
+
+    fn access(&self) -> Result<Self::Guard<'_>, Error> {
+        self.scripted()?;
+        match self.value.lock() {
+            Ok(re) => Ok(self.guard(re)),
+            Err(_) => Err(self.fail(Error::Poisoned)),
+        }
+    }
+    fn try_access(&self) -> Result<Self::Guard<'_>, Error> {
+        self.scripted()?;
+        match self.value.try_lock() {
+            Ok(re) => Ok(self.guard(re)),
+            Err(TryLockError::Poisoned(_)) => Err(self.fail(Error::Poisoned)),
+            Err(TryLockError::WouldBlock) => Err(self.fail(Error::WouldBlock)),
+        }
+    }
+    fn reset(&self) -> Result<(), Error> {
+        self.scripted()?;
+        match self.value.lock() {
+            Ok(mut re) => {
+                self.reset_value(&mut re);
+                Ok(())
+            }
+            Err(_) => Err(self.fail(Error::Poisoned)),
+        }
+    }
+}
+impl<T: Debug, F: FnMut(&mut T)> Debug for MockTemp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut d = f.debug_struct("MockTemp");
+        match self.value.try_lock() {
+            Ok(guard) => d.field("value", &&*guard),
+            Err(TryLockError::Poisoned(err)) => d.field("value", &&*err.into_inner()),
+            Err(TryLockError::WouldBlock) => d.field("value", &format_args!("<locked>")),
+        };
+        d.field("events", &*lock(&self.events)).finish()
+    }
+}
+
+// The bookkeeping is never left inconsistent by a panic, so poisoning is ignored.
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
@@ -0,0 +1,61 @@
+//! A ticket queue which makes the blocking locks of a `mutex::Temp` FIFO. This module requires std.
+
+extern crate std;
+
+use std::sync::PoisonError;
+
+#[cfg(loom)]
+use crate::sync::WaitWhile;
+use crate::sync::{Condvar, Mutex};
+
+struct Tickets {
+    next: usize,
+    serving: usize,
+}
+
+// Hands out turns to take the lock in the order they were asked for.
+//
+// Only the thread whose turn it is waits on the lock itself, and the turn is passed on once it has the lock,
+// so a thread which unlocks and locks again has to queue up behind the threads which were already waiting.
+pub(crate) struct TicketQueue {
+    tickets: Mutex<Tickets>,
+    turn: Condvar,
+}
+impl TicketQueue {
+    pub(crate) fn new() -> Self {
+        TicketQueue {
+            tickets: Mutex::new(Tickets {
+                next: 0,
+                serving: 0,
+            }),
+            turn: Condvar::new(),
+        }
+    }
+    // Blocks until it is the turn of the current thread, which lasts until the returned value is dropped.
+    pub(crate) fn wait(&self) -> Turn<'_> {
+        let mut tickets = self.tickets.lock().unwrap_or_else(PoisonError::into_inner);
+        let ticket = tickets.next;
+        tickets.next = tickets.next.wrapping_add(1);
+        let _tickets = self
+            .turn
+            .wait_while(tickets, |tickets| tickets.serving != ticket)
+            .unwrap_or_else(PoisonError::into_inner);
+        Turn { queue: self }
+    }
+}
+
+pub(crate) struct Turn<'a> {
+    queue: &'a TicketQueue,
+}
+impl Drop for Turn<'_> {
+    fn drop(&mut self) {
+        let mut tickets = self
+            .queue
+            .tickets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        tickets.serving = tickets.serving.wrapping_add(1);
+        drop(tickets);
+        self.queue.turn.notify_all();
+    }
+}
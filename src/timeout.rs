@@ -0,0 +1,93 @@
+//! Time-bounded lock acquisition for the std backends. This module requires std.
+
+extern crate std;
+
+#[cfg(not(loom))]
+use core::time::Duration;
+use std::sync::PoisonError;
+#[cfg(not(loom))]
+use std::sync::TryLockError;
+#[cfg(not(loom))]
+use std::time::Instant;
+
+use crate::sync::atomic::{AtomicUsize, Ordering, fence};
+use crate::sync::{Condvar, Mutex};
+
+/// The threads waiting for a lock with a timeout, which are woken up whenever a guard of the lock is released.
+///
+/// std locks cannot be waited on with a deadline, so a waiter tries the lock, counts itself in and tries once more
+/// before it waits on a `Condvar`. A releaser checks the count after it has unlocked, and both sides go through a
+/// `SeqCst` fence in between, so either the second try sees the lock released or the releaser sees the waiter.
+pub(crate) struct Waiters {
+    count: AtomicUsize,
+    lock: Mutex<()>,
+    released: Condvar,
+}
+impl Waiters {
+    #[cfg(not(loom))]
+    pub(crate) const fn new() -> Self {
+        Waiters {
+            count: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            released: Condvar::new(),
+        }
+    }
+    #[cfg(loom)]
+    pub(crate) fn new() -> Self {
+        Waiters {
+            count: AtomicUsize::new(0),
+            lock: Mutex::new(()),
+            released: Condvar::new(),
+        }
+    }
+
+    /// Wakes up the waiters, if there are any. Called once a guard has released the lock.
+    pub(crate) fn notify(&self) {
+        fence(Ordering::SeqCst);
+        if self.count.load(Ordering::Relaxed) == 0 {
+            return;
+        }
+        // Taken so that a waiter which has counted itself in is already waiting.
+        drop(self.lock.lock().unwrap_or_else(PoisonError::into_inner));
+        self.released.notify_all();
+    }
+
+    /// Calls `attempt` until it stops returning `WouldBlock` or `timeout` has elapsed, waiting for a release in between.
+    #[cfg(not(loom))]
+    pub(crate) fn retry_until<G>(
+        &self,
+        timeout: Duration,
+        mut attempt: impl FnMut() -> Result<G, TryLockError<G>>,
+    ) -> Result<G, TryLockError<G>> {
+        let deadline = Instant::now().checked_add(timeout);
+        loop {
+            match attempt() {
+                Err(TryLockError::WouldBlock) => {}
+                result => return result,
+            }
+            let lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+            self.count.fetch_add(1, Ordering::Relaxed);
+            fence(Ordering::SeqCst);
+            let result = match attempt() {
+                Err(TryLockError::WouldBlock) => match deadline {
+                    Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                        Some(remaining) if !remaining.is_zero() => {
+                            drop(self.released.wait_timeout(lock, remaining));
+                            None
+                        }
+                        _ => Some(Err(TryLockError::WouldBlock)),
+                    },
+                    None => {
+                        drop(self.released.wait(lock));
+                        None
+                    }
+                },
+                result => Some(result),
+            };
+            self.count.fetch_sub(1, Ordering::Relaxed);
+            if let Some(result) = result {
+                return result;
+            }
+        }
+    }
+}
@@ -0,0 +1,173 @@
+//! Triple-buffered version of TempRef for one producer and one consumer. This module doesn't require std.
+//!
+//! Neither side ever waits for the other: the producer writes into its own buffer and publishes it when the guard
+//! is dropped, and the consumer reads the latest published buffer. The buffer the producer gets back in exchange
+//! is reset before it is written again, so every write starts from a clean value.
+
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicU8, AtomicUsize, Ordering},
+};
+
+use crate::{UnwindPolicy, unwind};
+
+// Set in `Shared::back` while the back buffer holds a value the consumer has not taken yet.
+const FRESH: u8 = 0b100;
+const INDEX: u8 = 0b011;
+
+struct Shared<T> {
+    buffers: [UnsafeCell<T>; 3],
+    back: AtomicU8,
+    generation: AtomicUsize,
+}
+// SAFETY: Each buffer is owned by the producer, the consumer or `back` at a time, and is handed over by swapping `back`.
+unsafe impl<T: Send> Sync for Shared<T> {}
+impl<T> Shared<T> {
+    // SAFETY: The caller must own the buffer at `index`.
+    #[allow(clippy::mut_from_ref)]
+    unsafe fn buffer(&self, index: u8) -> &mut T {
+        unsafe { &mut *self.buffers[(index & INDEX) as usize].get() }
+    }
+}
+
+/// The writing half of [`Temp<T, F>`].
+pub struct Producer<'a, T, F: FnMut(&mut T)> {
+    shared: &'a Shared<T>,
+    index: &'a mut u8,
+    reset: &'a mut F,
+}
+impl<'a, T, F: FnMut(&mut T)> Producer<'a, T, F> {
+    /// Returns a [`TempRef`] to the producer's buffer, which is published when it is dropped.
+    pub fn write(&mut self) -> TempRef<'_, 'a, T, F> {
+        TempRef { producer: self }
+    }
+}
+
+/// A mutable reference to the buffer of a [`Producer`].
+/// When it is dropped, the buffer is published and the buffer taken back in exchange is reset.
+pub struct TempRef<'p, 'a, T, F: FnMut(&mut T)> {
+    producer: &'p mut Producer<'a, T, F>,
+}
+impl<'p, 'a, T, F: FnMut(&mut T)> core::ops::Deref for TempRef<'p, 'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The producer owns its buffer.
+        unsafe { self.producer.shared.buffer(*self.producer.index) }
+    }
+}
+impl<'p, 'a, T, F: FnMut(&mut T)> core::ops::DerefMut for TempRef<'p, 'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // SAFETY: The producer owns its buffer.
+        unsafe { self.producer.shared.buffer(*self.producer.index) }
+    }
+}
+impl<'p, 'a, T, F: FnMut(&mut T)> Drop for TempRef<'p, 'a, T, F> {
+    fn drop(&mut self) {
+        let producer = &mut *self.producer;
+        let back = producer
+            .shared
+            .back
+            .swap(*producer.index | FRESH, Ordering::AcqRel);
+        *producer.index = back & INDEX;
+        // SAFETY: The producer owns the buffer it has just taken back.
+        let value = unsafe { producer.shared.buffer(*producer.index) };
+        unwind::reset_on_drop(UnwindPolicy::Reset, value, producer.reset, || {});
+        producer.shared.generation.fetch_add(1, Ordering::Release);
+    }
+}
+
+/// The reading half of [`Temp<T, F>`].
+pub struct Consumer<'a, T> {
+    shared: &'a Shared<T>,
+    index: &'a mut u8,
+}
+impl<'a, T> Consumer<'a, T> {
+    /// Returns whether a buffer has been published since the last [`Self::read`].
+    pub fn has_update(&self) -> bool {
+        self.shared.back.load(Ordering::Relaxed) & FRESH != 0
+    }
+    /// Returns how many reclaimed buffers the producer has reset.
+    pub fn generation(&self) -> usize {
+        self.shared.generation.load(Ordering::Acquire)
+    }
+    /// Takes the latest published buffer if there is one, and returns a reference to it.
+    pub fn read(&mut self) -> &T {
+        if self.has_update() {
+            let back = self.shared.back.swap(*self.index, Ordering::AcqRel);
+            *self.index = back & INDEX;
+        }
+        // SAFETY: The consumer owns its buffer.
+        unsafe { self.shared.buffer(*self.index) }
+    }
+}
+
+/// Three buffers shared by a [`Producer`] and a [`Consumer`], which call the reset function on every reclaimed buffer.
+///
+/// # Examples
+/// ```
+/// use tempref::triple::Temp;
+///
+/// let mut frames = Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+/// let (mut producer, mut consumer) = frames.split();
+/// std::thread::scope(|s| {
+///     s.spawn(move || {
+///         for n in 1..=3 {
+///             producer.write().push(n); // Published here. The next buffer written is cleared.
+///         }
+///     });
+/// });
+/// assert_eq!(consumer.read(), &[3]);
+/// ```
+pub struct Temp<T, F: FnMut(&mut T) = fn(&mut T)> {
+    shared: Shared<T>,
+    write: u8,
+    read: u8,
+    reset: F,
+}
+impl<T, F: FnMut(&mut T)> Temp<T, F> {
+    /// A constructor of Temp<T, F>, which clones `value` into the three buffers.
+    pub fn new(value: T, reset: F) -> Self
+    where
+        T: Clone,
+    {
+        Temp::from_buffers([value.clone(), value.clone(), value], reset)
+    }
+    /// A constructor of Temp<T, F> with the three buffers given.
+    ///
+    /// The first one is read by the consumer until the producer publishes, and the second one is written first.
+    pub fn from_buffers(buffers: [T; 3], reset: F) -> Self {
+        let [read, write, back] = buffers;
+        Temp {
+            shared: Shared {
+                buffers: [
+                    UnsafeCell::new(read),
+                    UnsafeCell::new(write),
+                    UnsafeCell::new(back),
+                ],
+                back: AtomicU8::new(2),
+                generation: AtomicUsize::new(0),
+            },
+            write: 1,
+            read: 0,
+            reset,
+        }
+    }
+    /// Returns how many reclaimed buffers the producer has reset.
+    pub fn generation(&self) -> usize {
+        self.shared.generation.load(Ordering::Acquire)
+    }
+    /// Splits the Temp into its [`Producer`] and [`Consumer`], which can be sent to different threads.
+    pub fn split(&mut self) -> (Producer<'_, T, F>, Consumer<'_, T>) {
+        (
+            Producer {
+                shared: &self.shared,
+                index: &mut self.write,
+                reset: &mut self.reset,
+            },
+            Consumer {
+                shared: &self.shared,
+                index: &mut self.read,
+            },
+        )
+    }
+}
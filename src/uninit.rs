@@ -0,0 +1,196 @@
+//! Single thread version of TempRef for uninitialized scratch buffers. This module requires alloc.
+//!
+//! The guard of [`TempUninit`] hands out the uninitialized part of the buffer as `&mut [MaybeUninit<T>]`, and keeps
+//! how many elements from the start have been initialized, which it derefs to. When the guard is dropped, the
+//! initialized elements are dropped and the whole buffer is uninitialized again, so nothing written by one guard
+//! can be read by the next. [`TempUninit::with_wipe`] also zeroes the memory, e.g. for buffers holding secrets.
+
+use alloc::boxed::Box;
+use core::{
+    cell::{Cell, RefCell, RefMut},
+    fmt::Debug,
+    mem::MaybeUninit,
+    ptr,
+};
+
+/// A mutable reference from [`TempUninit<T>`], whose first [`Self::len`] elements are initialized.
+/// When it is dropped, the initialized elements are dropped and the buffer is uninitialized again.
+pub struct UninitRef<'a, T> {
+    re: RefMut<'a, Box<[MaybeUninit<T>]>>,
+    len: usize,
+    temp: &'a TempUninit<T>,
+}
+impl<'a, T> UninitRef<'a, T> {
+    /// Returns how many elements from the start are initialized.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    /// Returns true if no element is initialized.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Returns the number of elements of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.re.len()
+    }
+    /// Returns the uninitialized rest of the buffer, after the initialized elements.
+    ///
+    /// What is written there only counts as initialized after [`Self::assume_init`].
+    pub fn spare_mut(&mut self) -> &mut [MaybeUninit<T>] {
+        &mut self.re[self.len..]
+    }
+    /// Marks the first `count` elements of [`Self::spare_mut`] as initialized.
+    ///
+    /// # Safety
+    /// Those elements must have been initialized, and `count` must not exceed the length of [`Self::spare_mut`].
+    pub unsafe fn assume_init(&mut self, count: usize) {
+        debug_assert!(count <= self.re.len() - self.len);
+        self.len += count;
+    }
+    /// Initializes the next element with `value`, or returns it back if the buffer is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        match self.re.get_mut(self.len) {
+            Some(slot) => {
+                slot.write(value);
+                self.len += 1;
+                Ok(())
+            }
+            None => Err(value),
+        }
+    }
+    /// Initializes the next elements with clones of `values`, and returns how many fit in the buffer.
+    pub fn extend_from_slice(&mut self, values: &[T]) -> usize
+    where
+        T: Clone,
+    {
+        let count = values.len().min(self.re.len() - self.len);
+        for value in &values[..count] {
+            // Counted one by one, so that a panicking `clone` leaves only initialized elements counted.
+            let _ = self.push(value.clone());
+        }
+        count
+    }
+    /// Drops the initialized elements and uninitializes the buffer now, wiping it if the `TempUninit` does.
+    pub fn reset(&mut self) {
+        let len = core::mem::take(&mut self.len);
+        let slice = ptr::slice_from_raw_parts_mut(self.re.as_mut_ptr().cast::<T>(), len);
+        // SAFETY: The first `len` elements are initialized, and they are no longer counted, so they are dropped once.
+        unsafe { ptr::drop_in_place(slice) };
+        if self.temp.wipe {
+            // SAFETY: Any byte pattern is valid for `MaybeUninit`, and the pointer covers the whole buffer.
+            unsafe { ptr::write_bytes(self.re.as_mut_ptr(), 0, self.re.len()) };
+        }
+    }
+}
+impl<'a, T> core::ops::Deref for UninitRef<'a, T> {
+    type Target = [T];
+    fn deref(&self) -> &Self::Target {
+        // SAFETY: The first `len` elements are initialized.
+        unsafe { &*(ptr::from_ref(&self.re[..self.len]) as *const [T]) }
+    }
+}
+impl<'a, T> core::ops::DerefMut for UninitRef<'a, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let len = self.len;
+        // SAFETY: As in `deref`.
+        unsafe { &mut *(ptr::from_mut(&mut self.re[..len]) as *mut [T]) }
+    }
+}
+impl<'a, T: Debug> Debug for UninitRef<'a, T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UninitRef")
+            .field("init", &&**self)
+            .field("capacity", &self.capacity())
+            .finish()
+    }
+}
+impl<'a, T> Drop for UninitRef<'a, T> {
+    fn drop(&mut self) {
+        self.reset();
+        self.temp.generation.set(self.temp.generation.get() + 1);
+    }
+}
+
+/// A buffer of `T` which is uninitialized whenever no guard is alive.
+///
+/// # Examples
+/// ```
+/// use tempref::uninit::TempUninit;
+///
+/// let scratch = TempUninit::<u32>::with_capacity(4);
+/// {
+///     let mut guard = scratch.borrow_mut();
+///     for (slot, n) in guard.spare_mut()[..2].iter_mut().zip([1, 2]) {
+///         slot.write(n);
+///     }
+///     // SAFETY: The first two elements were just written.
+///     unsafe { guard.assume_init(2) };
+///     guard.push(3).unwrap();
+///     assert_eq!(*guard, [1, 2, 3]);
+/// }
+/// assert!(scratch.borrow_mut().is_empty()); // Uninitialized again.
+/// ```
+pub struct TempUninit<T> {
+    buf: RefCell<Box<[MaybeUninit<T>]>>,
+    capacity: usize,
+    wipe: bool,
+    generation: Cell<usize>,
+}
+impl<T> TempUninit<T> {
+    /// A constructor of `TempUninit<T>`, whose buffer has room for `capacity` elements.
+    pub fn with_capacity(capacity: usize) -> Self {
+        TempUninit {
+            buf: RefCell::new(Box::new_uninit_slice(capacity)),
+            capacity,
+            wipe: false,
+            generation: Cell::new(0),
+        }
+    }
+    /// Sets whether the guards zero the whole buffer after dropping its elements.
+    ///
+    /// The zeroing is not volatile, so it may be optimized out if the buffer is never read again.
+    pub fn with_wipe(mut self, wipe: bool) -> Self {
+        self.wipe = wipe;
+        self
+    }
+    /// Returns whether the guards zero the buffer, as set with [`Self::with_wipe`].
+    pub fn wipes(&self) -> bool {
+        self.wipe
+    }
+    /// Mutably borrows the buffer, with no element initialized.
+    ///
+    /// # Panics
+    /// Panics if the buffer is already borrowed.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> UninitRef<'_, T> {
+        UninitRef {
+            re: self.buf.borrow_mut(),
+            len: 0,
+            temp: self,
+        }
+    }
+    /// Mutably borrows the buffer, or returns [`Error::Borrow`](crate::Error::Borrow) if it is already borrowed.
+    pub fn try_borrow_mut(&self) -> Result<UninitRef<'_, T>, crate::Error> {
+        Ok(UninitRef {
+            re: self.buf.try_borrow_mut()?,
+            len: 0,
+            temp: self,
+        })
+    }
+    /// Returns the number of elements of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Returns how many guards have been dropped, each of which uninitialized the buffer.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+}
+impl<T> Debug for TempUninit<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("TempUninit")
+            .field("capacity", &self.capacity)
+            .field("wipe", &self.wipe)
+            .finish()
+    }
+}
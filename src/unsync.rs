@@ -1,35 +1,139 @@
 //! Single thread version of TempRef. This module doesn't require std.
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "std")]
+use core::time::Duration;
 use core::{
-    cell::{BorrowError, BorrowMutError, Ref, RefCell, RefMut, UnsafeCell},
-    fmt::Debug,
+    cell::{BorrowError, BorrowMutError, Cell, Ref, RefCell, RefMut, UnsafeCell},
+    fmt::{Debug, Display},
+    mem::{self, ManuallyDrop},
+    pin::Pin,
+    ptr,
 };
 
+#[cfg(feature = "alloc")]
+use alloc::{boxed::Box, rc::Rc};
+
+#[cfg(feature = "hooks")]
+use crate::TempHooks;
+use crate::clock::ResetClock;
+use crate::hooks::Hooks;
+use crate::instrument::Instrument;
+#[cfg(feature = "stats")]
+use crate::instrument::Stats;
+use crate::{UnwindPolicy, unwind};
+
+// The predicate set with `with_reset_if()`.
+#[cfg(feature = "alloc")]
+type ResetIf<T> = Rc<dyn Fn(&T) -> bool>;
+
 /// A mutable reference wrapper from [`Temp<T, F>`].
 ///
 /// When dropped, it automatically calls the reset function on the underlying value.
 /// This ensures that temporary mutations never leave the value in an inconsistent state.
 pub struct TempRef<'a, T, F: FnMut(&mut T)> {
     re: RefMut<'a, T>,
-    reset: &'a mut F,
+    temp: &'a Temp<T, F>,
 }
 impl<'a, T, F: FnMut(&mut T)> TempRef<'a, T, F> {
+    #[track_caller]
     fn new(temp: &'a Temp<T, F>) -> Self {
-        TempRef {
-            re: temp.value.borrow_mut(),
-            reset: unsafe { &mut *temp.reset.get() },
-        }
+        let mut re = temp.borrow_value_mut();
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re);
+        temp.instrument.acquire::<T>();
+        TempRef { re, temp }
     }
+    #[track_caller]
     fn try_new(temp: &'a Temp<T, F>) -> Result<Self, BorrowMutError> {
-        Ok(TempRef {
-            re: temp.value.try_borrow_mut()?,
-            reset: unsafe { &mut *temp.reset.get() },
-        })
+        let mut re = temp.try_borrow_value_mut()?;
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re);
+        temp.instrument.acquire::<T>();
+        Ok(TempRef { re, temp })
     }
 
     /// Invokes the reset function on the internal value.
+    ///
+    /// If the reset function panics, the `Temp` is marked as [reset-poisoned](Temp::is_reset_poisoned).
     pub fn reset(&mut self) {
-        (self.reset)(&mut self.re);
+        self.temp.reset_or_poison(&mut self.re);
+    }
+    /// Makes a [`MappedTempRef`] for a component of the borrowed data.
+    ///
+    /// The reset function still runs on the whole value when the returned reference is dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::{Temp, TempRef};
+    ///
+    /// let workspace = Temp::new((vec![0; 4], 0), |(v, n)| {
+    ///     v.fill(0);
+    ///     *n = 0;
+    /// });
+    ///
+    /// {
+    ///     let mut first = TempRef::map(workspace.borrow_mut(), |(v, _)| v);
+    ///     first.fill(1);
+    /// }
+    /// assert_eq!(*workspace.borrow(), (vec![0; 4], 0));
+    /// ```
+    pub fn map<U: ?Sized, M: FnOnce(&mut T) -> &mut U>(
+        orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, U, F> {
+        let (re, temp) = orig.into_parts();
+        MappedTempRef::new(RefMut::map(re, f), temp)
+    }
+    /// Splits a `TempRef` into multiple [`MappedTempRef`]s for different components of the borrowed data.
+    ///
+    /// The reset function runs on the whole value once both returned references are dropped.
+    pub fn map_split<U: ?Sized, V: ?Sized, M: FnOnce(&mut T) -> (&mut U, &mut V)>(
+        orig: TempRef<'a, T, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, U, F>, MappedTempRef<'a, T, V, F>) {
+        let (re, temp) = orig.into_parts();
+        let (a, b) = RefMut::map_split(re, f);
+        (MappedTempRef::new(a, temp), MappedTempRef::new(b, temp))
+    }
+
+    /// Moves the value out of the guard, leaving `T::default()` in its place, without cloning it.
+    ///
+    /// If `reset` is true, the reset function runs on the default value when the guard is dropped as usual.
+    /// Otherwise the default value is left as it is.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::{Temp, TempRef};
+    ///
+    /// let results = Temp::new(Vec::new(), |v: &mut Vec<i32>| v.clear());
+    /// let mut guard = results.borrow_mut();
+    /// guard.extend([1, 2, 3]);
+    /// assert_eq!(TempRef::take(guard, false), [1, 2, 3]);
+    /// assert!(results.borrow().is_empty());
+    /// assert_eq!(results.generation(), 0);
+    /// ```
+    pub fn take(orig: TempRef<'a, T, F>, reset: bool) -> T
+    where
+        T: Default,
+    {
+        let mut orig = orig;
+        let value = mem::take(&mut *orig);
+        if !reset {
+            let (re, temp) = orig.into_parts();
+            temp.hooks.release(&re);
+            temp.instrument.release::<T>();
+            drop(re);
+        }
+        value
+    }
+
+    fn into_parts(self) -> (RefMut<'a, T>, &'a Temp<T, F>) {
+        let this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `re` is moved out exactly once.
+        (unsafe { ptr::read(&this.re) }, this.temp)
     }
 }
 impl<'a, T, F: FnMut(&mut T)> core::ops::Deref for TempRef<'a, T, F> {
@@ -43,9 +147,284 @@ impl<'a, T, F: FnMut(&mut T)> core::ops::DerefMut for TempRef<'a, T, F> {
         &mut self.re
     }
 }
+impl<'a, T, F: FnMut(&mut T)> AsRef<T> for TempRef<'a, T, F> {
+    fn as_ref(&self) -> &T {
+        self
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> AsMut<T> for TempRef<'a, T, F> {
+    fn as_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::borrow::Borrow<T> for TempRef<'a, T, F> {
+    fn borrow(&self) -> &T {
+        self
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::borrow::BorrowMut<T> for TempRef<'a, T, F> {
+    fn borrow_mut(&mut self) -> &mut T {
+        self
+    }
+}
+impl<'a, T: core::fmt::Write, F: FnMut(&mut T)> core::fmt::Write for TempRef<'a, T, F> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.re.write_str(s)
+    }
+    fn write_char(&mut self, c: char) -> core::fmt::Result {
+        self.re.write_char(c)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> core::fmt::Result {
+        self.re.write_fmt(args)
+    }
+}
+#[cfg(feature = "std")]
+impl<'a, T: std::io::Read, F: FnMut(&mut T)> std::io::Read for TempRef<'a, T, F> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.re.read(buf)
+    }
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        self.re.read_vectored(bufs)
+    }
+    fn read_to_end(&mut self, buf: &mut std::vec::Vec<u8>) -> std::io::Result<usize> {
+        self.re.read_to_end(buf)
+    }
+    fn read_to_string(&mut self, buf: &mut std::string::String) -> std::io::Result<usize> {
+        self.re.read_to_string(buf)
+    }
+    fn read_exact(&mut self, buf: &mut [u8]) -> std::io::Result<()> {
+        self.re.read_exact(buf)
+    }
+}
+#[cfg(feature = "std")]
+impl<'a, T: std::io::Write, F: FnMut(&mut T)> std::io::Write for TempRef<'a, T, F> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.re.write(buf)
+    }
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        self.re.write_vectored(bufs)
+    }
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.re.flush()
+    }
+    fn write_all(&mut self, buf: &[u8]) -> std::io::Result<()> {
+        self.re.write_all(buf)
+    }
+    fn write_fmt(&mut self, args: core::fmt::Arguments<'_>) -> std::io::Result<()> {
+        self.re.write_fmt(args)
+    }
+}
+impl<'a, T: Display, F: FnMut(&mut T)> Display for TempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+/// Archives the value alone, so `Archived<TempRef<'_, T, F>>` is `Archived<T>`.
+///
+/// The guard holds the borrow, so the value cannot change between serializing and resolving it.
+/// The archive deserializes back into a `T`. The `Ref` of [`Temp::borrow`] is `core`'s, so a shared borrow is
+/// archived as `&*temp.borrow()`.
+#[cfg(feature = "rkyv")]
+impl<'a, T: rkyv::Archive, F: FnMut(&mut T)> rkyv::Archive for TempRef<'a, T, F> {
+    type Archived = T::Archived;
+    type Resolver = T::Resolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: rkyv::Place<Self::Archived>) {
+        (**self).resolve(resolver, out);
+    }
+}
+#[cfg(feature = "rkyv")]
+impl<'a, T, F, S> rkyv::Serialize<S> for TempRef<'a, T, F>
+where
+    T: rkyv::Serialize<S>,
+    F: FnMut(&mut T),
+    S: rkyv::rancor::Fallible + ?Sized,
+{
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        (**self).serialize(serializer)
+    }
+}
 impl<'a, T, F: FnMut(&mut T)> Drop for TempRef<'a, T, F> {
     fn drop(&mut self) {
-        (self.reset)(&mut self.re);
+        self.temp.release(&mut self.re);
+    }
+}
+
+/// A mutable reference to a component of a value borrowed from [`Temp<T, F>`].
+///
+/// Created by [`TempRef::map`] and [`TempRef::map_split`].
+/// The reset function runs on the whole value when the last reference mapped from the same `TempRef` is dropped.
+pub struct MappedTempRef<'a, T, U: ?Sized, F: FnMut(&mut T)> {
+    re: ManuallyDrop<RefMut<'a, U>>,
+    temp: &'a Temp<T, F>,
+}
+impl<'a, T, U: ?Sized, F: FnMut(&mut T)> MappedTempRef<'a, T, U, F> {
+    fn new(re: RefMut<'a, U>, temp: &'a Temp<T, F>) -> Self {
+        MappedTempRef {
+            re: ManuallyDrop::new(re),
+            temp,
+        }
+    }
+
+    /// Makes a new `MappedTempRef` for a component of the borrowed data.
+    pub fn map<V: ?Sized, M: FnOnce(&mut U) -> &mut V>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> MappedTempRef<'a, T, V, F> {
+        let (re, temp) = orig.into_parts();
+        MappedTempRef::new(RefMut::map(re, f), temp)
+    }
+    /// Splits a `MappedTempRef` into multiple `MappedTempRef`s for different components of the borrowed data.
+    pub fn map_split<V: ?Sized, W: ?Sized, M: FnOnce(&mut U) -> (&mut V, &mut W)>(
+        orig: MappedTempRef<'a, T, U, F>,
+        f: M,
+    ) -> (MappedTempRef<'a, T, V, F>, MappedTempRef<'a, T, W, F>) {
+        let (re, temp) = orig.into_parts();
+        let (a, b) = RefMut::map_split(re, f);
+        (MappedTempRef::new(a, temp), MappedTempRef::new(b, temp))
+    }
+
+    fn into_parts(self) -> (RefMut<'a, U>, &'a Temp<T, F>) {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: `this` is never dropped, so `re` is taken exactly once.
+        (unsafe { ManuallyDrop::take(&mut this.re) }, this.temp)
+    }
+}
+impl<'a, T, U: ?Sized, F: FnMut(&mut T)> core::ops::Deref for MappedTempRef<'a, T, U, F> {
+    type Target = U;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T, U: ?Sized, F: FnMut(&mut T)> core::ops::DerefMut for MappedTempRef<'a, T, U, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T, U: Display + ?Sized, F: FnMut(&mut T)> Display for MappedTempRef<'a, T, U, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+impl<'a, T, U: ?Sized, F: FnMut(&mut T)> Drop for MappedTempRef<'a, T, U, F> {
+    fn drop(&mut self) {
+        // SAFETY: `re` is not used after this point.
+        unsafe { ManuallyDrop::drop(&mut self.re) };
+        // Succeeds only once every reference split from the same `TempRef` has been released.
+        if let Ok(mut value) = self.temp.value.try_borrow_mut() {
+            self.temp.release(&mut value);
+        }
+    }
+}
+
+/// A mutable reference from an `Rc<Temp<T, F>>`.
+///
+/// Created by [`Temp::borrow_mut_owned`]. Unlike [`TempRef`], it keeps the `Temp` alive by itself and has no lifetime,
+/// so it can be stored in `'static` contexts such as callbacks.
+/// When dropped, it calls the reset function before releasing the `Rc`.
+#[cfg(feature = "alloc")]
+pub struct OwnedTempRef<T: 'static, F: FnMut(&mut T) + 'static> {
+    // Declared before `temp` so that it is dropped first.
+    guard: TempRef<'static, T, F>,
+    temp: Rc<Temp<T, F>>,
+}
+#[cfg(feature = "alloc")]
+impl<T: 'static, F: FnMut(&mut T) + 'static> OwnedTempRef<T, F> {
+    #[track_caller]
+    fn try_new(temp: &Rc<Temp<T, F>>) -> Result<Self, BorrowMutError> {
+        let temp = Rc::clone(temp);
+        // SAFETY: the guard borrows the `Temp` owned by `temp`, which is dropped after the guard.
+        let target: &'static Temp<T, F> = unsafe { &*Rc::as_ptr(&temp) };
+        Ok(OwnedTempRef {
+            guard: TempRef::try_new(target)?,
+            temp,
+        })
+    }
+
+    /// Invokes the reset function on the internal value.
+    pub fn reset(&mut self) {
+        self.guard.reset();
+    }
+    /// Returns the `Rc` which owns the borrowed `Temp`.
+    pub fn temp(&self) -> &Rc<Temp<T, F>> {
+        &self.temp
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: 'static, F: FnMut(&mut T) + 'static> core::ops::Deref for OwnedTempRef<T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: 'static, F: FnMut(&mut T) + 'static> core::ops::DerefMut for OwnedTempRef<T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T: Display + 'static, F: FnMut(&mut T) + 'static> Display for OwnedTempRef<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+
+/// A transactional mutable reference from [`Temp<T, F>`].
+///
+/// Changes made through it are kept only if [`Self::commit`] is called.
+/// Otherwise the reset function is called when it is dropped,
+/// including while unwinding from a panic unless the `Temp` uses [`UnwindPolicy::Skip`].
+pub struct Transaction<'a, T, F: FnMut(&mut T)> {
+    re: RefMut<'a, T>,
+    temp: &'a Temp<T, F>,
+    committed: bool,
+}
+impl<'a, T, F: FnMut(&mut T)> Transaction<'a, T, F> {
+    #[track_caller]
+    fn new(temp: &'a Temp<T, F>) -> Self {
+        let mut re = temp.borrow_value_mut();
+        temp.expire(&mut re);
+        temp.hooks.acquire(&re);
+        temp.instrument.acquire::<T>();
+        Transaction {
+            re,
+            temp,
+            committed: false,
+        }
+    }
+
+    /// Ends the transaction, keeping the changes without calling the reset function.
+    pub fn commit(mut self) {
+        self.committed = true;
+    }
+    /// Ends the transaction, calling the reset function immediately.
+    pub fn rollback(self) {}
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::Deref for Transaction<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> core::ops::DerefMut for Transaction<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T: Display, F: FnMut(&mut T)> Display for Transaction<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&**self, f)
+    }
+}
+impl<'a, T, F: FnMut(&mut T)> Drop for Transaction<'a, T, F> {
+    fn drop(&mut self) {
+        if self.committed {
+            self.temp.hooks.release(&self.re);
+            self.temp.instrument.release::<T>();
+        } else {
+            self.temp.reset_on_drop(&mut self.re);
+        }
     }
 }
 
@@ -74,9 +453,38 @@ impl<'a, T, F: FnMut(&mut T)> Drop for TempRef<'a, T, F> {
 /// }
 /// assert_eq!(*workspace.borrow(), vec![0;128]);
 /// ```
-pub struct Temp<T, F: FnMut(&mut T)> {
+///
+/// # Unsized values
+/// `Temp<[T]>`, `Temp<str>` and `Temp<dyn Trait>` are not supported, and `T: ?Sized` is not planned. A struct can only
+/// be unsized through its last field, and the type of the value appears in more than one field of `Temp`: the hooks and
+/// the [`Self::with_reset_if`] predicate are typed against it, so a `Temp<[u8; 16]>` can never coerce into a
+/// `Temp<[u8]>`. Boxing the value internally would only hide the same `Box` as below behind every access.
+///
+/// Slices, `str` and trait objects can be kept in a `Box`, and [`TempRef::map`] gives a guard typed against the
+/// unsized value itself.
+/// ```
+/// use tempref::unsync::{Temp, TempRef};
+///
+/// let workspace: Temp<Box<[u8]>> = Temp::new(vec![0; 16].into(), |bytes| bytes.fill(0));
+/// {
+///     let mut bytes = TempRef::map(workspace.borrow_mut(), |bytes| &mut **bytes);
+///     bytes.copy_from_slice(&[1; 16]);
+/// }
+/// assert_eq!(*workspace.borrow(), [0; 16].into());
+/// ```
+pub struct Temp<T, F: FnMut(&mut T) = fn(&mut T)> {
     value: RefCell<T>,
     reset: UnsafeCell<F>,
+    unwind: UnwindPolicy,
+    reset_poisoned: Cell<bool>,
+    generation: Cell<usize>,
+    hooks: Hooks<T>,
+    instrument: Instrument,
+    clock: ResetClock,
+    // Shared by the clones of the `Temp`.
+    #[cfg(feature = "alloc")]
+    reset_if: Option<ResetIf<T>>,
+    borrowed_at: BorrowSite,
 }
 impl<T, F: FnMut(&mut T)> Temp<T, F> {
     /// A constructor of Temp<T, F>.
@@ -84,6 +492,15 @@ impl<T, F: FnMut(&mut T)> Temp<T, F> {
         Temp {
             value: RefCell::new(value),
             reset: UnsafeCell::new(reset),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: Cell::new(false),
+            generation: Cell::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            #[cfg(feature = "alloc")]
+            reset_if: None,
+            borrowed_at: BorrowSite::new(),
         }
     }
     /// A constructor of Temp<T, F>.
@@ -95,24 +512,301 @@ impl<T, F: FnMut(&mut T)> Temp<T, F> {
         Temp {
             value: RefCell::new(value),
             reset: UnsafeCell::new(reset),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: Cell::new(false),
+            generation: Cell::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            #[cfg(feature = "alloc")]
+            reset_if: None,
+            borrowed_at: BorrowSite::new(),
         }
     }
+    /// Replaces the reset function.
+    ///
+    /// Since this call borrows the `Temp` mutably, no guard can be using the old one.
+    pub fn set_reset(&mut self, reset: F) {
+        *self.reset.get_mut() = reset;
+    }
+    /// Consumes the Temp, returning a new one with the same value and a reset function of another type.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let buffer = Temp::new(vec![1u8; 4], |v| v.clear());
+    /// let buffer = buffer.with_reset(|v: &mut Vec<u8>| v.fill(0));
+    /// buffer.borrow_mut().push(1);
+    /// assert_eq!(*buffer.borrow(), vec![0; 5]);
+    /// ```
+    pub fn with_reset<G: FnMut(&mut T)>(self, reset: G) -> Temp<T, G> {
+        Temp {
+            value: self.value,
+            reset: UnsafeCell::new(reset),
+            unwind: self.unwind,
+            reset_poisoned: self.reset_poisoned,
+            generation: self.generation,
+            hooks: self.hooks,
+            instrument: self.instrument,
+            clock: self.clock,
+            #[cfg(feature = "alloc")]
+            reset_if: self.reset_if,
+            borrowed_at: self.borrowed_at,
+        }
+    }
+    /// Consumes the Temp, returning a new one whose reset function calls the current one followed by `extra`.
+    ///
+    /// The value is not reset, and the [`UnwindPolicy`] and the reset-poisoned flag are kept.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let buffer = Temp::new(Vec::with_capacity(16), |v: &mut Vec<u8>| v.clear())
+    ///     .chain_reset(|v| v.shrink_to(64));
+    /// buffer.borrow_mut().extend([0; 1024]);
+    /// assert!(buffer.borrow().is_empty());
+    /// assert!(buffer.borrow().capacity() <= 64);
+    /// ```
+    pub fn chain_reset<G: FnMut(&mut T)>(self, mut extra: G) -> Temp<T, impl FnMut(&mut T)> {
+        let Temp {
+            value,
+            reset,
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            #[cfg(feature = "alloc")]
+            reset_if,
+            borrowed_at,
+        } = self;
+        let mut reset = reset.into_inner();
+        Temp {
+            value,
+            reset: UnsafeCell::new(move |value: &mut T| {
+                reset(value);
+                extra(value);
+            }),
+            unwind,
+            reset_poisoned,
+            generation,
+            hooks,
+            instrument,
+            clock,
+            #[cfg(feature = "alloc")]
+            reset_if,
+            borrowed_at,
+        }
+    }
+    /// Sets what the guards do with the reset function when they are dropped during a panic.
+    /// The default is [`UnwindPolicy::Reset`].
+    ///
+    /// Without the `std` feature, the policy has no effect because a panic cannot be detected.
+    pub fn with_unwind_policy(mut self, policy: UnwindPolicy) -> Self {
+        self.unwind = policy;
+        self
+    }
+    /// Sets the label of the `tracing` events of this `Temp`, which are recorded with its type name.
+    #[cfg(feature = "tracing")]
+    pub fn with_label(mut self, label: &'static str) -> Self {
+        self.instrument.set_label(label);
+        self
+    }
+    /// Sets how long the value may go without a reset.
+    ///
+    /// Once it is older than `max_age`, the next guard which resets the value calls the reset function before it is returned.
+    /// This is only checked when such a guard is created, so no background thread is involved.
+    #[cfg(feature = "std")]
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.clock.set_max_age(max_age);
+        self
+    }
+    /// Sets a predicate deciding whether a dropped guard calls the reset function, so a guard which left the value clean
+    /// doesn't reset it. Explicit resets and rolled back transactions don't ask it.
+    ///
+    /// The predicate is a `Fn`, which the clones of the `Temp` share without a `RefCell`.
+    /// A predicate which keeps state has to keep it in a `Cell`. This method requires alloc.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let buffer = Temp::new(Vec::new(), |v: &mut Vec<u8>| *v = Vec::new());
+    /// let limit = 1024;
+    /// let buffer = buffer.with_reset_if(move |v| v.capacity() > limit);
+    /// buffer.borrow_mut().extend_from_slice(b"small");
+    /// assert_eq!(buffer.borrow().as_slice(), b"small"); // Kept, since it stayed small.
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn with_reset_if(mut self, reset_if: impl Fn(&T) -> bool + 'static) -> Self {
+        self.reset_if = Some(Rc::new(reset_if));
+        self
+    }
+    /// Sets the minimum interval between the resets of dropped guards.
+    ///
+    /// A guard dropped less than `interval` after the last reset leaves the value as it is, and the reset is postponed
+    /// until a guard which resets the value is created after the interval has passed.
+    /// So a guard may see what an earlier guard left. Explicit resets, rolled back transactions and guards dropped
+    /// during a panic always reset.
+    #[cfg(feature = "std")]
+    pub fn with_min_reset_interval(mut self, interval: Duration) -> Self {
+        self.clock.set_min_interval(interval);
+        self
+    }
+    /// Returns the minimum interval set with [`Self::with_min_reset_interval`].
+    #[cfg(feature = "std")]
+    pub fn min_reset_interval(&self) -> Option<Duration> {
+        self.clock.min_interval()
+    }
+    /// Returns the maximum age set with [`Self::with_max_age`].
+    #[cfg(feature = "std")]
+    pub fn max_age(&self) -> Option<Duration> {
+        self.clock.max_age()
+    }
+    /// Returns the counters of the guards of this `Temp`.
+    #[cfg(feature = "stats")]
+    pub fn stats(&self) -> Stats {
+        self.instrument.stats()
+    }
+    /// Sets the [`TempHooks`] called by the guards and the reset function.
+    #[cfg(feature = "hooks")]
+    pub fn with_hooks<H: TempHooks<T>>(mut self) -> Self {
+        self.hooks = Hooks::of::<H>();
+        self
+    }
+    /// Returns what the guards do with the reset function when they are dropped during a panic.
+    pub fn unwind_policy(&self) -> UnwindPolicy {
+        self.unwind
+    }
+    /// Returns how many times the reset function has completed on the value.
+    ///
+    /// A change of the generation tells that anything derived from the value may be stale.
+    pub fn generation(&self) -> usize {
+        self.generation.get()
+    }
+    /// Returns `true` if the reset function has panicked, which may have left the value half-reset.
+    ///
+    /// The flag stays set until [`Self::clear_reset_poison`] is called, even if later resets succeed.
+    pub fn is_reset_poisoned(&self) -> bool {
+        self.reset_poisoned.get()
+    }
+    /// Clears the flag set when the reset function panicked.
+    pub fn clear_reset_poison(&self) {
+        self.reset_poisoned.set(false);
+    }
     /// Immutably borrows the wrapped value.
     /// The borrow lasts until the returned Ref exits scope. Multiple immutable borrows can be taken out at the same time.
+    #[track_caller]
     pub fn borrow<'a>(&'a self) -> Ref<'a, T> {
-        self.value.borrow()
+        self.borrow_value()
     }
     /// Mutably borrows the wrapped value as `TempRef`.
     /// The value cannot be borrowed while this borrow is active.
     /// Automatically resets itself when dropped.
+    #[track_caller]
     pub fn borrow_mut<'a>(&'a self) -> TempRef<'a, T, F> {
         TempRef::new(self)
     }
+    /// Immutably borrows the wrapped value for the duration of `f`, returning its result.
+    /// Like [`Self::borrow`], the reset function is not called.
+    #[track_caller]
+    pub fn with<R, C: FnOnce(&T) -> R>(&self, f: C) -> R {
+        f(&self.borrow())
+    }
+    /// Returns a clone of the value, which is only borrowed while it is cloned.
+    /// The reset function is not called.
+    #[track_caller]
+    pub fn read_cloned(&self) -> Result<T, BorrowError>
+    where
+        T: Clone,
+    {
+        Ok(self.try_borrow()?.clone())
+    }
+    /// Mutably borrows the wrapped value for the duration of `f`, returning its result.
+    /// The reset function is called after `f` returns, so the borrow can never outlive the closure.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let workspace = Temp::new(Vec::new(), |v| v.clear());
+    ///
+    /// let sum: i32 = workspace.with_mut(|v| {
+    ///     v.extend([1, 2, 3]);
+    ///     v.iter().sum()
+    /// });
+    /// assert_eq!(sum, 6);
+    /// assert!(workspace.with(|v| v.is_empty()));
+    /// ```
+    #[track_caller]
+    pub fn with_mut<R, C: FnOnce(&mut T) -> R>(&self, f: C) -> R {
+        f(&mut self.borrow_mut())
+    }
+    /// Mutably borrows the wrapped value as a [`Transaction`].
+    /// The value cannot be borrowed while this borrow is active.
+    /// The changes are kept only if the transaction is committed.
+    #[track_caller]
+    pub fn transaction<'a>(&'a self) -> Transaction<'a, T, F> {
+        Transaction::new(self)
+    }
+    /// Mutably borrows the wrapped value through an `Rc` as [`OwnedTempRef`].
+    /// The value cannot be borrowed while this borrow is active.
+    /// Automatically resets itself when dropped.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::rc::Rc;
+    /// use tempref::unsync::Temp;
+    ///
+    /// let workspace = Rc::new(Temp::new(0, |n| *n = 0));
+    /// let mut callback = {
+    ///     let mut guard = workspace.borrow_mut_owned();
+    ///     move || {
+    ///         *guard += 1;
+    ///         *guard
+    ///     }
+    /// };
+    /// assert_eq!(callback(), 1);
+    /// assert_eq!(callback(), 2);
+    /// drop(callback);
+    /// assert_eq!(*workspace.borrow(), 0);
+    /// ```
+    #[cfg(feature = "alloc")]
+    #[track_caller]
+    pub fn borrow_mut_owned(self: &Rc<Self>) -> OwnedTempRef<T, F>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        match OwnedTempRef::try_new(self) {
+            Ok(guard) => guard,
+            Err(err) => self.borrowed_at.conflict(err),
+        }
+    }
+    /// A safer function; `self.borrow_mut_owned()`.
+    #[cfg(feature = "alloc")]
+    #[track_caller]
+    pub fn try_borrow_mut_owned(self: &Rc<Self>) -> Result<OwnedTempRef<T, F>, BorrowMutError>
+    where
+        T: 'static,
+        F: 'static,
+    {
+        OwnedTempRef::try_new(self)
+    }
     /// A safer function; `self.borrow()`.
+    #[track_caller]
     pub fn try_borrow<'a>(&'a self) -> Result<Ref<'a, T>, BorrowError> {
-        self.value.try_borrow()
+        let re = self.value.try_borrow()?;
+        self.borrowed_at.record();
+        Ok(re)
     }
     /// A safer function; `self.borrow_mut()`.
+    #[track_caller]
     pub fn try_borrow_mut<'a>(&'a self) -> Result<TempRef<'a, T, F>, BorrowMutError> {
         TempRef::try_new(self)
     }
@@ -120,6 +814,28 @@ impl<T, F: FnMut(&mut T)> Temp<T, F> {
     pub fn into_inner(self) -> T {
         self.value.into_inner()
     }
+    /// Consumes the Temp, returning the wrapped value and the reset function.
+    pub fn into_parts(self) -> (T, F) {
+        (self.value.into_inner(), self.reset.into_inner())
+    }
+    /// Returns a mutable reference to the wrapped value.
+    ///
+    /// Since this call borrows the `Temp` mutably, no runtime borrow checking is needed.
+    /// Like [`Self::with`], the reset function is not called.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.value.get_mut()
+    }
+    /// Calls `f` with a mutable reference to the wrapped value, then calls the reset function like [`Self::with_mut`].
+    ///
+    /// Since this call borrows the `Temp` mutably, the borrow can never fail.
+    ///
+    /// This is also how a `Temp` kept in a cell of `qcell` is used, so there is no adapter for its owners:
+    /// `QCellOwner::rw()` and the like already hand out `&mut Temp`, and this method resets the value through it
+    /// without a borrow flag which could panic.
+    #[track_caller]
+    pub fn get_mut_reset<R, C: FnOnce(&mut T) -> R>(&mut self, f: C) -> R {
+        self.with_mut(f)
+    }
     /// Replaces the wrapped value with a new one, returning the old value, without deinitializing either one.
     pub fn replace(&self, value: T) -> T {
         self.value.replace(value)
@@ -128,19 +844,361 @@ impl<T, F: FnMut(&mut T)> Temp<T, F> {
     pub fn replace_with<C: FnOnce(&mut T) -> T>(&self, f: C) -> T {
         self.value.replace_with(f)
     }
+    /// Takes the wrapped value, leaving `Default::default()` in its place.
+    ///
+    /// The reset function is not called, so the default value is stored as it is.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let log = Temp::new(Vec::new(), |v| v.clear());
+    /// log.with_mut(|v| v.push(1));
+    /// log.transaction().push(2);
+    /// assert_eq!(log.take(), Vec::<i32>::new());
+    ///
+    /// let mut tx = log.transaction();
+    /// tx.extend([1, 2]);
+    /// tx.commit();
+    /// assert_eq!(log.take(), vec![1, 2]);
+    /// assert!(log.borrow().is_empty());
+    /// ```
+    pub fn take(&self) -> T
+    where
+        T: Default,
+    {
+        self.value.take()
+    }
     /// Swaps the wrapped value of self with the wrapped value of other, without deinitializing either one.
     pub fn swap(&self, other: &RefCell<T>) {
         self.value.swap(other);
     }
     /// Invokes the reset function on the internal value.
+    #[track_caller]
     pub fn reset(&self) {
-        unsafe { (*self.reset.get())(&mut self.value.borrow_mut()) }
+        self.reset_or_poison(&mut self.borrow_value_mut());
     }
     /// A safer function; self.reset().
+    #[track_caller]
     pub fn try_reset(&self) -> Result<(), BorrowMutError> {
-        unsafe { (*self.reset.get())(&mut *self.value.try_borrow_mut()?) }
+        self.reset_or_poison(&mut *self.try_borrow_value_mut()?);
         Ok(())
     }
+
+    #[track_caller]
+    fn borrow_value(&self) -> Ref<'_, T> {
+        match self.value.try_borrow() {
+            Ok(re) => {
+                self.borrowed_at.record();
+                re
+            }
+            Err(err) => self.borrowed_at.conflict(err),
+        }
+    }
+    #[track_caller]
+    fn borrow_value_mut(&self) -> RefMut<'_, T> {
+        match self.try_borrow_value_mut() {
+            Ok(re) => re,
+            Err(err) => self.borrowed_at.conflict(err),
+        }
+    }
+    #[track_caller]
+    fn try_borrow_value_mut(&self) -> Result<RefMut<'_, T>, BorrowMutError> {
+        let re = self.value.try_borrow_mut()?;
+        self.borrowed_at.record();
+        Ok(re)
+    }
+    #[allow(clippy::mut_from_ref)]
+    fn get_reset(&self) -> &mut F {
+        unsafe { &mut *self.reset.get() }
+    }
+    // Resets the value first if it is older than the maximum age, or a postponed reset is due.
+    fn expire(&self, value: &mut T) {
+        if self.clock.due() {
+            self.reset_or_poison(value);
+        }
+    }
+    // The reset function followed by the `on_reset` hook, inside the span of the reset.
+    fn hooked_reset(&self) -> impl FnMut(&mut T) + '_ {
+        let reset = self.get_reset();
+        move |value: &mut T| {
+            let _span = self.instrument.enter_reset::<T>();
+            reset(value);
+            self.generation.set(self.generation.get() + 1);
+            self.clock.reset();
+            self.hooks.reset(value);
+        }
+    }
+    fn reset_or_poison(&self, value: &mut T) {
+        unwind::reset_or_poison(value, &mut self.hooked_reset(), || {
+            self.reset_poisoned.set(true)
+        });
+    }
+    // Called when a guard is dropped. The reset is skipped if `reset_if` returns false,
+    // and postponed if the last one ran less than the minimum interval ago.
+    fn release(&self, value: &mut T) {
+        if !self.should_reset(value) || self.clock.postpone() {
+            self.hooks.release(value);
+            self.instrument.release::<T>();
+        } else {
+            self.reset_on_drop(value);
+        }
+    }
+    // Asks the `with_reset_if` predicate, if there is one.
+    fn should_reset(&self, value: &T) -> bool {
+        #[cfg(feature = "alloc")]
+        if let Some(reset_if) = &self.reset_if {
+            return reset_if(value);
+        }
+        let _ = value;
+        true
+    }
+    fn reset_on_drop(&self, value: &mut T) {
+        self.hooks.release(value);
+        self.instrument.release::<T>();
+        unwind::reset_on_drop(self.unwind, value, &mut self.hooked_reset(), || {
+            self.reset_poisoned.set(true)
+        });
+    }
+}
+// The location of the latest borrow of a `Temp`, which is only recorded with the `debug_borrow` feature.
+struct BorrowSite {
+    #[cfg(feature = "debug_borrow")]
+    location: Cell<Option<&'static core::panic::Location<'static>>>,
+}
+impl BorrowSite {
+    const fn new() -> Self {
+        BorrowSite {
+            #[cfg(feature = "debug_borrow")]
+            location: Cell::new(None),
+        }
+    }
+    #[track_caller]
+    fn record(&self) {
+        #[cfg(feature = "debug_borrow")]
+        self.location.set(Some(core::panic::Location::caller()));
+    }
+    #[track_caller]
+    fn conflict(&self, err: impl Display) -> ! {
+        #[cfg(feature = "debug_borrow")]
+        if let Some(location) = self.location.get() {
+            panic!("{err}: the latest borrow was taken at {location}");
+        }
+        panic!("{err}")
+    }
+}
+/// A [`Temp`] whose reset function is a function pointer, which is the default of the `F` parameter.
+///
+/// Non-capturing closures coerce to function pointers, so the type can be spelled out in a `static`.
+///
+/// # Examples
+/// ```
+/// use tempref::unsync::{Temp, TempFn};
+///
+/// thread_local! {
+///     static SCRATCH: TempFn<[u8; 512]> = const { Temp::new([0; 512], |b| b.fill(0)) };
+/// }
+///
+/// SCRATCH.with(|scratch| scratch.borrow_mut()[0] = 1);
+/// SCRATCH.with(|scratch| assert_eq!(scratch.borrow()[0], 0));
+/// ```
+pub type TempFn<T> = Temp<T, fn(&mut T)>;
+
+/// A [`Temp`] whose reset function is boxed, so that the type can be named without a closure type parameter.
+///
+/// # Examples
+/// ```
+/// use tempref::unsync::BoxedTemp;
+///
+/// struct Worker {
+///     scratch: BoxedTemp<Vec<u8>>,
+/// }
+///
+/// let worker = Worker {
+///     scratch: BoxedTemp::new_boxed(Vec::new(), |v| v.clear()),
+/// };
+/// # let _ = worker.scratch;
+/// ```
+#[cfg(feature = "alloc")]
+pub type BoxedTemp<T> = Temp<T, Box<dyn FnMut(&mut T)>>;
+#[cfg(feature = "alloc")]
+impl<T> Temp<T, Box<dyn FnMut(&mut T)>> {
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    pub fn new_boxed<F: FnMut(&mut T) + 'static>(value: T, reset: F) -> Self {
+        Temp::new(value, Box::new(reset))
+    }
+    /// A constructor of [`BoxedTemp<T>`], which boxes the given `reset` function.
+    ///
+    /// Unlike [`Self::new_boxed`], this constructor immediately applies the given `reset`
+    /// function to the initial `value` before storing it.
+    pub fn new_boxed_with<F: FnMut(&mut T) + 'static>(value: T, reset: F) -> Self {
+        Temp::new_with(value, Box::new(reset))
+    }
+}
+#[cfg(feature = "alloc")]
+impl<T> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp whose reset function is shared through an `Rc`, e.g. with other `Temp`s.
+    ///
+    /// The returned `Temp` can be cloned, and every clone calls the same `reset`.
+    ///
+    /// # Examples
+    /// ```
+    /// use std::cell::Cell;
+    /// use std::rc::Rc;
+    /// use tempref::unsync::Temp;
+    ///
+    /// let resets = Rc::new(Cell::new(0));
+    /// let counted = Rc::clone(&resets);
+    /// let a = Temp::new_shared(0, Rc::new(move |n: &mut i32| {
+    ///     *n = 0;
+    ///     counted.set(counted.get() + 1);
+    /// }));
+    /// let b = a.clone();
+    /// *a.borrow_mut() = 1;
+    /// *b.borrow_mut() = 2;
+    /// assert_eq!(resets.get(), 2);
+    /// ```
+    pub fn new_shared<R: Fn(&mut T) + ?Sized>(
+        value: T,
+        reset: Rc<R>,
+    ) -> Temp<T, impl FnMut(&mut T) + Clone> {
+        Temp::new(value, move |value: &mut T| (*reset)(value))
+    }
+}
+/// A mutable reference wrapper from [`PinnedTemp<T, F>`], which never exposes `&mut T` unless `T: Unpin`.
+///
+/// When dropped, it calls the reset function with the pinned value.
+pub struct PinnedTempRef<'a, T, F: FnMut(Pin<&mut T>)> {
+    re: RefMut<'a, T>,
+    reset: &'a RefCell<F>,
+}
+impl<'a, T, F: FnMut(Pin<&mut T>)> PinnedTempRef<'a, T, F> {
+    /// Returns the pinned value.
+    pub fn as_mut(&mut self) -> Pin<&mut T> {
+        // SAFETY: The value lives in a pinned `PinnedTemp`, which never moves it.
+        unsafe { Pin::new_unchecked(&mut *self.re) }
+    }
+    /// Invokes the reset function on the pinned value.
+    pub fn reset(&mut self) {
+        // SAFETY: The value lives in a pinned `PinnedTemp`, which never moves it.
+        let value = unsafe { Pin::new_unchecked(&mut *self.re) };
+        (self.reset.borrow_mut())(value);
+    }
+}
+impl<'a, T, F: FnMut(Pin<&mut T>)> core::ops::Deref for PinnedTempRef<'a, T, F> {
+    type Target = T;
+    fn deref(&self) -> &Self::Target {
+        &self.re
+    }
+}
+impl<'a, T: Unpin, F: FnMut(Pin<&mut T>)> core::ops::DerefMut for PinnedTempRef<'a, T, F> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.re
+    }
+}
+impl<'a, T: Debug, F: FnMut(Pin<&mut T>)> Debug for PinnedTempRef<'a, T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinnedTempRef")
+            .field("value", &*self.re)
+            .finish()
+    }
+}
+impl<'a, T, F: FnMut(Pin<&mut T>)> Drop for PinnedTempRef<'a, T, F> {
+    fn drop(&mut self) {
+        self.reset();
+    }
+}
+
+/// A `Temp` for `!Unpin` values such as self-referential states, whose guards yield `Pin<&mut T>`.
+///
+/// Once pinned, the value is never moved by this crate: there is no `replace`, `take` or `swap`,
+/// the reset function receives `Pin<&mut T>`, and the value is dropped in place with the `PinnedTemp`.
+/// Guards can only be taken through `Pin<&Self>`, from [`Self::pin`] or [`core::pin::pin!`].
+///
+/// # Examples
+/// ```
+/// use std::marker::PhantomPinned;
+/// use std::pin::Pin;
+/// use tempref::unsync::PinnedTemp;
+///
+/// struct State {
+///     depth: usize,
+///     _pinned: PhantomPinned,
+/// }
+/// impl State {
+///     fn depth(self: Pin<&mut Self>) -> &mut usize {
+///         // SAFETY: `depth` is not structurally pinned.
+///         unsafe { &mut self.get_unchecked_mut().depth }
+///     }
+/// }
+///
+/// let state = PinnedTemp::pin(
+///     State { depth: 0, _pinned: PhantomPinned },
+///     |state: Pin<&mut State>| *state.depth() = 0,
+/// );
+/// {
+///     let mut guard = state.as_ref().borrow_mut();
+///     *guard.as_mut().depth() += 2;
+///     assert_eq!(guard.depth, 2);
+/// }
+/// assert_eq!(state.borrow().depth, 0);
+/// ```
+pub struct PinnedTemp<T, F: FnMut(Pin<&mut T>) = fn(Pin<&mut T>)> {
+    value: RefCell<T>,
+    reset: RefCell<F>,
+}
+impl<T, F: FnMut(Pin<&mut T>)> PinnedTemp<T, F> {
+    /// A constructor of PinnedTemp<T, F>, which has to be pinned before it is borrowed mutably.
+    pub const fn new(value: T, reset: F) -> Self {
+        PinnedTemp {
+            value: RefCell::new(value),
+            reset: RefCell::new(reset),
+        }
+    }
+    /// A constructor of PinnedTemp<T, F>, pinned in a `Box`.
+    #[cfg(feature = "alloc")]
+    pub fn pin(value: T, reset: F) -> Pin<Box<Self>> {
+        Box::pin(PinnedTemp::new(value, reset))
+    }
+    /// Immutably borrows the value.
+    ///
+    /// # Panics
+    /// Panics if the value is currently mutably borrowed.
+    #[track_caller]
+    pub fn borrow(&self) -> Ref<'_, T> {
+        self.value.borrow()
+    }
+    /// Mutably borrows the pinned value. The returned [`PinnedTempRef`] calls the reset function when dropped.
+    ///
+    /// # Panics
+    /// Panics if the value is currently borrowed.
+    #[track_caller]
+    pub fn borrow_mut(self: Pin<&Self>) -> PinnedTempRef<'_, T, F> {
+        let this = self.get_ref();
+        PinnedTempRef {
+            re: this.value.borrow_mut(),
+            reset: &this.reset,
+        }
+    }
+    /// Mutably borrows the pinned value, or returns an error if it is currently borrowed.
+    pub fn try_borrow_mut(self: Pin<&Self>) -> Result<PinnedTempRef<'_, T, F>, BorrowMutError> {
+        let this = self.get_ref();
+        Ok(PinnedTempRef {
+            re: this.value.try_borrow_mut()?,
+            reset: &this.reset,
+        })
+    }
+    /// Consumes the PinnedTemp, returning the value. Only possible while it has not been pinned, or if `T: Unpin`.
+    pub fn into_inner(self) -> T {
+        self.value.into_inner()
+    }
+}
+impl<T: Debug, F: FnMut(Pin<&mut T>)> Debug for PinnedTemp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PinnedTemp")
+            .field("value", &self.value)
+            .finish()
+    }
 }
 impl<T: Default, F: FnMut(&mut T)> Temp<T, F> {
     /// Creates a new `Temp<T, F>` using `T::default()` as the initial value.
@@ -148,6 +1206,15 @@ impl<T: Default, F: FnMut(&mut T)> Temp<T, F> {
         Temp {
             value: RefCell::new(T::default()),
             reset: UnsafeCell::new(reset),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: Cell::new(false),
+            generation: Cell::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            #[cfg(feature = "alloc")]
+            reset_if: None,
+            borrowed_at: BorrowSite::new(),
         }
     }
     /// Creates a new `Temp<T, F>` using `T::default()` as the initial value,
@@ -161,6 +1228,153 @@ impl<T: Default, F: FnMut(&mut T)> Temp<T, F> {
         Temp {
             value: RefCell::new(default),
             reset: UnsafeCell::new(reset),
+            unwind: UnwindPolicy::Reset,
+            reset_poisoned: Cell::new(false),
+            generation: Cell::new(0),
+            hooks: Hooks::new(),
+            instrument: Instrument::new(),
+            clock: ResetClock::new(),
+            #[cfg(feature = "alloc")]
+            reset_if: None,
+            borrowed_at: BorrowSite::new(),
+        }
+    }
+}
+/// Creates a `Temp` holding `T::default()`, whose reset function sets the value back to `T::default()`.
+///
+/// Closures and function pointers do not implement `Default`, so this is implemented for [`TempFn<T>`].
+///
+/// # Examples
+/// ```
+/// use tempref::unsync::TempFn;
+///
+/// #[derive(Default)]
+/// struct Parser {
+///     stack: TempFn<Vec<u32>>,
+/// }
+///
+/// let parser = Parser::default();
+/// parser.stack.borrow_mut().push(1);
+/// assert!(parser.stack.borrow().is_empty());
+/// ```
+impl<T: Default> Default for Temp<T, fn(&mut T)> {
+    fn default() -> Self {
+        Temp::new_default(crate::reset_to_default::<T>)
+    }
+}
+/// Creates a `Temp` whose reset function sets the value to `T::default()`.
+impl<T: Default> From<T> for Temp<T, fn(&mut T)> {
+    fn from(value: T) -> Self {
+        Temp::new(value, crate::reset_to_default::<T>)
+    }
+}
+impl<T: AsMut<[u8]>> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a byte buffer such as `Vec<u8>`, `Box<[u8]>` or `[u8; N]`,
+    /// whose reset function zeroes the bytes in place with a single `memset`, keeping the length.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let buffer = Temp::zeroing(vec![0u8; 4096]);
+    /// buffer.borrow_mut()[..5].copy_from_slice(b"hello");
+    /// assert!(buffer.borrow().iter().all(|&b| b == 0));
+    /// ```
+    pub fn zeroing(value: T) -> Self {
+        Temp::new(value, crate::zero_bytes::<T>)
+    }
+}
+#[cfg(any(feature = "alloc", feature = "heapless"))]
+impl<T: crate::Clear> Temp<T, fn(&mut T)> {
+    /// A constructor of a Temp for a collection implementing [`Clear`](crate::Clear), such as `heapless::Vec`,
+    /// whose reset function empties it in place, keeping its capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// use tempref::unsync::Temp;
+    ///
+    /// let workspace = Temp::clearing(Vec::with_capacity(64));
+    /// workspace.borrow_mut().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.borrow().is_empty());
+    /// ```
+    pub fn clearing(value: T) -> Self {
+        Temp::new(value, T::clear)
+    }
+}
+#[cfg(feature = "allocator_api")]
+impl<T, A: core::alloc::Allocator> Temp<alloc::vec::Vec<T, A>, fn(&mut alloc::vec::Vec<T, A>)> {
+    /// A constructor of an empty `Vec` workspace in `alloc`, whose reset function clears it and keeps the capacity.
+    ///
+    /// # Examples
+    /// ```
+    /// #![feature(allocator_api)]
+    /// use std::alloc::System;
+    /// use tempref::unsync::Temp;
+    ///
+    /// let workspace = Temp::new_in(System);
+    /// workspace.borrow_mut().extend_from_slice(&[1, 2, 3]);
+    /// assert!(workspace.borrow().is_empty());
+    /// ```
+    pub fn new_in(alloc: A) -> Self {
+        Temp::new(alloc::vec::Vec::new_in(alloc), alloc::vec::Vec::clear)
+    }
+    /// Like [`Self::new_in`], but allocates room for `capacity` elements up front.
+    pub fn with_capacity_in(capacity: usize, alloc: A) -> Self {
+        Temp::new(
+            alloc::vec::Vec::with_capacity_in(capacity, alloc),
+            alloc::vec::Vec::clear,
+        )
+    }
+}
+/// Clones the value and the reset function, keeping the [`UnwindPolicy`] and the reset-poisoned flag.
+///
+/// # Panics
+/// Panics if the value is currently mutably borrowed.
+impl<T: Clone, F: FnMut(&mut T) + Clone> Clone for Temp<T, F> {
+    fn clone(&self) -> Self {
+        let value = self.borrow();
+        // SAFETY: the reset function is only called while the value is mutably borrowed, which `value` rules out.
+        let reset = unsafe { &*self.reset.get() }.clone();
+        Temp {
+            value: RefCell::new(value.clone()),
+            reset: UnsafeCell::new(reset),
+            unwind: self.unwind,
+            reset_poisoned: Cell::new(self.reset_poisoned.get()),
+            generation: Cell::new(self.generation.get()),
+            hooks: self.hooks.clone(),
+            instrument: self.instrument.clone(),
+            clock: self.clock.clone(),
+            #[cfg(feature = "alloc")]
+            reset_if: self.reset_if.clone(),
+            borrowed_at: BorrowSite::new(),
+        }
+    }
+}
+/// Compares the values of two `Temp`s, whose reset functions may differ.
+///
+/// # Panics
+/// Panics if either value is currently mutably borrowed.
+impl<T: PartialEq, F: FnMut(&mut T), G: FnMut(&mut T)> PartialEq<Temp<T, G>> for Temp<T, F> {
+    fn eq(&self, other: &Temp<T, G>) -> bool {
+        *self.borrow() == *other.borrow()
+    }
+}
+impl<T: Eq, F: FnMut(&mut T)> Eq for Temp<T, F> {}
+/// Compares the value of a `Temp` with a plain value.
+///
+/// # Panics
+/// Panics if the value is currently mutably borrowed.
+impl<T: PartialEq, F: FnMut(&mut T)> PartialEq<T> for Temp<T, F> {
+    fn eq(&self, other: &T) -> bool {
+        *self.borrow() == *other
+    }
+}
+/// Displays the value, or `<borrowed>` while it is mutably borrowed.
+impl<T: Display, F: FnMut(&mut T)> Display for Temp<T, F> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self.value.try_borrow() {
+            Ok(value) => Display::fmt(&*value, f),
+            Err(_) => f.write_str("<borrowed>"),
         }
     }
 }
@@ -169,3 +1383,48 @@ impl<T: Debug, F: FnMut(&mut T)> Debug for Temp<T, F> {
         f.debug_struct("Temp").field("value", &self.value).finish()
     }
 }
+
+/// Serializes the value alone, as if the `Temp` were not there.
+///
+/// Fails while the value is mutably borrowed.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize, F: FnMut(&mut T)> serde::Serialize for Temp<T, F> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.value.try_borrow() {
+            Ok(value) => value.serialize(serializer),
+            Err(_) => Err(serde::ser::Error::custom("already mutably borrowed")),
+        }
+    }
+}
+/// Deserializes the value into a `Temp` whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de> + Default> serde::Deserialize<'de> for Temp<T, fn(&mut T)> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Temp::from)
+    }
+}
+#[cfg(feature = "serde")]
+impl<T, F: FnMut(&mut T)> Temp<T, F> {
+    /// Deserializes the value and wraps it in a `Temp` with the given `reset` function.
+    pub fn deserialize_with<'de, D: serde::Deserializer<'de>>(
+        deserializer: D,
+        reset: F,
+    ) -> Result<Self, D::Error>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        T::deserialize(deserializer).map(|value| Temp::new(value, reset))
+    }
+}
+/// Generates a `Temp` holding an arbitrary value, whose reset function sets it back to `T::default()`, like [`From<T>`].
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + Default> proptest::arbitrary::Arbitrary
+    for Temp<T, fn(&mut T)>
+{
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        proptest::strategy::Strategy::prop_map(T::arbitrary_with(args), Temp::from)
+    }
+}
@@ -0,0 +1,83 @@
+//! What guards do when they are dropped during a panic.
+
+/// What a guard does with the reset function when it is dropped while the current thread is panicking.
+///
+/// A panic in the reset function during unwinding would abort the process.
+/// With [`UnwindPolicy::Reset`] such a panic is caught instead, and with [`UnwindPolicy::Skip`] the reset function is not called at all.
+/// The locks of `mutex` and `rwlock` are poisoned by the unwinding either way,
+/// so the value can still be recovered with `lock_unpoisoned()` or `write_unpoisoned()`.
+///
+/// Detecting a panic requires the `std` feature, which is enabled by `mutex` and `rwlock`.
+/// Without it, the guards of `unsync` always call the reset function.
+///
+/// # Examples
+/// ```
+/// use tempref::{UnwindPolicy, mutex::Temp};
+///
+/// let workspace = Temp::new(0, |n| *n = 0).with_unwind_policy(UnwindPolicy::Skip);
+/// std::thread::scope(|s| {
+///     let result = s.spawn(|| {
+///         let mut guard = workspace.lock().unwrap();
+///         *guard = 1;
+///         panic!("leaves the value as it is");
+///     });
+///     assert!(result.join().is_err());
+/// });
+/// assert_eq!(*workspace.lock().unwrap_err().into_inner(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnwindPolicy {
+    /// Calls the reset function. If it panics as well, the second panic is caught and the value is left as it is.
+    #[default]
+    Reset,
+    /// Does not call the reset function, leaving the value as the panicking code left it.
+    Skip,
+}
+
+/// Calls `reset` on `value`, calling `poison` if it panics.
+pub(crate) fn reset_or_poison<T: ?Sized, F: FnMut(&mut T), P: FnOnce()>(
+    value: &mut T,
+    reset: &mut F,
+    poison: P,
+) {
+    struct Bomb<P: FnOnce()>(Option<P>);
+    impl<P: FnOnce()> Drop for Bomb<P> {
+        fn drop(&mut self) {
+            if let Some(poison) = self.0.take() {
+                poison();
+            }
+        }
+    }
+
+    let mut bomb = Bomb(Some(poison));
+    reset(value);
+    bomb.0 = None;
+}
+
+/// Calls `reset` on `value` from the `Drop` of a guard, following `policy` if the current thread is panicking.
+#[cfg(feature = "std")]
+pub(crate) fn reset_on_drop<T: ?Sized, F: FnMut(&mut T), P: FnOnce()>(
+    policy: UnwindPolicy,
+    value: &mut T,
+    reset: &mut F,
+    poison: P,
+) {
+    extern crate std;
+    use std::panic::{AssertUnwindSafe, catch_unwind};
+
+    if !std::thread::panicking() {
+        reset_or_poison(value, reset, poison);
+    } else if policy == UnwindPolicy::Reset {
+        let _ = catch_unwind(AssertUnwindSafe(|| reset_or_poison(value, reset, poison)));
+    }
+}
+/// Calls `reset` on `value` from the `Drop` of a guard.
+#[cfg(not(feature = "std"))]
+pub(crate) fn reset_on_drop<T: ?Sized, F: FnMut(&mut T), P: FnOnce()>(
+    _: UnwindPolicy,
+    value: &mut T,
+    reset: &mut F,
+    poison: P,
+) {
+    reset_or_poison(value, reset, poison);
+}
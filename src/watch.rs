@@ -0,0 +1,121 @@
+//! Snapshots of a `Temp` for observers which don't take its lock. This module requires std.
+//!
+//! [`mutex::Temp::watch`](crate::mutex::Temp::watch) and [`rwlock::Temp::watch`](crate::rwlock::Temp::watch)
+//! return a [`Watcher`] which receives a [`Snapshot`] every time the reset function runs.
+//! Like a watch channel, only the latest snapshot is kept.
+
+extern crate std;
+
+use core::time::Duration;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+
+/// The value of a `Temp` just before and just after its reset function ran.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot<T> {
+    /// The value as the guard left it.
+    pub dirty: T,
+    /// The value after the reset function.
+    pub reset: T,
+}
+
+struct Shared<T> {
+    latest: Mutex<(u64, Option<Arc<Snapshot<T>>>)>,
+    changed: Condvar,
+}
+
+// Publishes snapshots from the reset function of a watched `Temp`.
+pub(crate) struct Publisher<T> {
+    shared: Arc<Shared<T>>,
+}
+impl<T> Publisher<T> {
+    pub(crate) fn publish(&self, dirty: T, reset: T) {
+        let mut latest = self
+            .shared
+            .latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        *latest = (latest.0 + 1, Some(Arc::new(Snapshot { dirty, reset })));
+        self.shared.changed.notify_all();
+    }
+}
+
+pub(crate) fn channel<T>() -> (Publisher<T>, Watcher<T>) {
+    let shared = Arc::new(Shared {
+        latest: Mutex::new((0, None)),
+        changed: Condvar::new(),
+    });
+    (
+        Publisher {
+            shared: shared.clone(),
+        },
+        Watcher { shared, seen: 0 },
+    )
+}
+
+/// The receiving side of a watched `Temp`, which can be cloned for every observer.
+pub struct Watcher<T> {
+    shared: Arc<Shared<T>>,
+    seen: u64,
+}
+impl<T> Watcher<T> {
+    /// Returns the latest snapshot, or `None` if the reset function has not run yet.
+    /// It is not marked as seen.
+    pub fn latest(&self) -> Option<Arc<Snapshot<T>>> {
+        self.lock().1.clone()
+    }
+    /// Returns `true` if a snapshot newer than the last one returned by [`Self::changed`] is available.
+    pub fn has_changed(&self) -> bool {
+        self.lock().0 != self.seen
+    }
+    /// Blocks the current thread until a snapshot newer than the last one seen is available, and returns it.
+    pub fn changed(&mut self) -> Arc<Snapshot<T>> {
+        let seen = self.seen;
+        let latest = self
+            .shared
+            .changed
+            .wait_while(self.lock(), |latest| latest.0 == seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        let (version, snapshot) = (latest.0, latest.1.clone());
+        drop(latest);
+        self.seen = version;
+        snapshot.unwrap()
+    }
+    /// Like [`Self::changed`], but returns `None` if no new snapshot is available within `timeout`.
+    pub fn changed_timeout(&mut self, timeout: Duration) -> Option<Arc<Snapshot<T>>> {
+        let seen = self.seen;
+        let (latest, _) = self
+            .shared
+            .changed
+            .wait_timeout_while(self.lock(), timeout, |latest| latest.0 == seen)
+            .unwrap_or_else(PoisonError::into_inner);
+        let (version, snapshot) = (latest.0, latest.1.clone());
+        drop(latest);
+        if version == seen {
+            return None;
+        }
+        self.seen = version;
+        snapshot
+    }
+
+    fn lock(&self) -> MutexGuard<'_, (u64, Option<Arc<Snapshot<T>>>)> {
+        self.shared
+            .latest
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+impl<T> Clone for Watcher<T> {
+    fn clone(&self) -> Self {
+        Watcher {
+            shared: self.shared.clone(),
+            seen: self.seen,
+        }
+    }
+}
+impl<T: core::fmt::Debug> core::fmt::Debug for Watcher<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Watcher")
+            .field("latest", &self.latest())
+            .finish()
+    }
+}
@@ -0,0 +1,140 @@
+//! Derive macros for [`tempref`](https://docs.rs/tempref). Use them through the `derive` feature of `tempref`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Expr, Fields, parse_macro_input};
+
+/// Implements `tempref::TempProject` for a struct with named fields, so that `rwlock::Temp::project_fields()`
+/// splits it into a `<Name>Fields` struct with a `rwlock::TempField` for every field.
+///
+/// `<Name>Fields` has an accessor for every field, such as `fields.ids()`, which locks the field and returns a guard
+/// that only resets that field when dropped.
+///
+/// A field is reset to `Default::default()`, unless it has `#[temp(reset = ...)]` with a function or a closure without
+/// captures, such as `#[temp(reset = Vec::clear)]`. A field with `#[temp(skip)]` is left out.
+#[proc_macro_derive(TempProject, attributes(temp))]
+pub fn derive_temp_project(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+struct Field {
+    ident: syn::Ident,
+    vis: syn::Visibility,
+    ty: syn::Type,
+    reset: Option<Expr>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "TempProject does not support generic structs",
+        ));
+    }
+    let named = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(named) => &named.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    &input.ident,
+                    "TempProject requires a struct with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                &input.ident,
+                "TempProject can only be derived for structs",
+            ));
+        }
+    };
+
+    let mut fields = Vec::new();
+    for field in named {
+        let mut reset = None;
+        let mut skip = false;
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("temp"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("reset") {
+                    reset = Some(meta.value()?.parse::<Expr>()?);
+                    Ok(())
+                } else if meta.path.is_ident("skip") {
+                    skip = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `reset = ...` or `skip`"))
+                }
+            })?;
+        }
+        if !skip {
+            fields.push(Field {
+                ident: field.ident.clone().expect("named field"),
+                vis: field.vis.clone(),
+                ty: field.ty.clone(),
+                reset,
+            });
+        }
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let fields_name = format_ident!("{}Fields", name);
+    let doc = format!(
+        "The fields of a [`{name}`], each with its own lock and reset function, made by `rwlock::Temp::project_fields()`."
+    );
+    let decls = fields.iter().map(|Field { ident, vis, ty, .. }| {
+        quote! { #vis #ident: ::tempref::rwlock::TempField<'a, #ty> }
+    });
+    let accessors = fields.iter().map(|Field { ident, vis, ty, .. }| {
+        let doc =
+            format!("Locks `{ident}` for writing, returning a guard which resets it when dropped.");
+        quote! {
+            #[doc = #doc]
+            #vis fn #ident(&self) -> ::tempref::rwlock::FieldRef<'_, 'a, #ty> {
+                self.#ident.write_unpoisoned()
+            }
+        }
+    });
+    let inits = fields.iter().map(
+        |Field {
+             ident, ty, reset, ..
+         }| {
+            let reset = match reset {
+                Some(reset) => quote! { #reset },
+                None => quote! { |value: &mut #ty| *value = ::core::default::Default::default() },
+            };
+            quote! {
+                #ident: ::tempref::rwlock::TempField::new(&mut value.#ident, {
+                    let reset: fn(&mut #ty) = #reset;
+                    reset
+                })
+            }
+        },
+    );
+
+    Ok(quote! {
+        #[doc = #doc]
+        #vis struct #fields_name<'a> {
+            #(#decls,)*
+        }
+        impl<'a> #fields_name<'a> {
+            #(#accessors)*
+        }
+        impl ::tempref::TempProject for #name {
+            type Fields<'a> = #fields_name<'a>;
+            fn project(value: &mut Self) -> #fields_name<'_> {
+                #fields_name {
+                    #(#inits,)*
+                }
+            }
+        }
+    })
+}
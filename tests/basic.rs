@@ -2,6 +2,7 @@ use tempref::*;
 
 #[cfg(test)]
 mod tests {
+    #[allow(clippy::useless_attribute, clippy::legacy_numeric_constants)]
     use std::i32;
 
     use super::*;
@@ -125,4 +126,1639 @@ mod tests {
         let default: mutex::Temp<i32, _> = mutex::Temp::new_default_with(|n| *n += 1);
         assert_eq!(1, *default.lock().unwrap());
     }
+
+    #[test]
+    fn transaction() {
+        let workspace = unsync::Temp::new(vec![0; 4], |v| v.fill(0));
+        {
+            let mut tx = workspace.transaction();
+            tx.fill(1);
+            tx.commit();
+        }
+        assert_eq!(*workspace.borrow(), vec![1; 4]);
+        {
+            let mut tx = workspace.transaction();
+            tx.fill(2);
+            tx.rollback();
+        }
+        assert_eq!(*workspace.borrow(), vec![0; 4]);
+
+        let workspace = std::sync::Arc::new(mutex::Temp::new(0, |n| *n = 0));
+        let shared = workspace.clone();
+        let _ = std::thread::spawn(move || {
+            let mut tx = shared.transaction().unwrap();
+            *tx = 1;
+            panic!("rolled back");
+        })
+        .join();
+        assert_eq!(*workspace.lock().unwrap_err().into_inner(), 0);
+
+        let workspace = rwlock::Temp::new(0, |n| *n = 0);
+        {
+            let mut tx = workspace.transaction().unwrap();
+            *tx = 1;
+            tx.commit();
+        }
+        assert_eq!(*workspace.read().unwrap(), 1);
+    }
+
+    #[test]
+    fn map() {
+        let workspace = unsync::Temp::new((vec![0; 4], 0), |(v, n)| {
+            v.fill(0);
+            *n = 0;
+        });
+        {
+            let (mut v, mut n) =
+                unsync::TempRef::map_split(workspace.borrow_mut(), |(v, n)| (v, n));
+            v.fill(1);
+            *n = 1;
+            drop(v);
+            assert!(workspace.try_borrow().is_err());
+            assert_eq!(*n, 1);
+        }
+        assert_eq!(*workspace.borrow(), (vec![0; 4], 0));
+
+        let workspace = mutex::Temp::new((vec![0; 4], 0), |(v, n)| {
+            v.fill(0);
+            *n = 0;
+        });
+        {
+            let (mut v, mut n) =
+                mutex::TempRef::map_split(workspace.lock().unwrap(), |(v, n)| (v, n));
+            v.fill(1);
+            *n = 1;
+            drop(n);
+            let mut first = mutex::MappedTempRef::map(v, |v| &mut v[0]);
+            *first = 2;
+        }
+        assert_eq!(*workspace.lock().unwrap(), (vec![0; 4], 0));
+
+        let workspace = rwlock::Temp::new(vec![0; 4], |v| v.fill(0));
+        {
+            let mut first = rwlock::TempRef::map(workspace.write().unwrap(), |v| &mut v[0]);
+            *first = 1;
+        }
+        assert_eq!(*workspace.read().unwrap(), vec![0; 4]);
+    }
+
+    #[test]
+    fn owned() {
+        let workspace = std::sync::Arc::new(rwlock::Temp::new(0, |n| *n = 0));
+        let mut guard = workspace.write_arc().unwrap();
+        *guard = 1;
+        assert!(workspace.try_write_arc().is_err());
+        assert_eq!(std::sync::Arc::strong_count(&workspace), 2);
+        drop(guard);
+        assert_eq!(std::sync::Arc::strong_count(&workspace), 1);
+        assert_eq!(*workspace.read().unwrap(), 0);
+
+        let workspace = std::sync::Arc::new(mutex::Temp::new(0, |n| *n = 0));
+        let mut guard = workspace.try_lock_arc().unwrap();
+        *guard = 1;
+        drop(workspace);
+        assert_eq!(*guard, 1);
+        let workspace = guard.temp().clone();
+        drop(guard);
+        assert_eq!(*workspace.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn lock_all() {
+        let a = mutex::Temp::new(0, |n| *n = 0);
+        let b = mutex::Temp::new(0, |n| *n = 0);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let (a, b) = tempref::lock_all((&a, &b));
+                    *a.unwrap() += 1;
+                    *b.unwrap() += 1;
+                }
+            });
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let (b, a) = tempref::lock_all((&b, &a));
+                    *a.unwrap() += 1;
+                    *b.unwrap() += 1;
+                }
+            });
+        });
+        assert_eq!(*a.lock().unwrap(), 0);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = tempref::lock_all((&a, &a));
+        }));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn timeout() {
+        use std::sync::TryLockError;
+        use std::time::Duration;
+
+        let workspace = mutex::Temp::new(0, |n| *n = 0);
+        let barrier = std::sync::Barrier::new(2);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut guard = workspace.lock().unwrap();
+                *guard = 1;
+                barrier.wait();
+                std::thread::sleep(Duration::from_millis(20));
+            });
+            barrier.wait();
+            assert!(matches!(
+                workspace.lock_timeout(Duration::ZERO),
+                Err(TryLockError::WouldBlock)
+            ));
+            let guard = workspace.lock_timeout(Duration::from_secs(10)).unwrap();
+            assert_eq!(*guard, 0);
+        });
+
+        let workspace = rwlock::Temp::new(0, |n| *n = 0);
+        let reader = workspace.read_timeout(Duration::ZERO).unwrap();
+        assert!(matches!(
+            workspace.write_timeout(Duration::from_millis(5)),
+            Err(TryLockError::WouldBlock)
+        ));
+        drop(reader);
+        assert!(workspace.write_timeout(Duration::ZERO).is_ok());
+
+        // A waiter is woken up by the release, also of a lock which no guard was handed out for.
+        let barrier = std::sync::Barrier::new(2);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                workspace.replace_with(|_| {
+                    barrier.wait();
+                    std::thread::sleep(Duration::from_millis(20));
+                    2
+                })
+            });
+            barrier.wait();
+            assert_eq!(*workspace.read_timeout(Duration::from_secs(10)).unwrap(), 2);
+        });
+    }
+
+    #[test]
+    fn unpoisoned() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let resets = AtomicUsize::new(0);
+        let workspace = rwlock::Temp::new(0, |n| {
+            *n = 0;
+            resets.fetch_add(1, Ordering::Relaxed);
+        });
+        let result = std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut guard = workspace.write().unwrap();
+                *guard = 1;
+                panic!("poisons the lock");
+            })
+            .join()
+        });
+        assert!(result.is_err());
+        assert!(workspace.write().is_err());
+        assert_eq!(resets.load(Ordering::Relaxed), 2);
+
+        assert_eq!(*workspace.write_unpoisoned(), 0);
+        assert_eq!(resets.load(Ordering::Relaxed), 4);
+        assert!(workspace.try_write_unpoisoned().is_some());
+        assert!(workspace.write().is_ok());
+    }
+
+    #[test]
+    fn unwind_policy() {
+        let workspace = mutex::Temp::new(0, |n| {
+            if *n != 0 {
+                *n = 0;
+                panic!("reset panics");
+            }
+        });
+        let result = std::thread::scope(|s| {
+            s.spawn(|| {
+                let mut guard = workspace.lock().unwrap();
+                *guard = 1;
+                panic!("unwinds through the guard");
+            })
+            .join()
+        });
+        assert!(result.is_err());
+        assert_eq!(*workspace.lock_unpoisoned(), 0);
+
+        let workspace = unsync::Temp::new(0, |n| *n = 0).with_unwind_policy(UnwindPolicy::Skip);
+        assert_eq!(workspace.unwind_policy(), UnwindPolicy::Skip);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut guard = workspace.borrow_mut();
+            *guard = 1;
+            panic!("unwinds through the guard");
+        }));
+        assert!(result.is_err());
+        assert_eq!(*workspace.borrow(), 1);
+    }
+
+    #[test]
+    fn reset_poison() {
+        let workspace = rwlock::Temp::new(0, |n| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        assert!(!workspace.is_reset_poisoned());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *workspace.write().unwrap() = -1;
+        }));
+        assert!(result.is_err());
+        assert!(workspace.is_reset_poisoned());
+        assert_eq!(*workspace.read().unwrap_err().into_inner(), -1);
+
+        *workspace.write().unwrap_err().into_inner() = 1;
+        assert_eq!(*workspace.read().unwrap_err().into_inner(), 0);
+        assert!(workspace.is_reset_poisoned());
+        workspace.clear_reset_poison();
+        assert!(!workspace.is_reset_poisoned());
+
+        let workspace = unsync::Temp::new(0, |n| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *workspace.borrow_mut() = -1;
+        }));
+        assert!(result.is_err());
+        assert!(workspace.is_reset_poisoned());
+    }
+
+    #[test]
+    fn get_mut() {
+        let mut workspace = unsync::Temp::new(vec![0; 4], |v| v.fill(0));
+        workspace.get_mut()[0] = 1;
+        assert_eq!(workspace.get_mut_reset(|v| v.iter().sum::<i32>()), 1);
+        assert_eq!(*workspace.borrow(), vec![0; 4]);
+
+        let mut workspace = mutex::Temp::new(vec![0; 4], |v| v.fill(0));
+        workspace.get_mut().unwrap()[0] = 1;
+        assert_eq!(
+            workspace.get_mut_reset(|v| v.iter().sum::<i32>()).unwrap(),
+            1
+        );
+        assert_eq!(*workspace.get_mut().unwrap(), vec![0; 4]);
+        assert_eq!(workspace.generation(), 1);
+
+        let mut workspace = rwlock::Temp::new(vec![0; 4], |v| v.fill(0));
+        workspace.get_mut().unwrap()[0] = 1;
+        assert_eq!(
+            workspace.get_mut_reset(|v| v.iter().sum::<i32>()).unwrap(),
+            1
+        );
+        assert_eq!(*workspace.get_mut().unwrap(), vec![0; 4]);
+        assert_eq!(workspace.generation(), 1);
+    }
+
+    #[test]
+    fn replace() {
+        let workspace = mutex::Temp::new(vec![1; 4], |v| v.fill(0));
+        assert_eq!(workspace.replace(vec![2; 2]).unwrap(), vec![1; 4]);
+        assert_eq!(
+            workspace.replace_with(|v| vec![v[0]; 3]).unwrap(),
+            vec![2; 2]
+        );
+        assert_eq!(workspace.take().unwrap(), vec![2; 3]);
+        assert!(workspace.lock().unwrap().is_empty());
+
+        let workspace = rwlock::Temp::new(vec![1; 4], |v| v.fill(0));
+        let spare = std::sync::RwLock::new(vec![3; 1]);
+        assert_eq!(workspace.replace(vec![2; 2]).unwrap(), vec![1; 4]);
+        workspace.swap(&spare).unwrap();
+        assert_eq!(*spare.read().unwrap(), vec![2; 2]);
+        assert_eq!(
+            workspace.replace_with(|v| vec![v[0]; 3]).unwrap(),
+            vec![3; 1]
+        );
+        assert_eq!(workspace.take().unwrap(), vec![3; 3]);
+        assert!(workspace.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn constructors() {
+        let reset = |v: &mut Vec<i32>| v.fill(0);
+
+        assert_eq!(*unsync::Temp::new(vec![1], reset).borrow(), vec![1]);
+        assert_eq!(*unsync::Temp::new_with(vec![1], reset).borrow(), vec![0]);
+        assert!(unsync::Temp::new_default(reset).borrow().is_empty());
+        assert!(unsync::Temp::new_default_with(reset).borrow().is_empty());
+
+        assert_eq!(*mutex::Temp::new(vec![1], reset).lock().unwrap(), vec![1]);
+        assert_eq!(
+            *mutex::Temp::new_with(vec![1], reset).lock().unwrap(),
+            vec![0]
+        );
+        assert!(mutex::Temp::new_default(reset).lock().unwrap().is_empty());
+        assert!(
+            mutex::Temp::new_default_with(reset)
+                .lock()
+                .unwrap()
+                .is_empty()
+        );
+
+        assert_eq!(*rwlock::Temp::new(vec![1], reset).read().unwrap(), vec![1]);
+        assert_eq!(
+            *rwlock::Temp::new_with(vec![1], reset).read().unwrap(),
+            vec![0]
+        );
+        assert!(rwlock::Temp::new_default(reset).read().unwrap().is_empty());
+        assert!(
+            rwlock::Temp::new_default_with(reset)
+                .read()
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn set_reset() {
+        let mut workspace: mutex::TempFn<Vec<u8>> = mutex::Temp::new(vec![1; 4], |v| v.clear());
+        workspace.set_reset(|v| v.fill(0));
+        workspace.lock().unwrap().push(1);
+        assert_eq!(*workspace.lock().unwrap(), vec![0; 5]);
+
+        let workspace = workspace.with_reset(|v: &mut Vec<u8>| v.truncate(2));
+        drop(workspace.lock().unwrap());
+        assert_eq!(*workspace.lock().unwrap(), vec![0; 2]);
+    }
+
+    #[test]
+    fn into_parts() {
+        let workspace = unsync::Temp::new(1, |n| *n = 0);
+        let (mut value, mut reset) = workspace.into_parts();
+        assert_eq!(value, 1);
+        reset(&mut value);
+        assert_eq!(value, 0);
+
+        let workspace = mutex::Temp::new(vec![1], |v| v.clear());
+        let (mut value, mut reset) = workspace.into_parts().unwrap();
+        reset(&mut value);
+        assert!(value.is_empty());
+
+        let workspace = rwlock::Temp::new(vec![1], |v| v.clear());
+        let (value, _) = workspace.into_parts().unwrap();
+        assert_eq!(value, vec![1]);
+    }
+
+    #[test]
+    fn debug() {
+        let workspace = mutex::Temp::new(1, |n| *n = 0);
+        assert_eq!(
+            format!("{workspace:?}"),
+            "Temp { value: 1, poisoned: false }"
+        );
+        let guard = workspace.lock().unwrap();
+        assert_eq!(
+            format!("{workspace:?}"),
+            "Temp { value: <locked>, poisoned: false }"
+        );
+        drop(guard);
+
+        let workspace = rwlock::Temp::new(1, |n| *n = 0);
+        let guard = workspace.read().unwrap();
+        assert_eq!(
+            format!("{workspace:?}"),
+            "Temp { value: 1, poisoned: false }"
+        );
+        drop(guard);
+        let _guard = workspace.write().unwrap();
+        assert_eq!(
+            format!("{workspace:?}"),
+            "Temp { value: <locked>, poisoned: false }"
+        );
+    }
+
+    #[test]
+    fn display() {
+        let workspace = unsync::Temp::new(1, |n| *n = 0);
+        let mut guard = workspace.borrow_mut();
+        *guard = 2;
+        assert_eq!(format!("{guard} {workspace}"), "2 <borrowed>");
+        drop(guard);
+        assert_eq!(workspace.to_string(), "0");
+
+        let workspace = mutex::Temp::new(1, |n| *n = 0);
+        assert_eq!(workspace.to_string(), "1");
+        let guard = workspace.lock().unwrap();
+        assert_eq!(format!("{guard} {workspace}"), "1 <locked>");
+    }
+
+    #[test]
+    fn eq() {
+        let a = unsync::Temp::new(1, |n| *n = 0);
+        let b = unsync::Temp::new(1, |n| *n = 1);
+        assert!(a == b && a == 1);
+
+        let a = mutex::Temp::new(vec![1], |v| v.clear());
+        let b = mutex::Temp::new(vec![1], |v| v.fill(0));
+        std::thread::scope(|s| {
+            s.spawn(|| (0..1000).all(|_| a == b));
+            s.spawn(|| (0..1000).all(|_| b == a));
+        });
+        assert!(a == a && a == b && a == vec![1]);
+
+        let a = rwlock::Temp::new(1.0, |n| *n = 0.0);
+        let b = rwlock::Temp::new(f64::NAN, |n| *n = 0.0);
+        assert!(a == 1.0 && a != b && b != b);
+    }
+
+    #[test]
+    fn clone() {
+        let prototype = mutex::Temp::new(vec![0; 4], |v| v.fill(0));
+        std::thread::scope(|s| {
+            for n in 1..4 {
+                let workspace = prototype.clone();
+                s.spawn(move || {
+                    workspace.lock().unwrap().fill(n);
+                    assert_eq!(workspace, vec![0; 4]);
+                });
+            }
+        });
+
+        let prototype = unsync::Temp::new(vec![1], |v| v.clear());
+        let workspace = prototype.clone();
+        workspace.borrow_mut().push(2);
+        assert!(workspace.borrow().is_empty());
+        assert_eq!(prototype, vec![1]);
+    }
+
+    #[test]
+    fn default() {
+        let workspace: rwlock::TempFn<Vec<u8>> = Default::default();
+        workspace.write().unwrap().push(1);
+        assert!(workspace.read().unwrap().is_empty());
+
+        let workspace = mutex::TempFn::from(vec![1]);
+        assert_eq!(workspace, vec![1]);
+        workspace.lock().unwrap().push(2);
+        assert_eq!(workspace, vec![]);
+    }
+
+    #[test]
+    fn as_mut() {
+        fn fill(mut buf: impl AsMut<Vec<u8>>) {
+            buf.as_mut().push(1);
+        }
+        let workspace = unsync::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let mut guard = workspace.borrow_mut();
+        fill(&mut *guard);
+        fill(&mut guard);
+        assert_eq!(guard.as_ref(), &[1, 1]);
+        drop(guard);
+
+        let workspace = mutex::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        fill(workspace.lock().unwrap());
+        assert!(workspace.lock().unwrap().is_empty());
+
+        let workspace = rwlock::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let mut guard = workspace.write().unwrap();
+        core::borrow::BorrowMut::<Vec<u8>>::borrow_mut(&mut guard).push(1);
+        assert_eq!(core::borrow::Borrow::<Vec<u8>>::borrow(&guard), &[1]);
+    }
+
+    #[test]
+    fn io() {
+        use std::io::{Read, Write};
+        let buf = mutex::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let mut guard = buf.lock().unwrap();
+        write!(guard, "{}-{}", 1, 2).unwrap();
+        guard.write_all(b"!").unwrap();
+        assert_eq!(*guard, b"1-2!");
+        drop(guard);
+        assert!(buf.lock().unwrap().is_empty());
+
+        let input = unsync::Temp::new(std::io::Cursor::new(b"abc".to_vec()), |c| c.set_position(0));
+        let mut text = String::new();
+        input.borrow_mut().read_to_string(&mut text).unwrap();
+        input.borrow_mut().read_to_string(&mut text).unwrap();
+        assert_eq!(text, "abcabc");
+    }
+
+    #[test]
+    fn fmt_write() {
+        use core::fmt::Write;
+        let buf = unsync::Temp::new(String::new(), |s| s.clear());
+        let mut guard = buf.borrow_mut();
+        write!(guard, "{}-{}", 1, 2).unwrap();
+        guard.write_char('!').unwrap();
+        assert_eq!(*guard, "1-2!");
+        drop(guard);
+        assert!(buf.borrow().is_empty());
+
+        let buf = rwlock::Temp::new(String::new(), |s| s.clear());
+        writeln!(buf.write().unwrap(), "line").unwrap();
+        assert!(buf.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn copy() {
+        let state = copy::Temp::new(1u32, 0);
+        assert_eq!(state.get(), 1);
+        {
+            let scope = state.scope();
+            assert_eq!(scope.replace(5), 1);
+            let _nested = state.scope();
+            assert_eq!(state.update(|n| n * 2), 10);
+        }
+        assert_eq!(state.get(), 0);
+
+        state.set(7);
+        assert_eq!(state.to_string(), "7");
+        state.reset();
+        assert_eq!(state.get(), state.reset_value());
+    }
+
+    #[test]
+    fn atomic() {
+        use atomic::AtomicTemp;
+        use core::sync::atomic::Ordering;
+        let flag = atomic::TempBool::new(true, false);
+        let counter = atomic::TempI64::default();
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    counter.fetch_add(1, Ordering::Relaxed);
+                });
+            }
+        });
+        assert_eq!(counter.load(Ordering::Relaxed), 4);
+        counter.reset(Ordering::Relaxed);
+        assert_eq!(counter.load(Ordering::Relaxed), 0);
+
+        {
+            let scope = flag.scope(Ordering::SeqCst);
+            assert!(scope.fetch_and(true, Ordering::SeqCst));
+        }
+        assert!(!flag.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn seqlock() {
+        let pair = seqlock::Temp::new((0u64, 0u64), |p| p.1 = p.0);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    let mut guard = pair.write();
+                    guard.0 += 1;
+                    guard.1 = 0;
+                }
+            });
+            for _ in 0..2 {
+                s.spawn(|| {
+                    for _ in 0..1000 {
+                        let (a, b) = pair.read();
+                        assert_eq!(a, b);
+                    }
+                });
+            }
+        });
+        assert_eq!(pair.read(), (1000, 1000));
+
+        let guard = pair.try_write().unwrap();
+        assert!(pair.try_write().is_err());
+        assert!(pair.try_read().is_err());
+        drop(guard);
+        assert_eq!(format!("{pair:?}"), "Temp { value: (1000, 1000) }");
+    }
+
+    #[test]
+    fn triple() {
+        let mut frames = triple::Temp::new(Vec::new(), |v: &mut Vec<u32>| v.clear());
+        let (mut producer, mut consumer) = frames.split();
+        assert!(consumer.read().is_empty());
+        std::thread::scope(|s| {
+            s.spawn(move || {
+                for n in 0..1000 {
+                    let mut frame = producer.write();
+                    assert!(frame.is_empty());
+                    frame.extend([n; 4]);
+                }
+            });
+            let mut last = 0;
+            while last != 999 {
+                let frame = consumer.read();
+                if let Some(&n) = frame.first() {
+                    assert_eq!(frame, &[n; 4]);
+                    assert!(n >= last);
+                    last = n;
+                }
+            }
+        });
+        assert!(!consumer.has_update());
+    }
+
+    #[test]
+    fn sharded() {
+        let counts: sharded::ShardedTemp<u32, _, 4> =
+            sharded::ShardedTemp::from_fn(|n| n as u32, |n: &mut u32| *n = 0);
+        assert!(std::ptr::eq(counts.shard(), counts.shard()));
+        std::thread::scope(|s| {
+            for _ in 0..8 {
+                s.spawn(|| *counts.lock().unwrap() += 10);
+            }
+        });
+        let guard = counts.lock().unwrap();
+        assert!(counts.try_lock().is_err());
+        drop(guard);
+
+        counts.reset_all().unwrap();
+        assert!(
+            counts
+                .as_slice()
+                .iter()
+                .all(|shard| *shard.lock().unwrap() == 0)
+        );
+    }
+
+    #[test]
+    fn reentrant() {
+        let log = reentrant::Temp::new(Vec::new(), |v: &mut Vec<u32>| v.clear());
+        let outer = log.lock();
+        let inner = log.try_lock().unwrap();
+        inner.borrow_mut().push(1);
+        drop(outer);
+        assert_eq!(*inner.borrow(), [1]);
+        std::thread::scope(|s| {
+            assert!(s.spawn(|| log.try_lock().is_err()).join().unwrap());
+            assert_eq!(format!("{log:?}"), "Temp { value: [1] }");
+            let waiter = s.spawn(|| log.lock().borrow().len());
+            drop(inner);
+            assert_eq!(waiter.join().unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn condvar() {
+        let workspace = mutex::Temp::new(0, |n| *n = 0);
+        let changed = std::sync::Condvar::new();
+        let (guard, timeout) = workspace
+            .wait_timeout_while(&changed, std::time::Duration::from_millis(10), |n| *n == 0)
+            .unwrap();
+        assert!(timeout.timed_out());
+        drop(guard);
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| *workspace.wait_while(&changed, |n| *n != 3).unwrap());
+            for _ in 0..3 {
+                workspace.update_and_notify(&changed, |n| *n += 1).unwrap();
+            }
+            assert_eq!(waiter.join().unwrap(), 3);
+        });
+        assert_eq!(*workspace.lock().unwrap(), 0);
+    }
+
+    #[test]
+    fn watch() {
+        let (workspace, mut watcher) = rwlock::Temp::new(vec![0u8], |v| v.truncate(1)).watch();
+        assert!(watcher.latest().is_none());
+        std::thread::scope(|s| {
+            let observer = s.spawn(move || watcher.changed().dirty.clone());
+            workspace.write().unwrap().push(1);
+            assert_eq!(observer.join().unwrap(), [0, 1]);
+        });
+
+        let (workspace, mut watcher) = mutex::Temp::new(0, |n| *n = 0).watch();
+        assert!(
+            watcher
+                .changed_timeout(std::time::Duration::from_millis(1))
+                .is_none()
+        );
+        workspace.reset().unwrap();
+        assert!(watcher.has_changed());
+        assert_eq!(*watcher.changed(), watch::Snapshot { dirty: 0, reset: 0 });
+        assert!(!watcher.has_changed());
+    }
+
+    #[test]
+    fn generation() {
+        let workspace = mutex::Temp::new(vec![0u8], |v| v.clear());
+        assert_eq!(workspace.generation(), 0);
+        workspace.lock().unwrap().push(1);
+        workspace.transaction().unwrap().commit();
+        assert_eq!(workspace.generation(), 1);
+
+        let workspace = unsync::Temp::new(0, |n| *n = 0);
+        workspace.borrow_mut().reset();
+        assert_eq!(workspace.generation(), 2);
+
+        let state = copy::Temp::new_with(0);
+        drop(state.scope());
+        assert_eq!(state.generation(), 1);
+
+        use atomic::AtomicTemp;
+        let counter = atomic::TempU32::new_with(0);
+        counter.reset(std::sync::atomic::Ordering::Relaxed);
+        assert_eq!(counter.generation(), 1);
+
+        let stats = seqlock::Temp::new(0, |n| *n = 0);
+        *stats.write() = 1;
+        assert_eq!(stats.generation(), 1);
+
+        let log = reentrant::Temp::new(0, |n| *n = 0);
+        {
+            let _outer = log.lock();
+            drop(log.lock());
+        }
+        assert_eq!(log.generation(), 1);
+    }
+
+    #[test]
+    fn max_age() {
+        use std::time::Duration;
+        let cache = mutex::Temp::new(0, |n| *n = 0).with_max_age(Duration::from_millis(50));
+        let mut transaction = cache.transaction().unwrap();
+        *transaction = 1;
+        transaction.commit();
+        let transaction = cache.transaction().unwrap();
+        assert_eq!(*transaction, 1);
+        transaction.commit();
+
+        std::thread::sleep(Duration::from_millis(60));
+        let mut transaction = cache.transaction().unwrap();
+        assert_eq!(*transaction, 0);
+        *transaction = 2;
+        transaction.commit();
+        assert_eq!(cache.generation(), 1);
+
+        let cache = unsync::Temp::new(0, |n| *n = 0).with_max_age(Duration::ZERO);
+        let mut transaction = cache.transaction();
+        *transaction = 1;
+        transaction.commit();
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(*cache.borrow_mut(), 0);
+    }
+
+    #[test]
+    fn spawn_resetter() {
+        use std::sync::Arc;
+        use std::time::Duration;
+        let workspace = Arc::new(rwlock::Temp::new(0, |n| *n = 0));
+        let resetter = workspace.spawn_resetter(Duration::from_millis(1));
+        let mut transaction = workspace.transaction().unwrap();
+        *transaction = 1;
+        transaction.commit();
+        while workspace.generation() == 0 {
+            std::thread::yield_now();
+        }
+        assert_eq!(*workspace.read().unwrap(), 0);
+        resetter.stop();
+        assert!(Arc::try_unwrap(workspace).is_ok());
+
+        let workspace = Arc::new(mutex::Temp::new(0, |n| *n = 0));
+        let resetter = workspace.spawn_resetter(Duration::from_secs(60));
+        drop(workspace);
+        drop(resetter);
+    }
+
+    #[test]
+    #[should_panic(expected = "must not be zero")]
+    fn spawn_resetter_zero_interval() {
+        let workspace = std::sync::Arc::new(mutex::Temp::new(0, |n| *n = 0));
+        let _resetter = workspace.spawn_resetter(std::time::Duration::ZERO);
+    }
+
+    #[test]
+    fn min_reset_interval() {
+        use std::time::Duration;
+        let index =
+            mutex::Temp::new(0, |n| *n = 0).with_min_reset_interval(Duration::from_millis(50));
+        *index.lock().unwrap() = 1;
+        assert_eq!(*index.lock().unwrap(), 1);
+        index.reset().unwrap();
+        assert_eq!(index.generation(), 1);
+
+        *index.lock().unwrap() = 2;
+        std::thread::sleep(Duration::from_millis(60));
+        let guard = index.lock().unwrap();
+        assert_eq!(*guard, 0);
+        drop(guard); // Postponed again, since the reset ran just now.
+        assert_eq!(index.generation(), 2);
+    }
+
+    #[test]
+    fn reset_if() {
+        let workspace = unsync::Temp::new(0, |n| *n = 0).with_reset_if(|n| *n > 10);
+        *workspace.borrow_mut() = 5;
+        assert_eq!(*workspace.borrow(), 5);
+        *workspace.borrow_mut() = 20;
+        assert_eq!(*workspace.borrow(), 0);
+        *workspace.transaction() = 5;
+        assert_eq!(*workspace.borrow(), 0);
+
+        // Resets every third guard, counting them in the predicate.
+        let drops = std::sync::atomic::AtomicUsize::new(0);
+        let workspace = mutex::Temp::new(0, |n| *n = 0).with_reset_if(move |_| {
+            drops.fetch_add(1, std::sync::atomic::Ordering::Relaxed) % 3 == 2
+        });
+        let copy = workspace.clone();
+        *workspace.lock().unwrap() += 1;
+        *workspace.lock().unwrap() += 1;
+        drop(copy.lock().unwrap()); // The third drop, counted with those of `workspace`.
+        assert_eq!(copy.generation(), 1);
+        assert_eq!(*workspace.lock().unwrap(), 2);
+        assert_eq!(workspace.generation(), 0);
+    }
+
+    #[test]
+    fn checkpoint() {
+        let state = checkpoint::Temp::new(0);
+        *state.borrow_mut() = 1;
+        assert_eq!(*state.borrow(), 0);
+        state.with_value(|n| *n = 1);
+        assert_eq!(state.checkpoint(), 1);
+        state.with_value(|n| *n = 2);
+        assert_eq!(state.checkpoint(), 2);
+        {
+            let mut guard = state.borrow_mut();
+            *guard = 3;
+            guard.reset();
+            assert_eq!(*guard, 2);
+        }
+        state.rollback_to(1);
+        assert_eq!((*state.borrow(), state.latest()), (1, 1));
+        state.rollback();
+        state.rollback();
+        assert_eq!((*state.borrow(), state.latest()), (0, 0));
+        assert!(
+            std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| state.rollback_to(1)))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn history() {
+        let state = history::HistoryTemp::new(0);
+        assert!(!state.undo());
+        for n in 1..=3 {
+            let mut guard = state.borrow_mut();
+            *guard = n;
+            guard.commit();
+        }
+        *state.borrow_mut() = 10;
+        assert_eq!(*state.borrow(), 3);
+        assert!(state.undo() && state.undo());
+        assert_eq!(*state.borrow(), 1);
+
+        let mut guard = state.borrow_mut();
+        *guard = 4;
+        guard.commit();
+        assert!(!state.can_redo());
+        assert!(state.undo());
+        assert_eq!(*state.borrow(), 1);
+        assert!(state.redo());
+        assert_eq!(*state.borrow(), 4);
+    }
+
+    #[test]
+    fn dirty() {
+        let resets = core::cell::RefCell::new(Vec::new());
+        let temp = dirty::Temp::new(vec![0; 100], |range: &mut [i32]| {
+            resets.borrow_mut().push(range.len());
+            range.fill(0);
+        });
+        {
+            let mut guard = temp.borrow_mut();
+            guard.slice_mut(10..20).fill(1);
+            guard.slice_mut(15..=25).fill(2);
+            guard.slice_mut(60..).fill(3);
+            *guard.get_mut(5).unwrap() = 4;
+            assert_eq!(guard[15], 2);
+        }
+        assert!(temp.borrow().iter().all(|&n| n == 0));
+        assert_eq!(*resets.borrow(), [1, 16, 40]);
+        assert_eq!(temp.generation(), 1);
+
+        drop(temp.borrow_mut());
+        assert_eq!(temp.generation(), 1);
+    }
+
+    #[test]
+    fn dirty_pages() {
+        let resets = core::cell::RefCell::new(Vec::new());
+        let temp = dirty::Temp::new(vec![0; 1000], |range: &mut [i32]| {
+            resets.borrow_mut().push(range.len());
+            range.fill(0);
+        })
+        .with_page_size(64);
+        assert_eq!(temp.page_size(), Some(64));
+        {
+            let mut guard = temp.borrow_mut();
+            guard.slice_mut(10..130).fill(1);
+            *guard.get_mut(999).unwrap() = 2;
+            *guard.get_mut(500).unwrap() = 3;
+        }
+        assert!(temp.borrow().iter().all(|&n| n == 0));
+        assert_eq!(*resets.borrow(), [192, 64, 40]);
+    }
+
+    #[test]
+    fn zeroing() {
+        let boxed = unsync::Temp::zeroing(vec![7u8; 16].into_boxed_slice());
+        boxed.borrow_mut().fill(1);
+        assert_eq!(*boxed.borrow(), [0; 16].into());
+
+        let array = mutex::Temp::zeroing([0u8; 8]);
+        array.lock().unwrap()[3] = 3;
+        assert_eq!(array.into_inner().unwrap(), [0; 8]);
+    }
+
+    #[test]
+    fn pinned_temp() {
+        let temp = core::pin::pin!(unsync::PinnedTemp::new(
+            Vec::new(),
+            |v: core::pin::Pin<&mut Vec<u8>>| v.get_mut().clear()
+        ));
+        let temp = temp.as_ref();
+        {
+            let mut guard = temp.borrow_mut();
+            guard.as_mut().get_mut().push(1);
+            guard.push(2);
+            assert!(temp.try_borrow_mut().is_err());
+        }
+        assert!(temp.borrow().is_empty());
+    }
+
+    #[test]
+    fn temp_array() {
+        static TABLE: array::TempArray<u16, 8> = array::TempArray::new(7);
+        {
+            let mut guard = TABLE.lock();
+            guard[..4].fill(1);
+            assert!(TABLE.try_lock().is_err());
+            guard.reset();
+            assert_eq!(*guard, [7; 8]);
+            guard[0] = 0;
+        }
+        assert_eq!(*TABLE.lock(), [7; 8]);
+        assert_eq!(TABLE.generation(), 2);
+    }
+
+    #[test]
+    fn ghost() {
+        ghost::GhostToken::scope(|mut token| {
+            let a = ghost::Temp::new(vec![1], |v: &mut Vec<i32>| v.truncate(1));
+            let b = ghost::Temp::new(0, |n: &mut i32| *n = 0);
+            {
+                let mut guard = a.borrow_mut(&mut token);
+                guard.push(2);
+                assert_eq!(*guard, [1, 2]);
+            }
+            *b.borrow_mut(&mut token) += a.borrow(&token).len() as i32;
+            assert_eq!(*a.borrow(&token), [1]);
+            assert_eq!(*b.borrow(&token), 0);
+        });
+    }
+
+    fn append_access<W: TempAccess<Value = Vec<u8>>>(
+        workspace: &W,
+        data: &[u8],
+    ) -> Result<usize, Error> {
+        let mut buf = workspace.access()?;
+        buf.extend_from_slice(data);
+        Ok(buf.len())
+    }
+
+    #[test]
+    fn temp_access() {
+        let temp = unsync::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let guard = temp.borrow_mut();
+        assert_eq!(append_access(&temp, b"ab"), Err(Error::Borrow));
+        drop(guard);
+        assert_eq!(append_access(&temp, b"ab"), Ok(2));
+
+        let temp = rwlock::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        assert_eq!(append_access(&temp, b"ab"), Ok(2));
+        assert_eq!(TempAccess::reset(&temp), Ok(()));
+        assert_eq!(temp.generation(), 2);
+    }
+
+    #[test]
+    fn mutex_fairness() {
+        let temp = mutex::Temp::new(0, |n: &mut i32| *n = 0).with_fairness(true);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        *temp.lock().unwrap() += 1;
+                    }
+                });
+            }
+            s.spawn(|| {
+                for n in 0..100 {
+                    temp.set(n).unwrap();
+                    temp.reset().unwrap();
+                }
+            });
+        });
+        assert_eq!(temp.generation(), 500);
+        let temp = temp.with_reset(|n: &mut i32| *n = -1);
+        assert!(temp.is_fair());
+        assert!(!temp.with_fairness(false).is_fair());
+    }
+
+    #[test]
+    fn lock_spin() {
+        use std::sync::TryLockError;
+
+        let temp = mutex::Temp::new(0, |n: &mut i32| *n = 0);
+        let guard = temp.lock().unwrap();
+        assert!(matches!(
+            temp.try_lock_spin(100),
+            Err(TryLockError::WouldBlock)
+        ));
+        drop(guard);
+        *temp.lock_spin(100).unwrap() = 1;
+
+        let temp = rwlock::Temp::new(0, |n: &mut i32| *n = 0);
+        let reader = temp.read().unwrap();
+        assert!(matches!(
+            temp.try_write_spin(0),
+            Err(TryLockError::WouldBlock)
+        ));
+        drop(reader);
+        *temp.write_spin(100).unwrap() = 1;
+        assert_eq!(temp.generation(), 1);
+    }
+
+    #[test]
+    fn temp_uninit() {
+        use std::rc::Rc;
+
+        let scratch = uninit::TempUninit::with_capacity(2).with_wipe(true);
+        let counter = Rc::new(());
+        {
+            let mut guard = scratch.borrow_mut();
+            guard.push(counter.clone()).unwrap();
+            guard.push(counter.clone()).unwrap();
+            assert!(guard.push(counter.clone()).is_err());
+            assert!(guard.spare_mut().is_empty());
+            assert!(scratch.try_borrow_mut().is_err());
+        }
+        assert_eq!(Rc::strong_count(&counter), 1);
+        let mut guard = scratch.borrow_mut();
+        assert_eq!(
+            guard.extend_from_slice(&[counter.clone(), counter.clone(), counter]),
+            2
+        );
+        guard.reset();
+        assert!(guard.is_empty());
+        drop(guard);
+        assert_eq!(scratch.generation(), 2);
+    }
+
+    #[test]
+    fn secret_temp() {
+        let password = secret::SecretTemp::new(String::new(), |s: &mut String| s.clear());
+        let mut guard = password.borrow_mut();
+        guard.expose_secret(|s| s.push_str("hunter2"));
+        assert_eq!(format!("{guard:?}"), "SecretRef([REDACTED])");
+        assert_eq!(guard.expose_secret_ref(|s| s.len()), 7);
+        assert_eq!(password.try_borrow_mut().unwrap_err(), Error::Borrow);
+        drop(guard);
+        assert!(password.with_secret(|s| s.is_empty()));
+        assert_eq!(password.generation(), 2);
+    }
+
+    #[test]
+    fn concurrent_resets() {
+        let mut calls = 0usize;
+        let temp = rwlock::Temp::new(0usize, move |n: &mut usize| {
+            calls += 1;
+            *n = calls;
+        });
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for _ in 0..100 {
+                        temp.reset().unwrap();
+                        drop(temp.read().unwrap());
+                    }
+                });
+            }
+        });
+        assert_eq!(*temp.read().unwrap(), 400);
+        assert_eq!(temp.generation(), 400);
+    }
+
+    #[test]
+    fn guard_size() {
+        use std::mem::size_of;
+        use std::sync::{MutexGuard, RwLockWriteGuard};
+        let word = size_of::<usize>();
+        let mut count = 0u64;
+        let capturing = move |n: &mut u8| {
+            count += 1;
+            *n = count as u8;
+        };
+        fn size_of_guard<T, F: FnMut(&mut T)>(_: &unsync::Temp<T, F>) -> usize {
+            size_of::<unsync::TempRef<'_, T, F>>()
+        }
+        assert_eq!(
+            size_of_guard(&unsync::Temp::new(0u8, capturing)),
+            size_of::<std::cell::RefMut<'_, u8>>() + word
+        );
+        // The reset function shares the lock of the value, so it adds nothing to the guard, and only with the
+        // `upgradable` feature a writer holds the gate besides the write lock.
+        let gate = if cfg!(feature = "upgradable") {
+            size_of::<MutexGuard<'_, ()>>()
+        } else {
+            0
+        };
+        assert_eq!(
+            size_of::<mutex::TempRef<'_, u8, fn(&mut u8)>>(),
+            size_of::<MutexGuard<'_, (u8, fn(&mut u8))>>() + word
+        );
+        assert_eq!(
+            size_of::<mutex::TempRef<'_, u8, fn(&mut u8)>>(),
+            size_of::<mutex::TempRef<'_, u8, Box<dyn FnMut(&mut u8) + Send>>>()
+        );
+        assert_eq!(
+            size_of::<rwlock::TempRef<'_, u8, Box<dyn FnMut(&mut u8) + Send + Sync>>>(),
+            size_of::<RwLockWriteGuard<'_, u8>>() + gate + word
+        );
+    }
+
+    #[test]
+    fn shared_reset() {
+        use std::sync::Arc;
+        type Strategy = dyn Fn(&mut Vec<u8>) + Send + Sync;
+        let strategy: Arc<Strategy> = Arc::new(|v: &mut Vec<u8>| v.clear());
+        let a = rwlock::Temp::new_shared(vec![1], Arc::clone(&strategy));
+        let b = a.clone();
+        a.write().unwrap().push(2);
+        assert!(b.read().unwrap().len() == 1);
+        b.reset().unwrap();
+        assert!(b.read().unwrap().is_empty());
+        assert_eq!(Arc::strong_count(&strategy), 3);
+        drop((a, b));
+        assert_eq!(Arc::strong_count(&strategy), 1);
+    }
+
+    #[test]
+    fn shared_temp() {
+        fn append<W: TempAccess<Value = Vec<u8>>>(workspace: &W) -> Result<usize, Error> {
+            let mut buf = workspace.try_access()?;
+            buf.push(1);
+            Ok(buf.len())
+        }
+        let workspace = mutex::SharedTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let handle = workspace.clone();
+        assert!(handle.ptr_eq(&workspace));
+        assert_eq!(workspace.handle_count(), 2);
+        let guard = handle.lock_arc().unwrap();
+        assert_eq!(append(&workspace), Err(Error::WouldBlock));
+        drop(guard);
+        assert_eq!(append(&workspace), Ok(1));
+        assert_eq!(handle.generation(), 2);
+        drop(handle);
+        assert_eq!(workspace.into_arc().lock().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn weak_temp() {
+        let workspace = mutex::SharedTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        let observer = workspace.downgrade();
+        assert_eq!(observer.handle_count(), 1);
+        let upgraded = observer.upgrade().unwrap();
+        assert!(upgraded.ptr_eq(&workspace));
+        assert_eq!(observer.handle_count(), 2);
+        drop((workspace, upgraded));
+        assert!(observer.upgrade().is_none());
+        assert!(mutex::WeakTemp::<u8>::new().upgrade().is_none());
+    }
+
+    #[test]
+    fn mutex_lock_read() {
+        let temp = mutex::Temp::new(vec![1, 2], |v: &mut Vec<i32>| v.clear());
+        {
+            let read = temp.lock_read().unwrap();
+            assert_eq!(*read, [1, 2]);
+            assert!(temp.try_lock().is_err());
+            assert!(temp.try_lock_read().is_err());
+        }
+        assert_eq!(*temp.try_lock_read().unwrap(), [1, 2]);
+        assert_eq!(temp.generation(), 0);
+    }
+
+    #[test]
+    fn read_cloned() {
+        let local = unsync::Temp::new(vec![1], |v: &mut Vec<i32>| v.clear());
+        {
+            let _guard = local.borrow_mut();
+            assert!(local.read_cloned().is_err());
+        }
+        local.borrow_mut().push(2);
+        assert_eq!(local.read_cloned().unwrap(), Vec::<i32>::new());
+
+        let shared = rwlock::Temp::new(vec![1], |v: &mut Vec<i32>| v.clear());
+        let _read = shared.read().unwrap();
+        assert_eq!(shared.read_cloned().unwrap(), [1]);
+        assert_eq!(shared.generation(), 0);
+    }
+
+    #[test]
+    fn take_from_guard() {
+        let results = mutex::Temp::new(vec![0], |v: &mut Vec<i32>| v.push(0));
+        let mut guard = results.lock().unwrap();
+        guard.push(1);
+        assert_eq!(mutex::TempRef::take(guard, true), [0, 1]);
+        assert_eq!(*results.lock_read().unwrap(), [0]);
+        assert_eq!(results.generation(), 1);
+
+        let results = rwlock::Temp::new(vec![0], |v: &mut Vec<i32>| v.push(0));
+        assert_eq!(rwlock::TempRef::take(results.write().unwrap(), false), [0]);
+        assert!(results.read().unwrap().is_empty());
+        assert_eq!(results.generation(), 0);
+    }
+
+    #[test]
+    fn set_value() {
+        let temp = mutex::Temp::new(vec![1], |v: &mut Vec<i32>| v.retain(|&n| n > 0));
+        temp.set(vec![2, 0]).unwrap();
+        assert_eq!(temp.read_cloned().unwrap(), [2, 0]);
+        temp.set_with(vec![3, 0]).unwrap();
+        assert_eq!(temp.read_cloned().unwrap(), [3]);
+        assert_eq!(temp.generation(), 0);
+
+        let temp = rwlock::Temp::new(1, |n: &mut i32| *n = 0);
+        temp.set_with(5).unwrap();
+        assert_eq!(*temp.read().unwrap(), 0);
+        temp.set(5).unwrap();
+        assert_eq!(*temp.read().unwrap(), 5);
+    }
+
+    #[test]
+    fn project_fields() {
+        let temp = rwlock::Temp::new((vec![0u8; 4], 0u32), |(v, n)| {
+            v.fill(0);
+            *n = 0;
+        });
+        temp.project(|(v, n)| {
+            let bytes = rwlock::TempField::new(v, |v: &mut Vec<u8>| v.fill(0));
+            let count = rwlock::TempField::new(n, |n: &mut u32| *n = 0);
+            let mut guard = bytes.write().unwrap();
+            guard[0] = 1;
+            // The other part is locked independently.
+            *count.try_write().unwrap() = 7;
+            assert!(bytes.try_write().is_err());
+            drop(guard);
+            assert_eq!(*bytes.read().unwrap(), [0; 4]);
+            assert_eq!((bytes.generation(), count.generation()), (1, 1));
+            // The whole value stays locked for writing meanwhile.
+            assert!(temp.try_read().is_err());
+        })
+        .unwrap();
+        assert_eq!(temp.generation(), 0);
+        assert_eq!(*temp.read().unwrap(), (vec![0; 4], 0));
+
+        let mut n = 0;
+        let part = rwlock::TempField::new(&mut n, |n: &mut i32| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *part.write().unwrap() = -1;
+        }));
+        assert!(result.is_err());
+        assert!(part.is_reset_poisoned());
+        *part.write_unpoisoned() = 1;
+        assert_eq!(
+            *part
+                .read()
+                .unwrap_or_else(std::sync::PoisonError::into_inner),
+            0
+        );
+        part.clear_reset_poison();
+        assert!(!part.is_reset_poisoned());
+    }
+
+    #[test]
+    fn reset_group() {
+        let a = unsync::Temp::new(1, |n: &mut i32| *n = 0);
+        let b = std::sync::Arc::new(mutex::Temp::new(1, |n: &mut i32| *n = 0));
+        let c = rwlock::Temp::new(1, |n: &mut i32| *n = 0);
+        let mut group = ResetGroup::new();
+        group.register(&a).register(&b).register(&c);
+        assert_eq!(group.len(), 3);
+        group.reset_all();
+        assert_eq!(
+            (*a.borrow(), b.read_cloned().unwrap(), *c.read().unwrap()),
+            (0, 0, 0)
+        );
+
+        let _a = a.borrow();
+        let errors = group.try_reset_all().unwrap_err();
+        assert_eq!(errors.failures(), [(0, Error::Borrow)]);
+        assert_eq!(
+            errors.to_string(),
+            "1 temp(s) could not be reset: #0 (the value is already borrowed)"
+        );
+        assert_eq!((b.generation(), c.generation()), (2, 2));
+    }
+
+    #[cfg(all(feature = "unsync", feature = "mutex"))]
+    #[test]
+    fn reset_all_iter() {
+        use std::rc::Rc;
+
+        let buffers: Vec<_> = (0..3)
+            .map(|_| mutex::Temp::new(vec![1u8], |v: &mut Vec<u8>| v.clear()))
+            .collect();
+        assert_eq!(reset_all(&buffers), Ok(()));
+        assert_eq!(reset_all(buffers.iter().skip(1)), Ok(()));
+        assert!(buffers.iter().all(|b| b.lock_read().unwrap().is_empty()));
+        assert_eq!(
+            buffers.iter().map(|b| b.generation()).collect::<Vec<_>>(),
+            [1, 2, 2]
+        );
+
+        let shared: Vec<_> = (0..3)
+            .map(|n| Rc::new(unsync::Temp::new(n, |n: &mut i32| *n = 0)))
+            .collect();
+        let _guard = shared[1].borrow_mut();
+        let errors = reset_all(shared.iter().rev()).unwrap_err();
+        assert_eq!(errors.failures(), [(1, Error::Borrow)]);
+        assert_eq!(*shared[2].borrow(), 0);
+        assert_eq!(reset_all(Vec::<&dyn TempLike>::new()), Ok(()));
+    }
+
+    #[cfg(feature = "slab")]
+    #[test]
+    fn temp_slab() {
+        use tempref::slab::TempSlab;
+
+        let mut slab = TempSlab::with_capacity(2, |v: &mut Vec<u32>| v.clear());
+        let keys: Vec<_> = (0..3).map(|n| slab.insert(vec![n])).collect();
+        assert_eq!(keys, [0, 1, 2]);
+        assert_eq!(slab.len(), 3);
+        {
+            let mut guard = slab.get_mut(1).unwrap();
+            guard.push(7);
+            assert_eq!((guard.key(), &**guard), (1, &[1, 7][..]));
+        }
+        assert_eq!(slab.get(1), Some(&vec![]));
+        assert_eq!(slab.get(0), Some(&vec![0]));
+        assert_eq!(slab.generation(), 1);
+
+        assert_eq!(slab.remove(2), Some(vec![]));
+        assert_eq!(slab.remove(0), Some(vec![]));
+        assert_eq!(slab.remove(0), None);
+        assert!(slab.get_mut(2).is_none() && !slab.contains(5));
+        assert_eq!((slab.len(), slab.generation()), (1, 3));
+        assert_eq!(slab.insert(vec![4]), 0);
+        assert_eq!(slab.insert(vec![5]), 2);
+        assert_eq!(slab.insert(vec![6]), 3);
+        assert_eq!(
+            slab.iter().map(|(k, v)| (k, v.len())).collect::<Vec<_>>(),
+            [(0, 1), (1, 0), (2, 1), (3, 1)]
+        );
+        assert_eq!(format!("{slab:?}"), "{0: [4], 1: [], 2: [5], 3: [6]}");
+    }
+
+    #[cfg(feature = "map")]
+    #[test]
+    fn temp_map() {
+        use tempref::map::TempMap;
+
+        let map = TempMap::new(|key: &&str| key.len(), |n: &mut usize| *n = 0);
+        assert!(map.get("abc").is_none());
+        {
+            let mut abc = map.lock("abc").unwrap();
+            assert_eq!(*abc, 3);
+            *abc = 10;
+            assert!(map.try_lock("abc").is_err());
+            let mut de = map.try_lock("de").unwrap();
+            assert_eq!(*de, 2);
+            *de = 20;
+        }
+        assert_eq!(*map.lock("abc").unwrap(), 0);
+        assert_eq!(map.len(), 2);
+        assert!(map.contains_key("de"));
+
+        map.entry("f").set(5).unwrap();
+        assert_eq!(map.reset_key("f"), Some(Ok(())));
+        assert_eq!(map.get("f").unwrap().read_cloned().unwrap(), 0);
+        assert_eq!(map.reset_key("g"), None);
+        assert_eq!(map.reset_all(), Ok(()));
+        assert_eq!(map.get("de").unwrap().generation(), 2);
+
+        let held = map.lock("abc").unwrap();
+        map.retain(|key, _| key.len() > 1);
+        assert_eq!(map.len(), 2);
+        assert!(map.remove("abc").is_some());
+        assert!(!map.contains_key("abc") && map.get("f").is_none());
+        drop(held);
+        assert_eq!(*map.lock("abc").unwrap(), 3);
+    }
+
+    #[cfg(all(feature = "mutex", feature = "rwlock"))]
+    #[test]
+    fn scoped_threads() {
+        let sum = mutex::Temp::new(0, |n: &mut i32| *n = 0);
+        let total = sum.scope(false, |s, shared| {
+            for n in 1..=4 {
+                s.spawn(move || {
+                    let mut tx = shared.transaction().unwrap();
+                    *tx += n;
+                    tx.commit();
+                });
+            }
+            "done"
+        });
+        assert_eq!(total, "done");
+        assert_eq!((*sum.lock_read().unwrap(), sum.generation()), (10, 0));
+
+        let log = rwlock::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        log.scope(true, |s, shared| {
+            for n in 0..3 {
+                s.spawn(move || {
+                    let mut tx = shared.transaction().unwrap();
+                    tx.push(n);
+                    tx.commit();
+                });
+            }
+        });
+        assert_eq!(log.generation(), 1);
+        assert!(log.read().unwrap().is_empty());
+    }
+
+    #[test]
+    fn seqlock_invalid_bit_patterns() {
+        // Torn copies of a `char` or a `bool` may be invalid, and must be discarded before they are taken as values.
+        let state = seqlock::Temp::new(('a', false), |s| s.1 = false);
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                for n in 0..1000u32 {
+                    let mut guard = state.write();
+                    guard.0 = char::from_u32(0x1F600 + n % 64).unwrap();
+                    guard.1 = true;
+                }
+            });
+            s.spawn(|| {
+                for _ in 0..1000 {
+                    if let Ok((c, flag)) = state.try_read() {
+                        assert!(c == 'a' || ('\u{1F600}'..'\u{1F640}').contains(&c));
+                        assert!(!flag);
+                    }
+                }
+            });
+        });
+        assert_eq!(state.generation(), 1000);
+    }
+
+    #[test]
+    #[cfg(feature = "upgradable")]
+    fn rwlock_downgrade() {
+        let workspace = rwlock::Temp::new(vec![0; 4], |b: &mut Vec<i32>| b.fill(0));
+        {
+            let mut guard = workspace.write().unwrap();
+            guard.fill(3);
+            let published = rwlock::TempRef::downgrade(guard);
+            assert_eq!(*workspace.read().unwrap(), [3; 4]);
+            assert!(workspace.try_write().is_err());
+            let kept = published.dismiss();
+            assert_eq!(*kept, [3; 4]);
+            assert!(workspace.try_write().is_err());
+        }
+        assert_eq!(*workspace.read().unwrap(), [3; 4]);
+        assert_eq!(workspace.generation(), 0);
+        drop(workspace.try_write().unwrap());
+
+        // Dropping the downgraded guard resets the value, once.
+        {
+            let mut guard = workspace.write().unwrap();
+            guard.fill(1);
+            let published = rwlock::TempRef::downgrade(guard);
+            assert_eq!(*published, [1; 4]);
+            assert!(workspace.try_upgradable_read().is_err());
+        }
+        assert_eq!(*workspace.read().unwrap(), [0; 4]);
+        assert_eq!(workspace.generation(), 2);
+
+        // A writer waiting meanwhile only gets in after the reset, so its changes are kept.
+        std::thread::scope(|s| {
+            let mut guard = workspace.write().unwrap();
+            guard.fill(2);
+            let published = rwlock::TempRef::downgrade(guard);
+            let writer = s.spawn(|| {
+                let mut tx = workspace.transaction().unwrap();
+                let seen = tx.clone();
+                tx.fill(5);
+                tx.commit();
+                seen
+            });
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            assert_eq!(*workspace.read().unwrap(), [2; 4]);
+            drop(published);
+            assert_eq!(writer.join().unwrap(), [0; 4]);
+        });
+        assert_eq!(*workspace.read().unwrap(), [5; 4]);
+        assert_eq!(workspace.generation(), 3);
+    }
+
+    #[test]
+    #[cfg(feature = "upgradable")]
+    fn rwlock_upgradable_read() {
+        let workspace = rwlock::Temp::new(vec![0; 4], |b: &mut Vec<i32>| b.fill(0));
+        let upgradable = workspace.upgradable_read().unwrap();
+        assert!(workspace.try_upgradable_read().is_err());
+        assert!(workspace.try_write().is_err());
+        let reader = workspace.read().unwrap();
+        assert_eq!(*upgradable, *reader);
+        drop(reader);
+        {
+            let mut guard = rwlock::UpgradableRead::upgrade(upgradable).unwrap();
+            guard.fill(4);
+            assert!(workspace.try_upgradable_read().is_err());
+        }
+        assert_eq!(*workspace.read().unwrap(), [0; 4]);
+        assert_eq!(workspace.generation(), 1);
+        drop(workspace.try_upgradable_read().unwrap());
+    }
+
+    #[test]
+    fn reentrant_reset_poison() {
+        let log = reentrant::Temp::new(0, |n| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let outer = log.lock();
+            *log.lock().borrow_mut() = -1;
+            drop(outer);
+        }));
+        assert!(result.is_err());
+        assert!(log.is_reset_poisoned());
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let guard = log.try_lock().unwrap();
+                assert_eq!(guard.replace(1), -1);
+            });
+        });
+        assert_eq!(*log.lock().borrow(), 0);
+        log.clear_reset_poison();
+        assert!(!log.is_reset_poisoned());
+    }
+
+    #[test]
+    fn stateless_reset() {
+        let a = mutex::Temp::new(0u32, |n| *n = 0);
+        let b = rwlock::Temp::new(0u32, |n| *n = 0);
+        std::thread::scope(|s| {
+            for _ in 0..4 {
+                s.spawn(|| {
+                    for n in 1..100 {
+                        *a.lock().unwrap() = n;
+                        *b.write().unwrap() = n;
+                        a.set_with(n).unwrap();
+                    }
+                });
+            }
+        });
+        assert_eq!((a.generation(), b.generation()), (396, 396));
+        assert_eq!((*a.lock().unwrap(), *b.read().unwrap()), (0, 0));
+    }
+
+    #[test]
+    fn boxed_rwlock_sync_reset() {
+        // The reset function is locked with the value, so a `Sync` one makes a `Sync` `BoxedTemp`.
+        let resets = std::sync::atomic::AtomicI32::new(0);
+        let workspace = rwlock::BoxedTemp::new_boxed(0, move |n| {
+            *n = resets.fetch_add(1, std::sync::atomic::Ordering::Relaxed) + 1;
+        });
+        std::thread::scope(|s| {
+            s.spawn(|| drop(workspace.write().unwrap()));
+        });
+        assert_eq!(*workspace.read().unwrap(), 1);
+    }
+
+    #[test]
+    fn seqlock_reset_poison() {
+        let state = seqlock::Temp::new(0i32, |n| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            *state.write() = -1;
+        }));
+        assert!(result.is_err());
+        assert!(state.is_reset_poisoned());
+        // The write was still published.
+        assert_eq!(state.try_read(), Ok(-1));
+        *state.write() = 1;
+        assert_eq!(state.read(), 0);
+        state.clear_reset_poison();
+        assert!(!state.is_reset_poisoned());
+    }
 }
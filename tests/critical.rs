@@ -0,0 +1,40 @@
+#![cfg(feature = "critical-section")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn critical() {
+        static COUNTS: critical::Temp<[u32; 4]> = critical::Temp::new([0; 4], |c| c.fill(0));
+        critical_section::with(|cs| {
+            let mut guard = COUNTS.borrow_mut(cs);
+            guard[1] = 5;
+            assert_eq!(COUNTS.try_borrow_mut(cs).unwrap_err(), Error::Borrow);
+            assert_eq!(COUNTS.read_cloned(), Err(Error::Borrow));
+        });
+        assert_eq!(COUNTS.with_mut(|c| c.iter().sum::<u32>()), 0);
+        assert_eq!(COUNTS.generation(), 2);
+        assert_eq!(COUNTS.read_cloned(), Ok([0; 4]));
+    }
+
+    #[test]
+    fn critical_reset_poison() {
+        static STATE: critical::Temp<i32> = critical::Temp::new(0, |n| {
+            if *n < 0 {
+                panic!("reset panics");
+            }
+            *n = 0;
+        });
+        let result = std::panic::catch_unwind(|| STATE.with_mut(|n| *n = -1));
+        assert!(result.is_err());
+        assert!(STATE.is_reset_poisoned());
+        // The borrow flag was cleared, so the value can be borrowed again.
+        assert_eq!(STATE.read_cloned(), Ok(-1));
+        STATE.with_mut(|n| *n = 1);
+        assert_eq!(STATE.read_cloned(), Ok(0));
+        STATE.clear_reset_poison();
+        assert!(!STATE.is_reset_poisoned());
+    }
+}
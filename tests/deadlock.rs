@@ -0,0 +1,93 @@
+#![cfg(feature = "debug-deadlock")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[should_panic(expected = "lock order inversion")]
+    fn deadlock_inversion() {
+        let a = mutex::Temp::new(0u8, |n| *n = 0);
+        let b = rwlock::Temp::new(0u16, |n| *n = 0);
+        {
+            let _a = a.lock().unwrap();
+            let _b = b.write().unwrap();
+        }
+        // The same order again is fine, even across threads.
+        std::thread::scope(|s| {
+            s.spawn(|| {
+                let _a = a.lock().unwrap();
+                let _b = b.write().unwrap();
+            });
+        });
+        let _b = b.write().unwrap();
+        let _a = a.lock().unwrap();
+    }
+
+    #[test]
+    fn deadlock_ordered() {
+        let a = mutex::Temp::new(0u8, |n| *n = 0);
+        let b = mutex::Temp::new(0u8, |n| *n = 0);
+        for _ in 0..2 {
+            let _a = a.lock().unwrap();
+            let _b = b.try_lock().unwrap();
+        }
+        let _a = a.try_lock().unwrap();
+        let _b = b.lock().unwrap();
+    }
+
+    #[test]
+    fn deadlock_dropped_lock() {
+        let a = mutex::Temp::new(0u8, |n| *n = 0);
+        let b = mutex::Temp::new(0u16, |n| *n = 0);
+        {
+            let x = mutex::Temp::new(0u32, |n| *n = 0);
+            let guard = a.lock().unwrap();
+            let _x = x.lock().unwrap();
+            drop(guard);
+            drop(b.lock().unwrap());
+        }
+        // `a` was only ordered before `b` through `x`, which left the graph when it was dropped.
+        let _b = b.lock().unwrap();
+        let _a = a.lock().unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "upgradable")]
+    fn deadlock_dismissed_downgrade() {
+        let a = rwlock::Temp::new(0u8, |n| *n = 0);
+        let b = rwlock::Temp::new(0u16, |n| *n = 0);
+        drop(rwlock::TempRef::downgrade(a.write().unwrap()).dismiss());
+        drop(b.write().unwrap());
+        // `a` was released by `dismiss`, so this is not an inversion.
+        let _b = b.write().unwrap();
+        let _a = a.write().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "lock order inversion")]
+    fn deadlock_inversion_without_guard() {
+        let a = mutex::Temp::new(0u8, |n| *n = 0);
+        let b = mutex::Temp::new(0u16, |n| *n = 0);
+        {
+            let _a = a.lock().unwrap();
+            b.set(1).unwrap();
+        }
+        let _b = b.lock().unwrap();
+        a.reset().unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "lock order inversion")]
+    fn deadlock_inversion_read() {
+        let a = rwlock::Temp::new(0u8, |n| *n = 0);
+        let b = rwlock::Temp::new(0u16, |n| *n = 0);
+        {
+            let _a = a.write().unwrap();
+            drop(b.read().unwrap());
+        }
+        let _b = b.write().unwrap();
+        drop(a.read().unwrap());
+    }
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "debug_borrow")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn location() {
+        let workspace = unsync::Temp::new(0, |n| *n = 0);
+        let _guard = workspace.borrow_mut();
+        let line = line!() - 1;
+        let err = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            workspace.borrow_mut();
+        }))
+        .unwrap_err();
+        let message = err.downcast_ref::<String>().unwrap();
+        assert!(
+            message.contains(&format!("tests/debug_borrow.rs:{line}:")),
+            "{message}"
+        );
+    }
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "derive")]
+use tempref::*;
+
+#[derive(Default, TempProject)]
+struct Scratch {
+    #[temp(reset = Vec::clear)]
+    ids: Vec<u32>,
+    #[temp(reset = |n: &mut u64| *n = 1)]
+    seed: u64,
+    label: String,
+    #[temp(skip)]
+    #[allow(dead_code)]
+    shared: u8,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn derive_temp_project() {
+        let workspace = rwlock::Temp::new(Scratch::default(), |s| *s = Scratch::default());
+        workspace
+            .project_fields(|fields| {
+                std::thread::scope(|s| {
+                    s.spawn(|| fields.ids().extend([1, 2]));
+                    s.spawn(|| *fields.seed() = 9);
+                    s.spawn(|| fields.label().push('x'));
+                });
+                assert!(fields.ids.read().unwrap().is_empty());
+                assert_eq!(*fields.seed.read().unwrap(), 1);
+                assert_eq!(fields.label.generation(), 1);
+            })
+            .unwrap();
+        assert_eq!(workspace.generation(), 0);
+        assert_eq!(workspace.read().unwrap().seed, 1);
+    }
+}
@@ -0,0 +1,50 @@
+#![cfg(feature = "ffi")]
+use std::sync::Arc;
+use tempref::ffi::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ffi_lock_unlock_reset() {
+        unsafe {
+            let temp = tempref_new(8);
+            let guard = tempref_lock(temp);
+            assert_eq!(tempref_guard_len(guard), 8);
+            std::slice::from_raw_parts_mut(tempref_guard_data(guard), 8)
+                .copy_from_slice(b"scratch!");
+            assert!(tempref_try_lock(temp).is_null());
+            tempref_unlock_reset(guard);
+            assert_eq!(tempref_generation(temp), 1);
+
+            let guard = tempref_try_lock(temp);
+            assert!(!guard.is_null());
+            assert_eq!(
+                std::slice::from_raw_parts(tempref_guard_data(guard), 8),
+                [0; 8]
+            );
+            tempref_unlock_reset(guard);
+            tempref_release(temp);
+        }
+    }
+
+    #[test]
+    fn ffi_shared_with_rust() {
+        let workspace = Arc::new(tempref::mutex::Temp::zeroing(vec![0u8; 4]));
+        let raw = into_raw(Arc::clone(&workspace));
+        unsafe {
+            assert_eq!(tempref_retain(raw), raw);
+            tempref_release(raw);
+            let guard = tempref_lock(raw);
+            *tempref_guard_data(guard) = 1;
+            // The guard keeps the buffer alive after its reference is given back.
+            drop(from_raw(raw));
+            assert_eq!(Arc::strong_count(&workspace), 2);
+            tempref_unlock_reset(guard);
+        }
+        assert_eq!(Arc::strong_count(&workspace), 1);
+        assert_eq!(workspace.generation(), 1);
+        assert_eq!(*workspace.lock().unwrap(), [0; 4]);
+    }
+}
@@ -0,0 +1,26 @@
+#![cfg(feature = "heapless")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heapless() {
+        let stack = unsync::Temp::clearing(heapless::Vec::<u8, 8>::new());
+        stack.borrow_mut().extend_from_slice(&[1, 2, 3]).unwrap();
+        assert!(stack.borrow().is_empty());
+
+        let line = unsync::Temp::clearing(heapless::String::<16>::new());
+        {
+            let mut guard = line.borrow_mut();
+            guard.push_str("AT+OK").unwrap();
+            assert_eq!(guard.as_str(), "AT+OK");
+        }
+        assert!(line.borrow().is_empty());
+
+        let map = unsync::Temp::clearing(heapless::index_map::FnvIndexMap::<u8, u8, 4>::new());
+        map.borrow_mut().insert(1, 2).unwrap();
+        assert!(map.borrow().is_empty());
+    }
+}
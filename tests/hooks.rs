@@ -0,0 +1,50 @@
+#![cfg(feature = "hooks")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    static EVENTS: Mutex<Vec<(&str, i32)>> = Mutex::new(Vec::new());
+
+    struct Recorded;
+    impl TempHooks<i32> for Recorded {
+        fn on_acquire(value: &i32) {
+            EVENTS.lock().unwrap().push(("acquire", *value));
+        }
+        fn on_release(value: &i32) {
+            EVENTS.lock().unwrap().push(("release", *value));
+        }
+        fn on_reset(value: &i32) {
+            EVENTS.lock().unwrap().push(("reset", *value));
+        }
+    }
+
+    #[test]
+    fn hooks() {
+        let unsync = unsync::Temp::new(0, |v: &mut i32| *v = 0).with_hooks::<Recorded>();
+        *unsync.borrow_mut() = 1;
+        let mutex = mutex::Temp::new(0, |v: &mut i32| *v = 0).with_hooks::<Recorded>();
+        mutex.lock().unwrap().reset();
+        let rwlock = rwlock::Temp::new(0, |v: &mut i32| *v = 0).with_hooks::<Recorded>();
+        let mut transaction = rwlock.transaction().unwrap();
+        *transaction = 2;
+        transaction.commit();
+
+        assert_eq!(
+            *EVENTS.lock().unwrap(),
+            [
+                ("acquire", 0),
+                ("release", 1),
+                ("reset", 0),
+                ("acquire", 0),
+                ("reset", 0),
+                ("release", 0),
+                ("reset", 0),
+                ("acquire", 0),
+                ("release", 2),
+            ]
+        );
+    }
+}
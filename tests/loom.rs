@@ -0,0 +1,47 @@
+#![cfg(all(loom, feature = "mutex", feature = "rwlock"))]
+use std::sync::Arc;
+
+use loom::thread;
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loom_mutex() {
+        loom::model(|| {
+            let temp = Arc::new(mutex::Temp::new(Vec::new(), |v: &mut Vec<u8>| v.clear()));
+            let handles: Vec<_> = (0..2)
+                .map(|n| {
+                    let temp = temp.clone();
+                    thread::spawn(move || {
+                        let mut guard = temp.lock().unwrap();
+                        assert!(guard.is_empty());
+                        guard.push(n);
+                    })
+                })
+                .collect();
+            for handle in handles {
+                handle.join().unwrap();
+            }
+            assert_eq!(temp.generation(), 2);
+            assert!(temp.lock().unwrap().is_empty());
+        });
+    }
+
+    #[test]
+    fn loom_rwlock() {
+        loom::model(|| {
+            let temp = Arc::new(rwlock::Temp::new(0, |n: &mut i32| *n = 0));
+            let writer = {
+                let temp = temp.clone();
+                thread::spawn(move || *temp.write().unwrap() = 1)
+            };
+            // A reader never observes the value of an unfinished write.
+            assert_eq!(*temp.read().unwrap(), 0);
+            writer.join().unwrap();
+            assert_eq!(temp.generation(), 1);
+        });
+    }
+}
@@ -0,0 +1,27 @@
+#![cfg(all(unix, feature = "mmap"))]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn temp_buffer() {
+        let buffer = mmap::TempBuffer::new(3 * 4096 + 5).unwrap();
+        assert_eq!(buffer.len(), 3 * 4096 + 5);
+        {
+            let mut guard = buffer.borrow_mut();
+            guard.fill(0xff);
+            assert!(buffer.try_borrow_mut().is_none());
+            guard.reset();
+            assert!(guard.iter().all(|&b| b == 0));
+            guard[4096 * 3 + 4] = 1;
+        }
+        assert!(buffer.borrow().iter().all(|&b| b == 0));
+        assert_eq!(buffer.generation(), 2);
+
+        let empty = mmap::TempBuffer::new(0).unwrap();
+        empty.borrow_mut().reset();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "rayon")]
+
+use tempref::per_thread::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rayon::prelude::*;
+
+    #[test]
+    fn get() {
+        let scratch = TempPerThread::new(Vec::new, |v: &mut Vec<usize>| v.clear());
+        (0..64).into_par_iter().for_each(|n| {
+            let mut buffer = scratch.get();
+            assert!(buffer.is_empty());
+            buffer.push(n);
+        });
+        let mut outside = scratch.get();
+        outside.push(0);
+        drop(outside);
+        assert!(
+            scratch
+                .as_slice()
+                .iter()
+                .all(|temp| temp.lock().unwrap().is_empty())
+        );
+    }
+}
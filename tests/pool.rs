@@ -0,0 +1,29 @@
+use tempref::pool::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checkout() {
+        let pool = TempPool::new([0, 0], |n| *n = 0);
+        assert_eq!(pool.len(), 2);
+
+        let mut first = pool.checkout().unwrap();
+        let mut second = pool.try_checkout().unwrap();
+        *first = 1;
+        *second = 2;
+        assert!(pool.try_checkout().is_err());
+
+        drop(first);
+        let third = pool.try_checkout().unwrap();
+        assert_eq!(*third, 0);
+        drop(third);
+        drop(second);
+        assert!(
+            pool.as_slice()
+                .iter()
+                .all(|temp| *temp.lock().unwrap() == 0)
+        );
+    }
+}
@@ -0,0 +1,20 @@
+#![cfg(feature = "proptest")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn arbitrary(unsync in any::<unsync::TempFn<Vec<u8>>>(), mutex in any::<mutex::TempFn<String>>(), rwlock in any::<rwlock::TempFn<u64>>()) {
+            unsync.borrow_mut().push(1);
+            prop_assert!(unsync.borrow().is_empty());
+            mutex.lock().unwrap().push('a');
+            prop_assert!(mutex.lock().unwrap().is_empty());
+            *rwlock.write().unwrap() += 1;
+            prop_assert_eq!(*rwlock.read().unwrap(), 0);
+        }
+    }
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "rcu")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rcu() {
+        let config = rcu::Temp::new(vec![0u8; 4], |v| v.fill(0));
+        let snapshot = config.load();
+        {
+            let mut guard = config.write();
+            guard.push(1);
+            assert!(config.try_write().is_err());
+        }
+        assert_eq!(*config.load(), vec![0; 5]);
+        assert_eq!(*snapshot, vec![0; 4]);
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let mut guard = config.write();
+            guard.clear();
+            panic!("discards the clone");
+        }));
+        assert!(result.is_err());
+        assert_eq!(config.into_inner().len(), 5);
+    }
+}
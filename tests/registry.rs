@@ -0,0 +1,40 @@
+#![cfg(feature = "registry")]
+use std::sync::Arc;
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_lookup_reset() {
+        let buf: Arc<mutex::TempFn<Vec<u8>>> = Arc::new(mutex::Temp::new(vec![1u8], |v| v.clear()));
+        let count = Arc::new(rwlock::Temp::new(7u32, |n: &mut u32| *n = 0));
+        registry::register("registry_test.buf", buf.clone()).unwrap();
+        registry::register("registry_test.count", count.clone()).unwrap();
+        assert!(registry::register("registry_test.buf", count.clone()).is_err());
+        assert!(registry::contains("registry_test.count"));
+        let names = registry::names();
+        let position = |name: &str| names.iter().position(|n| n == name).unwrap();
+        assert!(position("registry_test.buf") < position("registry_test.count"));
+
+        let found = registry::get::<mutex::TempFn<Vec<u8>>>("registry_test.buf").unwrap();
+        assert!(Arc::ptr_eq(&found, &buf));
+        assert!(registry::get::<mutex::TempFn<u32>>("registry_test.buf").is_none());
+        assert!(registry::get::<mutex::TempFn<Vec<u8>>>("registry_test.none").is_none());
+
+        assert_eq!(registry::reset("registry_test.none"), None);
+        assert_eq!(registry::reset("registry_test.count"), Some(Ok(())));
+        assert_eq!((*count.read().unwrap(), count.generation()), (0, 1));
+        buf.set(vec![1, 2]).unwrap();
+        assert_eq!(registry::reset_all(), Ok(()));
+        assert_eq!(buf.generation(), 1);
+        assert!(buf.lock_read().unwrap().is_empty());
+
+        assert!(registry::unregister("registry_test.buf"));
+        assert!(!registry::unregister("registry_test.buf"));
+        assert!(registry::get::<mutex::TempFn<Vec<u8>>>("registry_test.buf").is_none());
+        assert!(registry::unregister("registry_test.count"));
+        assert_eq!(Arc::strong_count(&buf), 2);
+    }
+}
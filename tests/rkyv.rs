@@ -0,0 +1,32 @@
+#![cfg(feature = "rkyv")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rkyv::rancor;
+
+    #[test]
+    fn archive() {
+        let workspace = mutex::Temp::new(vec![1u32, 2, 3], |v: &mut Vec<u32>| v.clear());
+        let bytes = rkyv::to_bytes::<rancor::Error>(&workspace.lock_read().unwrap()).unwrap();
+        let archived = rkyv::access::<rkyv::Archived<Vec<u32>>, rancor::Error>(&bytes).unwrap();
+        assert_eq!(archived.as_slice(), [1, 2, 3]);
+
+        let value = rkyv::deserialize::<Vec<u32>, rancor::Error>(archived).unwrap();
+        let workspace = rwlock::Temp::from(value);
+        let bytes = rkyv::to_bytes::<rancor::Error>(&workspace.read().unwrap()).unwrap();
+        assert_eq!(
+            bytes.as_slice(),
+            rkyv::to_bytes::<rancor::Error>(&vec![1u32, 2, 3])
+                .unwrap()
+                .as_slice()
+        );
+
+        // Archiving through a `TempRef` resets the value once the guard is dropped, like any other use of it.
+        let workspace = unsync::Temp::new(1u32, |n| *n = 0);
+        let bytes = rkyv::to_bytes::<rancor::Error>(&workspace.borrow_mut()).unwrap();
+        assert_eq!(rkyv::from_bytes::<u32, rancor::Error>(&bytes).unwrap(), 1);
+        assert_eq!(*workspace.borrow(), 0);
+    }
+}
@@ -0,0 +1,56 @@
+#![cfg(feature = "crossbeam")]
+use crossbeam::channel;
+use tempref::mutex::Temp;
+use tempref::pool::TempPool;
+use tempref::scoped::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scope_shares_temp() {
+        let sum = Temp::new(0, |n: &mut u32| *n = 0);
+        let result = scope(&sum, false, |s, shared| {
+            for n in 1..=3 {
+                s.spawn(move |_| {
+                    let mut tx = shared.transaction().unwrap();
+                    *tx += n;
+                    tx.commit();
+                });
+            }
+            7
+        });
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(*sum.lock_read().unwrap(), 6);
+
+        let result = scope(&sum, true, |s, _| {
+            s.spawn(|_| panic!("worker"));
+        });
+        assert!(result.is_err());
+        assert_eq!((*sum.lock_read().unwrap(), sum.generation()), (0, 1));
+    }
+
+    #[test]
+    fn lend_through_channel() {
+        let pool = TempPool::new([vec![0u8; 0], vec![]], |v| v.clear());
+        let (tx, rx) = channel::unbounded();
+        crossbeam::scope(|s| {
+            s.spawn(|_| {
+                let mut buf = pool.checkout().unwrap();
+                buf.extend_from_slice(b"abc");
+                tx.send(lend(buf)).unwrap();
+            });
+        })
+        .unwrap();
+        let mut lent = rx.recv().unwrap();
+        assert_eq!(*lent, b"abc");
+        lent.push(b'd');
+        let slot = lent.temp();
+        assert!(slot.lock_read().unwrap().is_empty());
+        let generation = slot.generation();
+        drop(lent);
+        assert_eq!(slot.generation(), generation + 1);
+        assert!(slot.lock_read().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,39 @@
+#![cfg(feature = "serde")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Serialize, serde::Deserialize)]
+    struct State {
+        names: unsync::TempFn<Vec<String>>,
+        counts: mutex::TempFn<Vec<u32>>,
+    }
+
+    #[test]
+    fn round_trip() {
+        let state = State {
+            names: unsync::Temp::from(vec!["a".to_string()]),
+            counts: mutex::Temp::from(vec![1, 2]),
+        };
+        let json = serde_json::to_string(&state).unwrap();
+        assert_eq!(json, r#"{"names":["a"],"counts":[1,2]}"#);
+
+        let state: State = serde_json::from_str(&json).unwrap();
+        assert_eq!(state.counts, vec![1, 2]);
+        state.counts.lock().unwrap().push(3);
+        assert_eq!(state.counts, vec![]);
+
+        let mut deserializer = serde_json::Deserializer::from_str("[1,2]");
+        let workspace =
+            rwlock::Temp::deserialize_with(&mut deserializer, |v: &mut Vec<u32>| v.fill(0))
+                .unwrap();
+        drop(workspace.write().unwrap());
+        assert_eq!(workspace, vec![0, 0]);
+
+        let workspace = unsync::Temp::new(1, |n| *n = 0);
+        let _guard = workspace.borrow_mut();
+        assert!(serde_json::to_string(&workspace).is_err());
+    }
+}
@@ -0,0 +1,41 @@
+#![cfg(feature = "stats")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn stats() {
+        let temp = mutex::Temp::new(0, |v: &mut i32| *v = 0);
+        assert_eq!(temp.stats(), Stats::default());
+        {
+            let mut guard = temp.lock().unwrap();
+            guard.reset();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+        *temp.transaction().unwrap() = 1;
+        let stats = temp.stats();
+        assert_eq!((stats.borrows, stats.resets, stats.contended), (2, 3, 0));
+        assert!(stats.max_hold >= Duration::from_millis(10));
+        assert!(stats.total_hold >= stats.max_hold);
+
+        let guard = temp.lock().unwrap();
+        std::thread::scope(|s| {
+            let waiter = s.spawn(|| drop(temp.lock()));
+            while temp.stats().contended == 0 {
+                std::thread::yield_now();
+            }
+            drop(guard);
+            waiter.join().unwrap();
+        });
+        assert_eq!(temp.stats().contended, 1);
+        assert_eq!(temp.stats().borrows, 4);
+
+        let unsync = unsync::Temp::new(0, |v: &mut i32| *v = 0);
+        unsync.borrow_mut();
+        unsync.reset();
+        assert_eq!((unsync.stats().borrows, unsync.stats().resets), (1, 2));
+    }
+}
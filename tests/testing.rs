@@ -0,0 +1,43 @@
+#![cfg(feature = "testing")]
+use tempref::testing::*;
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn append<W: TempAccess<Value = Vec<u8>>>(workspace: &W, data: &[u8]) -> Result<usize, Error> {
+        let mut buf = workspace.access()?;
+        buf.extend_from_slice(data);
+        Ok(buf.len())
+    }
+
+    #[test]
+    fn mock_temp() {
+        let mock = MockTemp::new(Vec::new(), |v: &mut Vec<u8>| v.clear());
+        assert_eq!(append(&mock, b"ab"), Ok(2));
+        assert_eq!(append(&mock, b"ab"), Ok(2));
+        assert_eq!((mock.borrows(), mock.resets()), (2, 2));
+
+        mock.clear_events();
+        mock.fail_next(Error::Poisoned);
+        mock.fail_next(Error::WouldBlock);
+        assert_eq!(append(&mock, b"ab"), Err(Error::Poisoned));
+        assert_eq!(mock.reset(), Err(Error::WouldBlock));
+        {
+            let _guard = mock.try_access().unwrap();
+            assert_eq!(mock.try_access().unwrap_err(), Error::WouldBlock);
+        }
+        assert_eq!(
+            mock.events(),
+            [
+                MockEvent::Fail(Error::Poisoned),
+                MockEvent::Fail(Error::WouldBlock),
+                MockEvent::Borrow,
+                MockEvent::Fail(Error::WouldBlock),
+                MockEvent::Reset,
+            ]
+        );
+        assert!(mock.into_inner().is_empty());
+    }
+}
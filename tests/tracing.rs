@@ -0,0 +1,88 @@
+#![cfg(feature = "tracing")]
+use tempref::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Debug;
+    use std::sync::{Arc, Mutex};
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    // Records the message of every event and the name of every span, with the label.
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+    struct Fields(String, String);
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            match field.name() {
+                "message" => self.0 = format!("{value:?}"),
+                "label" => self.1 = format!("{value:?}"),
+                _ => {}
+            }
+        }
+    }
+    impl Subscriber for Recorder {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let mut fields = Fields(span.metadata().name().to_string(), String::new());
+            span.record(&mut fields);
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", fields.0, fields.1));
+            Id::from_u64(1)
+        }
+        fn record(&self, _: &Id, _: &Record<'_>) {}
+        fn record_follows_from(&self, _: &Id, _: &Id) {}
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(String::new(), String::new());
+            event.record(&mut fields);
+            self.0
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", fields.0, fields.1));
+        }
+        fn enter(&self, _: &Id) {}
+        fn exit(&self, _: &Id) {}
+    }
+
+    #[test]
+    fn tracing() {
+        let recorder = Recorder::default();
+        let mutex = mutex::Temp::new(0, |v: &mut i32| *v = 0).with_label("mutex");
+        let (locked, wait) = std::sync::mpsc::channel();
+        std::thread::scope(|s| {
+            // Another recorder, so that the callsites hit by this thread are enabled.
+            s.spawn(|| {
+                tracing::subscriber::with_default(Recorder::default(), || {
+                    let _guard = mutex.lock().unwrap();
+                    locked.send(()).unwrap();
+                    std::thread::sleep(std::time::Duration::from_millis(20));
+                })
+            });
+            wait.recv().unwrap();
+            tracing::subscriber::with_default(recorder.clone(), || {
+                *mutex.lock().unwrap() = 1;
+                let unsync = unsync::Temp::new(0, |v: &mut i32| *v = 0).with_label("unsync");
+                unsync.borrow_mut().reset();
+            });
+        });
+        assert_eq!(
+            *recorder.0.lock().unwrap(),
+            [
+                "contended \"mutex\"",
+                "acquired \"mutex\"",
+                "released \"mutex\"",
+                "reset \"mutex\"",
+                "acquired \"unsync\"",
+                "reset \"unsync\"",
+                "released \"unsync\"",
+                "reset \"unsync\"",
+            ]
+        );
+    }
+}